@@ -0,0 +1,528 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing of the `#[metadata(..)]` helper attributes.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+
+use syn::{
+	parse::{Parser, Result},
+	punctuated::Punctuated,
+	Attribute, Error, Ident, Lit, LitStr, Meta, MetaNameValue, NestedMeta, Path, Type, WherePredicate,
+};
+
+use crate::utils::is_rust_identifier;
+
+/// The `#[metadata(..)]` attributes applied to a type.
+#[derive(Default)]
+pub struct TypeAttrs {
+	/// Overrides the name of the type.
+	pub rename: Option<String>,
+	/// Overrides the namespace of the type, given by its segments.
+	pub namespace: Option<Vec<String>>,
+	/// Forwards the type identifier and definition to the single field of a struct.
+	///
+	/// Implied by `#[repr(transparent)]` if the `repr-transparent` feature is enabled.
+	pub transparent: bool,
+	/// Replaces the automatically generated bounds on the type parameters.
+	///
+	/// An empty list suppresses all generated bounds.
+	pub bound: Option<Vec<WherePredicate>>,
+	/// Variant indices that are retired and must not be used by any variant.
+	pub reserved_indices: Vec<u64>,
+	/// Overrides the path to the `type_metadata` crate used by the generated code.
+	pub crate_path: Option<Path>,
+	/// Marks the type as deprecated with the given note.
+	///
+	/// Also honors the `#[deprecated]` attribute.
+	pub deprecated: Option<String>,
+	/// The fixed-width integer type of the discriminants given by `#[repr(..)]`, if any.
+	pub repr: Option<Ident>,
+}
+
+/// Parses the `#[metadata(..)]` attributes applied to a type.
+pub fn parse_type_attrs(attrs: &[Attribute]) -> Result<TypeAttrs> {
+	let mut type_attrs = TypeAttrs::default();
+	let mut remote = None;
+	for meta in metadata_metas(attrs)? {
+		match meta {
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("rename") => {
+				type_attrs.rename = Some(parse_rename(name_value)?)
+			}
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("namespace") => {
+				type_attrs.namespace = Some(parse_namespace(name_value)?)
+			}
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("remote") => {
+				remote = Some(parse_remote(name_value)?)
+			}
+			Meta::Path(ref path) if path.is_ident("transparent") => type_attrs.transparent = true,
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("deprecated") => {
+				type_attrs.deprecated = Some(parse_deprecated(name_value)?)
+			}
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("crate") => {
+				type_attrs.crate_path = Some(parse_crate_path(name_value)?)
+			}
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("bound") => {
+				type_attrs.bound = Some(parse_bound(name_value)?)
+			}
+			Meta::List(ref list) if list.path.is_ident("reserved_indices") => {
+				for nested in &list.nested {
+					match nested {
+						NestedMeta::Lit(Lit::Int(lit_int)) => {
+							type_attrs.reserved_indices.push(lit_int.base10_parse::<u64>()?)
+						}
+						_ => {
+							return Err(Error::new_spanned(
+								nested,
+								"expected an integer literal as reserved index",
+							))
+						}
+					}
+				}
+			}
+			_ => return Err(unknown_attr(&meta)),
+		}
+	}
+	if let Some((lit_str, path)) = remote {
+		// A remote type is identified by the name and namespace of its path.
+		if type_attrs.rename.is_some() || type_attrs.namespace.is_some() {
+			return Err(Error::new_spanned(
+				lit_str,
+				"`remote` cannot be combined with `rename` or `namespace`",
+			));
+		}
+		let mut segments = path
+			.segments
+			.iter()
+			.map(|segment| segment.ident.to_string())
+			.collect::<Vec<_>>();
+		type_attrs.rename = segments.pop();
+		if !segments.is_empty() {
+			type_attrs.namespace = Some(segments);
+		}
+	}
+	// Types with their own name or namespace are never implicitly transparent.
+	if cfg!(feature = "repr-transparent")
+		&& is_repr_transparent(attrs)
+		&& type_attrs.rename.is_none()
+		&& type_attrs.namespace.is_none()
+	{
+		type_attrs.transparent = true;
+	}
+	if type_attrs.deprecated.is_none() {
+		type_attrs.deprecated = parse_rust_deprecated(attrs);
+	}
+	type_attrs.repr = parse_repr_int(attrs);
+	Ok(type_attrs)
+}
+
+/// Returns `true` if there is a `#[repr(transparent)]` attribute.
+fn is_repr_transparent(attrs: &[Attribute]) -> bool {
+	repr_idents(attrs).any(|ident| ident == "transparent")
+}
+
+/// Returns the fixed-width integer type given by a `#[repr(..)]` attribute, if any.
+///
+/// `usize` and `isize` are not recorded since their width depends on the target.
+fn parse_repr_int(attrs: &[Attribute]) -> Option<Ident> {
+	const INTS: &[&str] = &["u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128"];
+	repr_idents(attrs).find(|ident| INTS.iter().any(|int| ident == int))
+}
+
+/// Returns the identifiers listed within the `#[repr(..)]` attributes, e.g. `C` or `u8`.
+fn repr_idents(attrs: &[Attribute]) -> impl Iterator<Item = Ident> + '_ {
+	attrs
+		.iter()
+		.filter(|attr| attr.path.is_ident("repr"))
+		.filter_map(|attr| match attr.parse_meta() {
+			Ok(Meta::List(list)) => Some(list),
+			_ => None,
+		})
+		.flat_map(|list| list.nested.into_iter())
+		.filter_map(|nested| match nested {
+			NestedMeta::Meta(Meta::Path(path)) => path.get_ident().cloned(),
+			_ => None,
+		})
+}
+
+/// The `#[metadata(..)]` attributes applied to a field.
+#[derive(Default)]
+pub struct FieldAttrs {
+	/// Uses the metadata of the given type for the field, e.g. of a remote type mirror.
+	pub with: Option<Type>,
+	/// Uses the `MetaType` returned by the given function for the field,
+	/// e.g. to describe a foreign type by the type it is encoded as.
+	pub meta_type_with: Option<Path>,
+	/// Overrides the name of the field.
+	pub rename: Option<String>,
+	/// Describes the field by an opaque type, see `MetaType::opaque`,
+	/// e.g. for field types that cannot implement `Metadata`.
+	pub opaque: bool,
+	/// Omit the field from the generated type definition.
+	///
+	/// Also honors the `#[codec(skip)]` attribute of `parity-scale-codec`.
+	pub skip: bool,
+	/// The field is encoded using the SCALE compact encoding.
+	///
+	/// Also honors the `#[codec(compact)]` attribute of `parity-scale-codec`.
+	pub compact: bool,
+	/// Marks the field as deprecated with the given note.
+	///
+	/// Also honors the `#[deprecated]` attribute.
+	pub deprecated: Option<String>,
+}
+
+/// Parses the `#[metadata(..)]` attributes applied to a field.
+pub fn parse_field_attrs(attrs: &[Attribute]) -> Result<FieldAttrs> {
+	let mut field_attrs = FieldAttrs::default();
+	for meta in metadata_metas(attrs)? {
+		match meta {
+			Meta::Path(ref path) if path.is_ident("skip") => field_attrs.skip = true,
+			Meta::Path(ref path) if path.is_ident("compact") => field_attrs.compact = true,
+			Meta::Path(ref path) if path.is_ident("opaque") => field_attrs.opaque = true,
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("deprecated") => {
+				field_attrs.deprecated = Some(parse_deprecated(name_value)?)
+			}
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("with") => {
+				field_attrs.with = Some(parse_with(name_value)?)
+			}
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("meta_type_with") => {
+				field_attrs.meta_type_with = Some(parse_meta_type_with(name_value)?)
+			}
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("rename") => {
+				field_attrs.rename = Some(parse_rename(name_value)?)
+			}
+			_ => return Err(unknown_attr(&meta)),
+		}
+	}
+	if field_attrs.with.is_some() && field_attrs.meta_type_with.is_some() {
+		return Err(Error::new_spanned(
+			&field_attrs.meta_type_with,
+			"`meta_type_with` cannot be combined with `with`",
+		));
+	}
+	if field_attrs.opaque && (field_attrs.with.is_some() || field_attrs.meta_type_with.is_some()) {
+		return Err(Error::new_spanned(
+			attrs.iter().find(|attr| attr.path.is_ident("metadata")),
+			"`opaque` cannot be combined with `with` or `meta_type_with`",
+		));
+	}
+	field_attrs.skip |= has_codec_flag(attrs, "skip");
+	field_attrs.compact |= has_codec_flag(attrs, "compact");
+	if field_attrs.deprecated.is_none() {
+		field_attrs.deprecated = parse_rust_deprecated(attrs);
+	}
+	Ok(field_attrs)
+}
+
+/// The `#[metadata(..)]` attributes applied to an enum variant.
+#[derive(Default)]
+pub struct VariantAttrs {
+	/// Overrides the name of the variant.
+	pub rename: Option<String>,
+	/// Omit the variant from the generated type definition.
+	///
+	/// Also honors the `#[codec(skip)]` attribute of `parity-scale-codec`.
+	pub skip: bool,
+	/// Sets the index of the variant.
+	///
+	/// Also honors the `#[codec(index = ..)]` attribute of `parity-scale-codec`.
+	/// Rejected for C-like enums whose variants are described by their discriminants.
+	pub index: Option<u64>,
+	/// Marks the variant as deprecated with the given note.
+	///
+	/// Also honors the `#[deprecated]` attribute.
+	pub deprecated: Option<String>,
+}
+
+/// Parses the `#[metadata(..)]` attributes applied to an enum variant.
+pub fn parse_variant_attrs(attrs: &[Attribute]) -> Result<VariantAttrs> {
+	let mut variant_attrs = VariantAttrs::default();
+	for meta in metadata_metas(attrs)? {
+		match meta {
+			Meta::Path(ref path) if path.is_ident("skip") => variant_attrs.skip = true,
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("rename") => {
+				variant_attrs.rename = Some(parse_rename(name_value)?)
+			}
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("index") => {
+				variant_attrs.index = Some(parse_index(name_value)?)
+			}
+			Meta::NameValue(ref name_value) if name_value.path.is_ident("deprecated") => {
+				variant_attrs.deprecated = Some(parse_deprecated(name_value)?)
+			}
+			_ => return Err(unknown_attr(&meta)),
+		}
+	}
+	if variant_attrs.index.is_none() {
+		variant_attrs.index = parse_codec_index(attrs)?;
+	}
+	variant_attrs.skip |= has_codec_flag(attrs, "skip");
+	if variant_attrs.deprecated.is_none() {
+		variant_attrs.deprecated = parse_rust_deprecated(attrs);
+	}
+	Ok(variant_attrs)
+}
+
+/// Parses the index of an `index = N` attribute.
+///
+/// The index may also be given as string literal as done by older versions of `parity-scale-codec`.
+/// Indices must fit into `u8` since they are encoded as a single byte.
+fn parse_index(name_value: &MetaNameValue) -> Result<u64> {
+	let index: u64 = match name_value.lit {
+		Lit::Int(ref lit_int) => lit_int.base10_parse()?,
+		Lit::Str(ref lit_str) => lit_str
+			.value()
+			.parse()
+			.map_err(|_| Error::new_spanned(lit_str, "expected an integer as index"))?,
+		_ => {
+			return Err(Error::new_spanned(
+				&name_value.lit,
+				"expected an integer literal as index",
+			))
+		}
+	};
+	if index > u64::from(u8::MAX) {
+		return Err(Error::new_spanned(
+			&name_value.lit,
+			format!(
+				"index {} does not fit into `u8` as encoded by `parity-scale-codec`",
+				index
+			),
+		));
+	}
+	Ok(index)
+}
+
+/// Returns the index of a `#[codec(index = ..)]` attribute, if any.
+///
+/// All other items of `#[codec(..)]` attributes are ignored.
+fn parse_codec_index(attrs: &[Attribute]) -> Result<Option<u64>> {
+	for attr in attrs.iter().filter(|attr| attr.path.is_ident("codec")) {
+		if let Ok(Meta::List(list)) = attr.parse_meta() {
+			for nested in &list.nested {
+				if let NestedMeta::Meta(Meta::NameValue(ref name_value)) = nested {
+					if name_value.path.is_ident("index") {
+						return parse_index(name_value).map(Some);
+					}
+				}
+			}
+		}
+	}
+	Ok(None)
+}
+
+/// Returns `true` if there is a `#[codec(..)]` attribute with the given flag, e.g. `compact`.
+///
+/// All other items of `#[codec(..)]` attributes are ignored.
+pub fn has_codec_flag(attrs: &[Attribute], flag: &str) -> bool {
+	attrs
+		.iter()
+		.filter(|attr| attr.path.is_ident("codec"))
+		.filter_map(|attr| match attr.parse_meta() {
+			Ok(Meta::List(list)) => Some(list),
+			_ => None,
+		})
+		.any(|list| {
+			list.nested.iter().any(|nested| match nested {
+				NestedMeta::Meta(Meta::Path(ref path)) => path.is_ident(flag),
+				_ => false,
+			})
+		})
+}
+
+/// Parses the segments of a `namespace = "..."` attribute.
+///
+/// Segments must be proper Rust identifiers just as required by `Namespace::new`.
+fn parse_namespace(name_value: &MetaNameValue) -> Result<Vec<String>> {
+	let lit_str = match name_value.lit {
+		Lit::Str(ref lit_str) => lit_str,
+		_ => {
+			return Err(Error::new_spanned(
+				&name_value.lit,
+				"expected a string literal as namespace",
+			))
+		}
+	};
+	let segments = lit_str.value().split("::").map(String::from).collect::<Vec<_>>();
+	if let Some(invalid) = segments.iter().find(|segment| !is_rust_identifier(segment)) {
+		return Err(Error::new_spanned(
+			lit_str,
+			format!("invalid namespace segment `{}`, expected a Rust identifier", invalid),
+		));
+	}
+	Ok(segments)
+}
+
+/// Parses the path of a `crate = "..."` attribute.
+fn parse_crate_path(name_value: &MetaNameValue) -> Result<Path> {
+	match name_value.lit {
+		Lit::Str(ref lit_str) => lit_str.parse(),
+		_ => Err(Error::new_spanned(
+			&name_value.lit,
+			"expected a string literal as crate path",
+		)),
+	}
+}
+
+/// Parses the path of a `remote = "..."` attribute.
+fn parse_remote(name_value: &MetaNameValue) -> Result<(LitStr, Path)> {
+	let lit_str = match name_value.lit {
+		Lit::Str(ref lit_str) => lit_str,
+		_ => {
+			return Err(Error::new_spanned(
+				&name_value.lit,
+				"expected a string literal as remote type path",
+			))
+		}
+	};
+	let path = lit_str.parse::<Path>()?;
+	if path.leading_colon.is_some() || path.segments.iter().any(|segment| !segment.arguments.is_empty()) {
+		return Err(Error::new_spanned(
+			lit_str,
+			"expected a remote type path without leading `::` and generic arguments",
+		));
+	}
+	Ok((lit_str.clone(), path))
+}
+
+/// Parses the type of a `with = "..."` attribute.
+fn parse_with(name_value: &MetaNameValue) -> Result<Type> {
+	match name_value.lit {
+		Lit::Str(ref lit_str) => lit_str.parse(),
+		_ => Err(Error::new_spanned(&name_value.lit, "expected a string literal as type")),
+	}
+}
+
+/// Parses the function path of a `meta_type_with = "..."` attribute.
+fn parse_meta_type_with(name_value: &MetaNameValue) -> Result<Path> {
+	match name_value.lit {
+		Lit::Str(ref lit_str) => lit_str.parse(),
+		_ => Err(Error::new_spanned(
+			&name_value.lit,
+			"expected a string literal as function path",
+		)),
+	}
+}
+
+/// Parses the where predicates of a `bound = "..."` attribute.
+fn parse_bound(name_value: &MetaNameValue) -> Result<Vec<WherePredicate>> {
+	match name_value.lit {
+		Lit::Str(ref lit_str) => {
+			let predicates = Punctuated::<WherePredicate, syn::token::Comma>::parse_terminated
+				.parse_str(&lit_str.value())
+				.map_err(|err| Error::new_spanned(lit_str, err))?;
+			Ok(predicates.into_iter().collect())
+		}
+		_ => Err(Error::new_spanned(
+			&name_value.lit,
+			"expected a string literal of where predicates",
+		)),
+	}
+}
+
+/// Parses the new name of a `rename = "..."` attribute.
+fn parse_rename(name_value: &MetaNameValue) -> Result<String> {
+	match name_value.lit {
+		Lit::Str(ref lit_str) if !lit_str.value().is_empty() => Ok(lit_str.value()),
+		_ => Err(Error::new_spanned(
+			&name_value.lit,
+			"expected a non-empty string literal as name",
+		)),
+	}
+}
+
+/// Parses the note of a `deprecated = "..."` attribute.
+fn parse_deprecated(name_value: &MetaNameValue) -> Result<String> {
+	match name_value.lit {
+		Lit::Str(ref lit_str) => Ok(lit_str.value()),
+		_ => Err(Error::new_spanned(
+			&name_value.lit,
+			"expected a string literal as deprecation note",
+		)),
+	}
+}
+
+/// Returns the note of a `#[deprecated]` attribute, if any.
+///
+/// A `#[deprecated]` attribute without a note yields an empty note.
+fn parse_rust_deprecated(attrs: &[Attribute]) -> Option<String> {
+	let attr = attrs.iter().find(|attr| attr.path.is_ident("deprecated"))?;
+	let note = match attr.parse_meta() {
+		Ok(Meta::NameValue(MetaNameValue {
+			lit: Lit::Str(lit_str), ..
+		})) => Some(lit_str.value()),
+		Ok(Meta::List(list)) => list.nested.iter().find_map(|nested| match nested {
+			NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+				path,
+				lit: Lit::Str(lit_str),
+				..
+			})) if path.is_ident("note") => Some(lit_str.value()),
+			_ => None,
+		}),
+		_ => None,
+	};
+	Some(note.unwrap_or_default())
+}
+
+/// Returns the lines of the doc comments, i.e. the `#[doc = "..."]` attributes.
+pub fn parse_docs(attrs: &[Attribute]) -> Vec<String> {
+	attrs
+		.iter()
+		.filter(|attr| attr.path.is_ident("doc"))
+		.filter_map(|attr| match attr.parse_meta() {
+			Ok(Meta::NameValue(MetaNameValue {
+				lit: Lit::Str(lit_str), ..
+			})) => {
+				let line = lit_str.value();
+				// Doc comments are separated from their `///` by a single space.
+				Some(if line.starts_with(' ') {
+					line.replacen(' ', "", 1)
+				} else {
+					line
+				})
+			}
+			_ => None,
+		})
+		.collect()
+}
+
+/// Returns all items listed within the `#[metadata(..)]` attributes.
+fn metadata_metas(attrs: &[Attribute]) -> Result<Vec<Meta>> {
+	let mut metas = Vec::new();
+	for attr in attrs.iter().filter(|attr| attr.path.is_ident("metadata")) {
+		match attr.parse_meta()? {
+			Meta::List(list) => {
+				for nested in list.nested {
+					match nested {
+						NestedMeta::Meta(meta) => metas.push(meta),
+						NestedMeta::Lit(lit) => return Err(Error::new_spanned(lit, "expected a metadata attribute")),
+					}
+				}
+			}
+			meta => return Err(Error::new_spanned(meta, "expected `#[metadata(..)]`")),
+		}
+	}
+	Ok(metas)
+}
+
+fn unknown_attr(meta: &Meta) -> Error {
+	Error::new_spanned(meta.path(), "unknown or misplaced metadata attribute")
+}
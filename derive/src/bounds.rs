@@ -0,0 +1,167 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::ToTokens;
+use syn::{parse_quote, Data, DeriveInput, Field, Generics, Ident, Type};
+
+use crate::{
+	attrs::{parse_field_attrs, parse_variant_attrs, TypeAttrs},
+	type_def::erase_lifetimes,
+};
+
+/// Adds the bounds required by the generated impls to the generics.
+///
+/// All type parameters are bounded by `'static`, the given type parameters and
+/// types are additionally bounded by `Metadata`.
+/// A `#[metadata(bound = "...")]` attribute replaces these bounds by the given predicates.
+pub fn add_bounds(generics: &mut Generics, type_attrs: &TypeAttrs, bounded_params: &[Ident], bounded_types: &[Type]) {
+	match type_attrs.bound {
+		Some(ref bound) => generics.make_where_clause().predicates.extend(bound.iter().cloned()),
+		None => {
+			generics.type_params_mut().for_each(|p| {
+				if bounded_params.contains(&p.ident) {
+					p.bounds.push(parse_quote!(_type_metadata::Metadata));
+				}
+				p.bounds.push(parse_quote!('static));
+			});
+			if !bounded_types.is_empty() {
+				let predicates = &mut generics.make_where_clause().predicates;
+				for ty in bounded_types {
+					predicates.push(parse_quote!(#ty: _type_metadata::Metadata + 'static));
+				}
+			}
+		}
+	}
+}
+
+/// Returns the type parameters and types to bound in order to describe the given field types.
+///
+/// Field types are bounded themselves rather than the type parameters they contain,
+/// e.g. `Vec<T>: Metadata` or `T::Output: Metadata`, so that no bounds are required
+/// of type parameters that the field types do not require either.
+/// Field types without any type parameters need no bounds.
+/// Field types referring to the type itself would lead to cyclic bounds, so the type
+/// parameters they contain are bounded instead.
+pub fn field_bounds(ast: &DeriveInput, field_types: &[Type]) -> (Vec<Ident>, Vec<Type>) {
+	let mut params = Vec::new();
+	let mut types = Vec::new();
+	for ty in field_types {
+		let tokens = ty.to_token_stream();
+		let contained = ast
+			.generics
+			.type_params()
+			.map(|param| &param.ident)
+			.filter(|ident| contains_ident(tokens.clone(), *ident))
+			.cloned()
+			.collect::<Vec<_>>();
+		if contained.is_empty() {
+			continue;
+		}
+		if contains_ident(tokens.clone(), &ast.ident) || contains_ident(tokens, "Self") {
+			for ident in contained {
+				if !params.contains(&ident) {
+					params.push(ident);
+				}
+			}
+		} else if !types.contains(ty) {
+			types.push(ty.clone());
+		}
+	}
+	(params, types)
+}
+
+/// Returns the types by which the fields are described in the type definition.
+///
+/// Lifetimes are erased and types given by `with` replace the field types.
+/// Skipped fields, `PhantomData` fields, fields described by `meta_type_with`
+/// or as opaque types and the fields of skipped variants are omitted.
+pub fn described_field_types(ast: &DeriveInput) -> Vec<Type> {
+	let fields: Vec<&Field> = match ast.data {
+		Data::Struct(ref data_struct) => data_struct.fields.iter().collect(),
+		Data::Enum(ref data_enum) => data_enum
+			.variants
+			.iter()
+			.filter(|v| parse_variant_attrs(&v.attrs).map(|attrs| !attrs.skip).unwrap_or(false))
+			.flat_map(|v| v.fields.iter())
+			.collect(),
+		Data::Union(ref data_union) => data_union.fields.named.iter().collect(),
+	};
+	let mut types = Vec::new();
+	for field in fields {
+		// Malformed attributes are reported when generating the field definitions.
+		let field_attrs = match parse_field_attrs(&field.attrs) {
+			Ok(field_attrs) => field_attrs,
+			Err(_) => continue,
+		};
+		if field_attrs.skip || field_attrs.opaque || field_attrs.meta_type_with.is_some() {
+			continue;
+		}
+		let ty = match field_attrs.with {
+			Some(with) => with,
+			None if is_phantom_data(&field.ty) => continue,
+			None => erase_lifetimes(&field.ty),
+		};
+		if !types.contains(&ty) {
+			types.push(ty);
+		}
+	}
+	types
+}
+
+/// Returns the type parameters contained in the types by which the fields are described.
+///
+/// The remaining type parameters only appear in `PhantomData` markers or fields
+/// that are not described otherwise, see `described_field_types`.
+pub fn described_type_params(ast: &DeriveInput) -> Vec<Ident> {
+	let field_types = described_field_types(ast);
+	ast.generics
+		.type_params()
+		.map(|param| &param.ident)
+		.filter(|ident| {
+			field_types
+				.iter()
+				.any(|ty| contains_ident(ty.to_token_stream(), *ident))
+		})
+		.cloned()
+		.collect()
+}
+
+/// Returns `true` if the type is a `PhantomData` marker.
+pub fn is_phantom_data(ty: &Type) -> bool {
+	if let Type::Path(type_path) = ty {
+		if let Some(segment) = type_path.path.segments.last() {
+			return segment.ident == "PhantomData";
+		}
+	}
+	false
+}
+
+/// Returns `true` if the tokens contain the given identifier.
+fn contains_ident<I>(tokens: TokenStream2, ident: &I) -> bool
+where
+	I: ?Sized,
+	Ident: PartialEq<I>,
+{
+	tokens.into_iter().any(|token| match token {
+		TokenTree::Ident(ref i) => i == ident,
+		TokenTree::Group(ref group) => contains_ident(group.stream(), ident),
+		_ => false,
+	})
+}
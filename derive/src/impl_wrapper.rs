@@ -19,20 +19,32 @@ use alloc::{format, string::ToString};
 
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
-use syn::Ident;
+use syn::{Ident, Path};
 
-pub fn wrap(ident: &Ident, trait_name: &'static str, impl_quote: TokenStream2) -> TokenStream2 {
+/// Wraps the impl into a dummy constant importing the `type_metadata` crate as `_type_metadata`.
+///
+/// The crate is imported from the given path or `type_metadata` by default.
+pub fn wrap(
+	ident: &Ident,
+	trait_name: &'static str,
+	crate_path: Option<&Path>,
+	impl_quote: TokenStream2,
+) -> TokenStream2 {
+	let crate_path = match crate_path {
+		Some(path) => quote! { #path },
+		None => quote! { type_metadata },
+	};
 	let mut renamed = format!("_IMPL_{}_FOR_", trait_name);
 	renamed.push_str(ident.to_string().trim_start_matches("r#"));
 	let dummy_const = Ident::new(&renamed, Span::call_site());
 
 	quote! {
-		#[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
+		#[allow(non_upper_case_globals, unused_attributes, unused_qualifications, deprecated)]
 		const #dummy_const: () = {
 			#[allow(unknown_lints)]
 			#[cfg_attr(feature = "cargo-clippy", allow(useless_attribute))]
 			#[allow(rust_2018_idioms)]
-			use type_metadata as _type_metadata;
+			use #crate_path as _type_metadata;
 
 			#[cfg(not(feature = "std"))]
 			extern crate alloc;
@@ -19,24 +19,42 @@
 extern crate alloc;
 extern crate proc_macro;
 
+mod attrs;
+mod bounds;
 mod impl_wrapper;
 mod metadata;
 mod type_def;
 mod type_id;
+// Shared with `type-metadata` so that namespaces are validated the same way.
+#[path = "../../src/utils.rs"]
+mod utils;
+
+#[cfg(test)]
+mod tests;
 
 use proc_macro::TokenStream;
 
-#[proc_macro_derive(TypeId)]
+#[proc_macro_derive(TypeId, attributes(metadata))]
 pub fn type_id(input: TokenStream) -> TokenStream {
 	type_id::generate(input.into()).into()
 }
 
-#[proc_macro_derive(TypeDef)]
+#[proc_macro_derive(TypeDef, attributes(metadata))]
 pub fn type_def(input: TokenStream) -> TokenStream {
 	type_def::generate(input.into()).into()
 }
 
-#[proc_macro_derive(Metadata)]
+#[proc_macro_derive(HasTypeId, attributes(metadata))]
+pub fn has_type_id(input: TokenStream) -> TokenStream {
+	type_id::generate(input.into()).into()
+}
+
+#[proc_macro_derive(HasTypeDef, attributes(metadata))]
+pub fn has_type_def(input: TokenStream) -> TokenStream {
+	type_def::generate(input.into()).into()
+}
+
+#[proc_macro_derive(Metadata, attributes(metadata))]
 pub fn metadata(input: TokenStream) -> TokenStream {
 	metadata::generate(input.into()).into()
 }
@@ -14,9 +14,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(not(feature = "std"))]
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::parse::Result;
+use syn::{parse::Result, Error};
 
 use crate::{type_def, type_id};
 
@@ -28,8 +35,24 @@ pub fn generate(input: TokenStream2) -> TokenStream2 {
 }
 
 pub fn generate_impl(input: TokenStream2) -> Result<TokenStream2> {
-	let mut tokens = quote! {};
-	tokens.extend(type_id::generate_impl(input.clone())?);
-	tokens.extend(type_def::generate_impl(input)?);
-	Ok(tokens)
+	match (type_id::generate_impl(input.clone()), type_def::generate_impl(input)) {
+		(Ok(type_id), Ok(type_def)) => Ok(quote! { #type_id #type_def }),
+		(Err(err), Ok(_)) | (Ok(_), Err(err)) => Err(err),
+		(Err(mut errors), Err(type_def_errors)) => {
+			// Errors about the input shared by both derives, e.g. about malformed
+			// type attributes, are reported only once.
+			let reported = errors.clone().into_iter().map(|err| key(&err)).collect::<Vec<_>>();
+			for err in type_def_errors {
+				if !reported.contains(&key(&err)) {
+					errors.combine(err);
+				}
+			}
+			Err(errors)
+		}
+	}
+}
+
+/// Identifies an error by its message and location.
+fn key(err: &Error) -> (String, String) {
+	(err.to_string(), format!("{:?}", err.span()))
 }
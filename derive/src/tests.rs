@@ -0,0 +1,307 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec,
+	vec::Vec,
+};
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+use crate::{metadata, type_def, type_id};
+
+/// Returns the messages of all errors reported by the `TypeDef` derive.
+fn type_def_errors(input: TokenStream2) -> Vec<String> {
+	match type_def::generate_impl(input) {
+		Ok(_) => Vec::new(),
+		Err(err) => err.into_iter().map(|err| err.to_string()).collect(),
+	}
+}
+
+#[test]
+fn reports_all_field_errors() {
+	let errors = type_def_errors(quote! {
+		struct S {
+			a: *const u8,
+			#[metadata(unknown)]
+			b: u8,
+			c: fn(),
+		}
+	});
+	assert_eq!(errors.len(), 3);
+	assert!(errors[0].starts_with("field `a` uses the type `*const u8`"));
+	assert_eq!(errors[1], "unknown or misplaced metadata attribute");
+	assert!(errors[2].starts_with("field `c` uses the type `fn()`"));
+}
+
+#[test]
+fn renders_types_as_written() {
+	let render = |ty: TokenStream2| type_def::type_to_string(&syn::parse2(ty).unwrap());
+	assert_eq!(render(quote!(*const u8)), "*const u8");
+	assert_eq!(render(quote!(fn())), "fn()");
+	assert_eq!(render(quote!(&'a mut [u8; 4])), "&'a mut [u8; 4]");
+	assert_eq!(
+		render(quote!(Box<dyn Fn(u8, bool) -> u8 + Send>)),
+		"Box<dyn Fn(u8, bool) -> u8 + Send>"
+	);
+	assert_eq!(
+		render(quote!(<Vec<T> as IntoIterator>::Item)),
+		"<Vec<T> as IntoIterator>::Item"
+	);
+}
+
+#[test]
+fn reports_all_variant_errors() {
+	let errors = type_def_errors(quote! {
+		enum E {
+			#[metadata(index = 3)]
+			A(bool),
+			B { b: *mut u8 },
+			#[metadata(index = 3)]
+			C(u8),
+		}
+	});
+	assert_eq!(
+		errors,
+		vec![
+			"field `b` uses the type `*mut u8` that cannot implement `Metadata`, \
+			 consider `#[metadata(skip)]`, `#[metadata(opaque)]`, `#[metadata(with = \"...\")]` \
+			 or a wrapper type described via `impl_metadata_for!`",
+			"index 3 is used by multiple variants",
+		]
+	);
+}
+
+#[test]
+fn rejects_invalid_discriminants() {
+	let errors = type_def_errors(quote! {
+		enum E {
+			A = 3 << 63,
+		}
+	});
+	assert_eq!(
+		errors,
+		vec!["cannot evaluate discriminant: arithmetic overflows 64 bits or divides by zero"]
+	);
+
+	let errors = type_def_errors(quote! {
+		enum E {
+			A = -1,
+		}
+	});
+	assert_eq!(
+		errors,
+		vec!["negative discriminants require a signed `#[repr(..)]`, e.g. `#[repr(i8)]`"]
+	);
+
+	let errors = type_def_errors(quote! {
+		#[repr(u8)]
+		enum E {
+			A = 255,
+			B,
+		}
+	});
+	assert_eq!(
+		errors,
+		vec!["discriminant 256 does not fit into `u8` or cannot be recorded in 64 bits"]
+	);
+
+	let errors = type_def_errors(quote! {
+		#[repr(i128)]
+		enum E {
+			A = 1 << 63,
+		}
+	});
+	assert_eq!(
+		errors,
+		vec!["discriminant 9223372036854775808 does not fit into `i128` or cannot be recorded in 64 bits"]
+	);
+
+	let errors = type_def_errors(quote! {
+		enum E {
+			A,
+			#[codec(index = 7)]
+			B,
+		}
+	});
+	assert_eq!(
+		errors,
+		vec![
+			"C-like enum variants are described by their discriminants, \
+			 give the discriminant as `Variant = N` instead of an index"
+		]
+	);
+}
+
+#[test]
+fn rejects_duplicate_discriminants() {
+	let errors = type_def_errors(quote! {
+		enum E {
+			A = 1,
+			B = 1,
+		}
+	});
+	assert_eq!(errors, vec!["discriminant 1 is used by multiple variants"]);
+
+	let errors = type_def_errors(quote! {
+		enum E {
+			A = 1,
+			B = 0,
+			C,
+		}
+	});
+	assert_eq!(errors, vec!["discriminant 1 is used by multiple variants"]);
+
+	// Skipped variants still take their discriminants.
+	let errors = type_def_errors(quote! {
+		enum E {
+			A,
+			#[metadata(skip)]
+			B,
+			C,
+		}
+	});
+	assert!(errors.is_empty());
+}
+
+#[test]
+fn codec_skipped_fields_are_not_lossy() {
+	let lossy = |input: TokenStream2| type_def::generate_impl(input).unwrap().to_string().contains("fn lossy");
+	assert!(!lossy(quote! {
+		struct S {
+			#[codec(skip)]
+			a: u8,
+		}
+	}));
+	assert!(lossy(quote! {
+		struct S {
+			#[metadata(skip)]
+			a: u8,
+		}
+	}));
+}
+
+#[test]
+fn describes_const_params_of_all_types() {
+	let output = type_id::generate_impl(quote! {
+		struct S<const N: i8, const M: u128, const B: bool, const C: char>;
+	})
+	.unwrap()
+	.to_string()
+	.split_whitespace()
+	.collect::<String>();
+	for param in &["N", "M", "B", "C"] {
+		assert!(output.contains(&format!("_type_metadata::ConstParam::from({})", param)));
+	}
+}
+
+#[test]
+fn reports_errors_once() {
+	let output = metadata::generate(quote! {
+		#[metadata(unknown)]
+		struct S;
+	});
+	assert_eq!(output.to_string().matches("compile_error").count(), 1);
+
+	// Errors shared by the type identifier and definition are reported once,
+	// errors of either of them are reported in any case.
+	let errors = |input: TokenStream2| {
+		metadata::generate_impl(input)
+			.unwrap_err()
+			.into_iter()
+			.map(|err| err.to_string())
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(
+		errors(quote! {
+			#[metadata(transparent)]
+			struct S(u8, u16);
+		}),
+		vec!["`transparent` requires exactly one field that is not skipped"]
+	);
+	assert_eq!(
+		errors(quote! {
+			#[metadata(transparent, rename = "T")]
+			struct S(u8);
+		}),
+		vec!["`rename` and `namespace` cannot be combined with `transparent`"]
+	);
+	assert_eq!(
+		errors(quote! {
+			struct S(*const u8);
+		})
+		.len(),
+		1
+	);
+}
+
+#[test]
+fn honors_codec_attributes() {
+	let output = type_def::generate_impl(quote! {
+		enum E {
+			A {
+				#[codec(compact)]
+				a: u64,
+				#[codec(skip)]
+				cache: u8,
+			},
+			#[codec(skip)]
+			B,
+			#[codec(index = "7")]
+			C,
+			D(bool),
+		}
+	})
+	.unwrap()
+	.to_string();
+	// The spacing between tokens depends on the version of `proc-macro2`.
+	let output = output.split_whitespace().collect::<String>();
+	assert!(output.contains(".compact()"));
+	assert!(!output.contains("cache"));
+	assert!(!output.contains("stringify!(B)"));
+	assert!(output.contains(".with_index(7u64)"));
+}
+
+#[test]
+fn rejects_indices_beyond_u8() {
+	let errors = type_def_errors(quote! {
+		enum E {
+			#[codec(index = 256)]
+			A(bool),
+			#[codec(index = "255")]
+			B(u8),
+		}
+	});
+	assert_eq!(
+		errors,
+		vec!["index 256 does not fit into `u8` as encoded by `parity-scale-codec`"]
+	);
+
+	let errors = type_def_errors(quote! {
+		enum E {
+			#[metadata(index = 300)]
+			A(bool),
+		}
+	});
+	assert_eq!(
+		errors,
+		vec!["index 300 does not fit into `u8` as encoded by `parity-scale-codec`"]
+	);
+}
@@ -14,14 +14,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
+#[cfg(not(feature = "std"))]
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+
+use proc_macro2::{Delimiter, Spacing, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{
-	parse::Result, parse_quote, punctuated::Punctuated, token::Comma, Data, DataEnum, DataStruct, DataUnion,
-	DeriveInput, Expr, ExprLit, Field, Fields, Lit, Variant,
+	parse::Result, parse_quote, punctuated::Punctuated, spanned::Spanned, token::Comma, visit_mut::VisitMut, Attribute,
+	BinOp, BoundLifetimes, Data, DataEnum, DataStruct, DataUnion, DeriveInput, Error, Expr, ExprBinary, ExprLit,
+	ExprUnary, Field, Fields, GenericArgument, Ident, Lifetime, Lit, PathArguments, Type, UnOp, Variant,
 };
 
-use crate::impl_wrapper::wrap;
+use crate::{
+	attrs::{
+		has_codec_flag, parse_docs, parse_field_attrs, parse_type_attrs, parse_variant_attrs, FieldAttrs, TypeAttrs,
+		VariantAttrs,
+	},
+	bounds::{add_bounds, described_field_types, field_bounds, is_phantom_data},
+	impl_wrapper::wrap,
+};
 
 pub fn generate(input: TokenStream2) -> TokenStream2 {
 	match generate_impl(input) {
@@ -33,62 +48,423 @@ pub fn generate(input: TokenStream2) -> TokenStream2 {
 pub fn generate_impl(input: TokenStream2) -> Result<TokenStream2> {
 	let mut ast: DeriveInput = syn::parse2(input)?;
 
-	ast.generics.type_params_mut().for_each(|p| {
-		p.bounds.push(parse_quote!(_type_metadata::Metadata));
-		p.bounds.push(parse_quote!('static));
-	});
+	let type_attrs = parse_type_attrs(&ast.attrs)?;
+	let (bounded_params, bounded_types) = field_bounds(&ast, &described_field_types(&ast));
+	add_bounds(&mut ast.generics, &type_attrs, &bounded_params, &bounded_types);
 
 	let ident = &ast.ident;
 	let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
-	let def = match &ast.data {
-		Data::Struct(ref s) => generate_struct_def(s),
-		Data::Enum(ref e) => generate_enum_def(e),
-		Data::Union(ref u) => generate_union_def(u),
+	if !type_attrs.reserved_indices.is_empty() {
+		if let Data::Struct(_) | Data::Union(_) = ast.data {
+			return Err(Error::new_spanned(
+				ident,
+				"`reserved_indices` is only supported on enums",
+			));
+		}
+	}
+
+	let type_def = if type_attrs.transparent {
+		let ty = transparent_field_type(&ast)?;
+		quote! {
+			<#ty as _type_metadata::HasTypeDef>::type_def()
+		}
+	} else {
+		let def = match &ast.data {
+			Data::Struct(ref s) => generate_struct_def(s)?,
+			Data::Enum(ref e) => generate_enum_def(e, &type_attrs)?,
+			Data::Union(ref u) => generate_union_def(u)?,
+		};
+		let docs = generate_docs(&ast.attrs);
+		let deprecated = generate_deprecated(&type_attrs.deprecated);
+		quote! {
+			#def#deprecated#docs.into()
+		}
+	};
+
+	// Computed after the type definition in order to report all field errors at once.
+	let lossy = match lossy_reason(&ast)? {
+		Some(reason) => quote! {
+			fn lossy() -> __core::option::Option<&'static str> {
+				__core::option::Option::Some(#reason)
+			}
+		},
+		None if type_attrs.transparent => {
+			let ty = transparent_field_type(&ast)?;
+			quote! {
+				fn lossy() -> __core::option::Option<&'static str> {
+					<#ty as _type_metadata::HasTypeDef>::lossy()
+				}
+			}
+		}
+		None => quote! {},
 	};
 
 	let has_type_def_impl = quote! {
 		impl #impl_generics _type_metadata::HasTypeDef for #ident #ty_generics #where_clause {
 			fn type_def() -> _type_metadata::TypeDef {
-				#def.into()
+				#type_def
 			}
+
+			#lossy
 		}
 	};
 
-	Ok(wrap(ident, "HAS_TYPE_DEF", has_type_def_impl))
+	Ok(wrap(
+		ident,
+		"HAS_TYPE_DEF",
+		type_attrs.crate_path.as_ref(),
+		has_type_def_impl,
+	))
 }
 
 type FieldsList = Punctuated<Field, Comma>;
 
-fn generate_fields_def(fields: &FieldsList) -> TokenStream2 {
-	let fields_def = fields.iter().map(|f| {
-		let (ty, ident) = (&f.ty, &f.ident);
-		let meta_type = quote! {
-			<#ty as _type_metadata::Metadata>::meta_type()
-		};
-		if let Some(i) = ident {
+/// Generates the definitions of all fields that are not skipped.
+///
+/// Fields are always emitted in their order of declaration.
+/// Errors of all fields are reported at once.
+fn generate_fields_def(fields: &FieldsList) -> Result<TokenStream2> {
+	let mut errors = Errors::default();
+	let mut fields_def = Vec::new();
+	for f in fields {
+		match generate_field_def(f) {
+			Ok(Some(field_def)) => fields_def.push(field_def),
+			Ok(None) => (),
+			Err(err) => errors.push(err),
+		}
+	}
+	errors.finish()?;
+	Ok(quote! { __core::vec![#( #fields_def, )*] })
+}
+
+/// Generates the definition of the field or `None` if it is skipped.
+fn generate_field_def(f: &Field) -> Result<Option<TokenStream2>> {
+	let field_attrs = parse_field_attrs(&f.attrs)?;
+	if is_skipped(f, &field_attrs) {
+		return Ok(None);
+	}
+	let compact = if field_attrs.compact {
+		quote! { .compact() }
+	} else {
+		quote! {}
+	};
+	let meta_type = match field_attrs.meta_type_with {
+		// Opaque fields are described independently of whether their types implement `Metadata`.
+		None if field_attrs.opaque => quote! {
+			_type_metadata::MetaType::opaque()
+		},
+		// Point errors about mismatched function signatures to the attribute.
+		Some(ref path) => quote_spanned! { path.span() =>
+			{
+				let meta_type_with: fn() -> _type_metadata::MetaType = #path;
+				meta_type_with()
+			}
+		},
+		None => {
+			let ty = match field_attrs.with {
+				Some(with) => with,
+				None => {
+					check_supported_type(f, &f.ty)?;
+					erase_lifetimes(&f.ty)
+				}
+			};
+			// Point errors about field types not implementing `Metadata` to the field.
+			quote_spanned! { f.ty.span() =>
+				<#ty as _type_metadata::Metadata>::meta_type()
+			}
+		}
+	};
+	Ok(Some(match f.ident {
+		Some(ref i) => {
+			let docs = generate_docs(&f.attrs);
+			let deprecated = generate_deprecated(&field_attrs.deprecated);
+			let name = match field_attrs.rename {
+				Some(rename) => quote! { #rename },
+				None => quote! { stringify!(#i) },
+			};
 			quote! {
-				_type_metadata::NamedField::new(stringify!(#i), #meta_type)
+				_type_metadata::NamedField::new(#name, #meta_type)#compact#deprecated#docs
 			}
-		} else {
+		}
+		None => {
+			if field_attrs.rename.is_some() {
+				return Err(Error::new_spanned(f, "`rename` is only supported on named fields"));
+			}
+			// Unnamed fields are serialized as plain types and thus carry no docs or deprecation notes.
 			quote! {
-				_type_metadata::UnnamedField::new(#meta_type)
+				_type_metadata::UnnamedField::new(#meta_type)#compact
 			}
 		}
-	});
-	quote! { __core::vec![#( #fields_def, )*] }
+	}))
+}
+
+/// Collects errors in order to report all of them at once.
+#[derive(Default)]
+struct Errors(Option<Error>);
+
+impl Errors {
+	/// Adds the error to the collected errors.
+	fn push(&mut self, err: Error) {
+		match self.0 {
+			Some(ref mut errors) => errors.combine(err),
+			None => self.0 = Some(err),
+		}
+	}
+
+	/// Returns all collected errors combined, if any.
+	fn finish(self) -> Result<()> {
+		match self.0 {
+			Some(errors) => Err(errors),
+			None => Ok(()),
+		}
+	}
+}
+
+/// Returns `true` if the field is omitted from the type definition.
+///
+/// Besides explicitly skipped fields this includes `PhantomData` markers
+/// unless their metadata is overridden by `#[metadata(with = "...")]`,
+/// `#[metadata(meta_type_with = "...")]` or `#[metadata(opaque)]`.
+fn is_skipped(field: &Field, field_attrs: &FieldAttrs) -> bool {
+	field_attrs.skip
+		|| (field_attrs.with.is_none()
+			&& field_attrs.meta_type_with.is_none()
+			&& !field_attrs.opaque
+			&& is_phantom_data(&field.ty))
+}
+
+/// Returns why the type definition is lossy, if it is.
+///
+/// Fields and variants omitted from the type definition make it lossy unless they are
+/// skipped by `#[codec(skip)]` and are thus not encoded either or are `PhantomData` markers.
+/// Opaque fields make it lossy as well.
+fn lossy_reason(ast: &DeriveInput) -> Result<Option<&'static str>> {
+	let mut fields = Vec::new();
+	match ast.data {
+		Data::Struct(ref data_struct) => fields.extend(&data_struct.fields),
+		Data::Enum(ref data_enum) => {
+			for v in &data_enum.variants {
+				if !parse_variant_attrs(&v.attrs)?.skip {
+					fields.extend(&v.fields);
+				} else if !has_codec_flag(&v.attrs, "skip") {
+					return Ok(Some("skips variants"));
+				}
+			}
+		}
+		Data::Union(ref data_union) => fields.extend(&data_union.fields.named),
+	}
+	for f in fields {
+		let field_attrs = parse_field_attrs(&f.attrs)?;
+		// Markers encode to nothing, thus omitting them loses nothing either.
+		if is_skipped(f, &field_attrs) && !has_codec_flag(&f.attrs, "skip") && !is_phantom_data(&f.ty) {
+			return Ok(Some("skips fields"));
+		}
+		if field_attrs.opaque {
+			return Ok(Some("describes fields as opaque types"));
+		}
+	}
+	Ok(None)
+}
+
+/// Returns the type of the single field a `#[metadata(transparent)]` struct forwards to.
+pub fn transparent_field_type(ast: &DeriveInput) -> Result<Type> {
+	let fields = match ast.data {
+		Data::Struct(ref data_struct) => &data_struct.fields,
+		_ => {
+			return Err(Error::new_spanned(
+				&ast.ident,
+				"`transparent` is only supported on structs",
+			))
+		}
+	};
+	let mut forwarded = Vec::new();
+	for f in fields {
+		let field_attrs = parse_field_attrs(&f.attrs)?;
+		if !is_skipped(f, &field_attrs) {
+			if let Some(path) = field_attrs.meta_type_with {
+				return Err(Error::new_spanned(
+					path,
+					"`meta_type_with` is not supported on the field of a `transparent` struct",
+				));
+			}
+			if field_attrs.opaque {
+				return Err(Error::new_spanned(
+					f,
+					"`opaque` is not supported on the field of a `transparent` struct",
+				));
+			}
+			forwarded.push(match field_attrs.with {
+				Some(with) => with,
+				None => {
+					check_supported_type(f, &f.ty)?;
+					erase_lifetimes(&f.ty)
+				}
+			});
+		}
+	}
+	match forwarded.len() {
+		1 => Ok(forwarded.remove(0)),
+		_ => Err(Error::new_spanned(
+			&ast.ident,
+			"`transparent` requires exactly one field that is not skipped",
+		)),
+	}
+}
+
+/// Returns an error if the field type can never implement `Metadata`.
+///
+/// This detects kinds of types without any `Metadata` implementations, such as
+/// raw pointers, function pointers and trait objects, also within other types.
+fn check_supported_type(field: &Field, ty: &Type) -> Result<()> {
+	match ty {
+		Type::Ptr(_) | Type::BareFn(_) | Type::TraitObject(_) | Type::ImplTrait(_) | Type::Never(_) => {
+			let field_name = match field.ident {
+				Some(ref ident) => format!("field `{}`", ident),
+				None => "unnamed field".into(),
+			};
+			Err(Error::new_spanned(
+				ty,
+				format!(
+					"{} uses the type `{}` that cannot implement `Metadata`, \
+					 consider `#[metadata(skip)]`, `#[metadata(opaque)]`, `#[metadata(with = \"...\")]` \
+					 or a wrapper type described via `impl_metadata_for!`",
+					field_name,
+					type_to_string(ty),
+				),
+			))
+		}
+		Type::Array(array) => check_supported_type(field, &array.elem),
+		Type::Slice(slice) => check_supported_type(field, &slice.elem),
+		Type::Reference(reference) => check_supported_type(field, &reference.elem),
+		Type::Paren(paren) => check_supported_type(field, &paren.elem),
+		Type::Group(group) => check_supported_type(field, &group.elem),
+		Type::Tuple(tuple) => tuple
+			.elems
+			.iter()
+			.try_for_each(|elem| check_supported_type(field, elem)),
+		Type::Path(type_path) => type_path
+			.path
+			.segments
+			.iter()
+			.filter_map(|segment| match segment.arguments {
+				PathArguments::AngleBracketed(ref arguments) => Some(&arguments.args),
+				_ => None,
+			})
+			.flatten()
+			.try_for_each(|argument| match argument {
+				GenericArgument::Type(ty) => check_supported_type(field, ty),
+				_ => Ok(()),
+			}),
+		_ => Ok(()),
+	}
+}
+
+/// Replaces all lifetimes of the field type by `'static`.
+///
+/// Metadata is only available for `'static` types, whereas the lifetimes of
+/// the fields have no impact on their type identifiers and definitions.
+pub fn erase_lifetimes(ty: &Type) -> Type {
+	struct EraseLifetimes;
+
+	impl VisitMut for EraseLifetimes {
+		fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+			*lifetime = parse_quote!('static);
+		}
+
+		fn visit_bound_lifetimes_mut(&mut self, _: &mut BoundLifetimes) {
+			// Lifetimes introduced by higher-ranked bounds cannot be `'static`.
+		}
+	}
+
+	let mut ty = ty.clone();
+	EraseLifetimes.visit_type_mut(&mut ty);
+	ty
 }
 
-fn generate_struct_def(data_struct: &DataStruct) -> TokenStream2 {
-	match data_struct.fields {
+/// Renders the type as written in Rust source for error messages.
+///
+/// In contrast to `quote!(#ty).to_string()` tokens are only separated where Rust
+/// source would separate them, e.g. `*const u8` and `fn()` rather than `* const u8`
+/// and `fn ( )`.
+pub fn type_to_string(ty: &Type) -> String {
+	// Keywords that are separated from a following group, e.g. `&mut [u8]`.
+	const KEYWORDS: &[&str] = &["as", "const", "dyn", "impl", "mut"];
+
+	fn write_tokens(output: &mut String, tokens: TokenStream2) {
+		// Whether the next token is separated from the previous one by a space.
+		let mut separated = false;
+		for token in tokens {
+			match token {
+				TokenTree::Ident(_) | TokenTree::Literal(_) => {
+					let follows_word = output
+						.chars()
+						.last()
+						.is_some_and(|last| last.is_alphanumeric() || last == '_' || last == '>');
+					if separated || follows_word {
+						output.push(' ');
+					}
+					let word = token.to_string();
+					separated = KEYWORDS.contains(&word.as_str());
+					output.push_str(&word);
+				}
+				TokenTree::Punct(punct) => match punct.as_char() {
+					',' | ';' => {
+						output.push(punct.as_char());
+						separated = true;
+					}
+					'+' | '=' => {
+						output.push(' ');
+						output.push(punct.as_char());
+						separated = true;
+					}
+					'-' if punct.spacing() == Spacing::Joint => output.push_str(" -"),
+					'>' if output.ends_with(" -") => {
+						output.push('>');
+						separated = true;
+					}
+					c => {
+						if separated {
+							output.push(' ');
+						}
+						output.push(c);
+						separated = false;
+					}
+				},
+				TokenTree::Group(group) => {
+					if separated {
+						output.push(' ');
+					}
+					let (open, close) = match group.delimiter() {
+						Delimiter::Parenthesis => ("(", ")"),
+						Delimiter::Bracket => ("[", "]"),
+						Delimiter::Brace => ("{ ", " }"),
+						Delimiter::None => ("", ""),
+					};
+					output.push_str(open);
+					write_tokens(output, group.stream());
+					output.push_str(close);
+					separated = false;
+				}
+			}
+		}
+	}
+
+	let mut output = String::new();
+	write_tokens(&mut output, ty.to_token_stream());
+	output
+}
+
+fn generate_struct_def(data_struct: &DataStruct) -> Result<TokenStream2> {
+	Ok(match data_struct.fields {
 		Fields::Named(ref fs) => {
-			let fields = generate_fields_def(&fs.named);
+			let fields = generate_fields_def(&fs.named)?;
 			quote! {
 				_type_metadata::TypeDefStruct::new(#fields)
 			}
 		}
 		Fields::Unnamed(ref fs) => {
-			let fields = generate_fields_def(&fs.unnamed);
+			let fields = generate_fields_def(&fs.unnamed)?;
 			quote! {
 				_type_metadata::TypeDefTupleStruct::new(#fields)
 			}
@@ -96,37 +472,215 @@ fn generate_struct_def(data_struct: &DataStruct) -> TokenStream2 {
 		Fields::Unit => quote! {
 			_type_metadata::TypeDefTupleStruct::unit()
 		},
-	}
+	})
 }
 
 type VariantList = Punctuated<Variant, Comma>;
 
-fn generate_c_like_enum_def(variants: &VariantList) -> TokenStream2 {
-	let variants_def = variants.into_iter().enumerate().map(|(i, v)| {
-		let name = &v.ident;
-		let discriminant = if let Some((
-			_,
-			Expr::Lit(ExprLit {
-				lit: Lit::Int(lit_int), ..
-			}),
-		)) = &v.discriminant
-		{
-			match lit_int.base10_parse::<u64>() {
-				Ok(i) => i,
-				Err(err) => return err.to_compile_error(),
-			}
+fn generate_c_like_enum_def(variants: &VariantList, type_attrs: &TypeAttrs) -> Result<TokenStream2> {
+	let (min, max) = discriminant_range(type_attrs.repr.as_ref());
+	let mut used_discriminants = Vec::new();
+	let mut variants_def = Vec::new();
+	// Variants are recorded with their Rust discriminants, i.e. `Variant as Repr`, thus
+	// skipped variants still count towards the implicit discriminants of the following ones.
+	let mut next = 0;
+	for v in variants {
+		let variant_attrs = parse_variant_attrs(&v.attrs)?;
+		let discriminant = match v.discriminant {
+			Some((_, ref expr)) => eval_discriminant(expr)?,
+			None => next,
+		};
+		next = discriminant + 1;
+		if variant_attrs.skip {
+			continue;
+		}
+		if variant_attrs.index.is_some() {
+			return Err(Error::new_spanned(
+				v,
+				"C-like enum variants are described by their discriminants, \
+				 give the discriminant as `Variant = N` instead of an index",
+			));
+		}
+		if discriminant < 0 && min == 0 {
+			return Err(Error::new_spanned(
+				v,
+				"negative discriminants require a signed `#[repr(..)]`, e.g. `#[repr(i8)]`",
+			));
+		}
+		if discriminant < min || discriminant > max {
+			let repr = match type_attrs.repr {
+				Some(ref repr) => repr.to_string(),
+				None => String::from("isize"),
+			};
+			return Err(Error::new_spanned(
+				v,
+				format!(
+					"discriminant {} does not fit into `{}` or cannot be recorded in 64 bits",
+					discriminant, repr
+				),
+			));
+		}
+		// Negative discriminants are recorded in two's complement.
+		let discriminant = if discriminant < 0 {
+			discriminant as i64 as u64
 		} else {
-			i as u64
+			discriminant as u64
 		};
-		quote! {
-			_type_metadata::ClikeEnumVariant::new(stringify!(#name), #discriminant)
+		let name = generate_variant_name(v, &variant_attrs);
+		let docs = generate_docs(&v.attrs);
+		let deprecated = generate_deprecated(&variant_attrs.deprecated);
+		check_not_reserved(v, discriminant, type_attrs)?;
+		if used_discriminants.contains(&discriminant) {
+			return Err(Error::new_spanned(
+				v,
+				format!("discriminant {} is used by multiple variants", discriminant),
+			));
 		}
-	});
+		used_discriminants.push(discriminant);
+		variants_def.push(quote! {
+			_type_metadata::ClikeEnumVariant::new(#name, #discriminant)#deprecated#docs
+		});
+	}
+	let reserved_indices = generate_reserved_indices(type_attrs);
+	let discriminant_type = match type_attrs.repr {
+		Some(ref repr) => quote! { .with_discriminant_type(_type_metadata::MetaType::new::<#repr>()) },
+		None => quote! {},
+	};
+	Ok(quote! {
+		_type_metadata::TypeDefClikeEnum::new(__core::vec![#( #variants_def, )*])#discriminant_type#reserved_indices
+	})
+}
+
+/// Returns the inclusive range of discriminants that can be recorded for the given `#[repr(..)]`.
+///
+/// Discriminants are recorded as `u64`, negative ones in two's complement, thus 128-bit
+/// discriminants are limited to the range of `u64` or `i64` respectively. Enums without
+/// a fixed-width `#[repr(..)]` are limited to the non-negative range of `isize` on 64-bit targets.
+fn discriminant_range(repr: Option<&Ident>) -> (i128, i128) {
+	let repr = match repr {
+		Some(repr) => repr.to_string(),
+		None => return (0, i128::from(i64::MAX)),
+	};
+	match repr.as_str() {
+		"u8" => (0, i128::from(u8::MAX)),
+		"u16" => (0, i128::from(u16::MAX)),
+		"u32" => (0, i128::from(u32::MAX)),
+		"u64" | "u128" => (0, i128::from(u64::MAX)),
+		"i8" => (i128::from(i8::MIN), i128::from(i8::MAX)),
+		"i16" => (i128::from(i16::MIN), i128::from(i16::MAX)),
+		"i32" => (i128::from(i32::MIN), i128::from(i32::MAX)),
+		_ => (i128::from(i64::MIN), i128::from(i64::MAX)),
+	}
+}
+
+/// Generates the name of the variant taking renames into account.
+fn generate_variant_name(variant: &Variant, variant_attrs: &VariantAttrs) -> TokenStream2 {
+	let ident = &variant.ident;
+	match variant_attrs.rename {
+		Some(ref rename) => quote! { #rename },
+		None => quote! { stringify!(#ident) },
+	}
+}
+
+/// Generates the call recording the doc comments, if any.
+///
+/// Doc comments are only collected if the `docs` feature is enabled.
+fn generate_docs(attrs: &[Attribute]) -> TokenStream2 {
+	let docs = parse_docs(attrs);
+	if !cfg!(feature = "docs") || docs.is_empty() {
+		return quote! {};
+	}
+	quote! {
+		.with_docs(&[#( #docs ),*])
+	}
+}
+
+/// Generates the call recording the deprecation note, if any.
+fn generate_deprecated(deprecated: &Option<String>) -> TokenStream2 {
+	match deprecated {
+		Some(note) => quote! { .with_deprecated(#note) },
+		None => quote! {},
+	}
+}
+
+/// Returns an error if the variant uses an index that has been reserved.
+fn check_not_reserved(variant: &Variant, index: u64, type_attrs: &TypeAttrs) -> Result<()> {
+	if type_attrs.reserved_indices.contains(&index) {
+		return Err(Error::new_spanned(
+			variant,
+			format!("variant uses the reserved index {}", index),
+		));
+	}
+	Ok(())
+}
+
+/// Generates the call recording the reserved indices, if any.
+fn generate_reserved_indices(type_attrs: &TypeAttrs) -> TokenStream2 {
+	if type_attrs.reserved_indices.is_empty() {
+		return quote! {};
+	}
+	let reserved_indices = &type_attrs.reserved_indices;
 	quote! {
-		_type_metadata::TypeDefClikeEnum::new(__core::vec![#( #variants_def, )*])
+		.with_reserved_indices(__core::vec![#( #reserved_indices, )*])
 	}
 }
 
+/// Evaluates the given explicit discriminant expression.
+///
+/// Supports integer literals combined by arithmetic, bitwise and shift operators as well
+/// as negation. All intermediate values must fit into either `i64` or `u64`.
+fn eval_discriminant(expr: &Expr) -> Result<i128> {
+	let result = match expr {
+		Expr::Lit(ExprLit {
+			lit: Lit::Int(lit_int), ..
+		}) => return lit_int.base10_parse::<u64>().map(i128::from),
+		Expr::Paren(paren) => return eval_discriminant(&paren.expr),
+		Expr::Unary(ExprUnary {
+			op: UnOp::Neg(_), expr, ..
+		}) => eval_discriminant(expr)?.checked_neg(),
+		Expr::Binary(ExprBinary { left, op, right, .. }) => {
+			let (lhs, rhs) = (eval_discriminant(left)?, eval_discriminant(right)?);
+			match op {
+				BinOp::Add(_) => lhs.checked_add(rhs),
+				BinOp::Sub(_) => lhs.checked_sub(rhs),
+				BinOp::Mul(_) => lhs.checked_mul(rhs),
+				BinOp::Div(_) => lhs.checked_div(rhs),
+				BinOp::Rem(_) => lhs.checked_rem(rhs),
+				BinOp::BitAnd(_) => Some(lhs & rhs),
+				BinOp::BitOr(_) => Some(lhs | rhs),
+				BinOp::BitXor(_) => Some(lhs ^ rhs),
+				// Bits shifted out of 64 bits are caught by the range check below.
+				BinOp::Shl(_) if (0..64).contains(&rhs) => Some(lhs << rhs),
+				BinOp::Shr(_) if (0..64).contains(&rhs) => Some(lhs >> rhs),
+				BinOp::Shl(_) | BinOp::Shr(_) => None,
+				_ => {
+					return Err(Error::new_spanned(
+						op,
+						"unsupported operator in discriminant expression",
+					))
+				}
+			}
+		}
+		_ => {
+			return Err(Error::new_spanned(
+				expr,
+				"cannot evaluate discriminant: expected integer literals combined by arithmetic operators",
+			))
+		}
+	};
+	result.filter(|result| in_range(*result)).ok_or_else(|| {
+		Error::new_spanned(
+			expr,
+			"cannot evaluate discriminant: arithmetic overflows 64 bits or divides by zero",
+		)
+	})
+}
+
+/// Returns `true` if the discriminant fits into either `i64` or `u64`.
+fn in_range(discriminant: i128) -> bool {
+	(i128::from(i64::MIN)..=i128::from(u64::MAX)).contains(&discriminant)
+}
+
 fn is_c_like_enum(variants: &VariantList) -> bool {
 	// any variant has an explicit discriminant
 	variants.iter().any(|v| v.discriminant.is_some()) ||
@@ -137,42 +691,93 @@ fn is_c_like_enum(variants: &VariantList) -> bool {
 	})
 }
 
-fn generate_enum_def(data_enum: &DataEnum) -> TokenStream2 {
+/// Generates the enum definition with variants in their order of declaration.
+fn generate_enum_def(data_enum: &DataEnum, type_attrs: &TypeAttrs) -> Result<TokenStream2> {
 	let variants = &data_enum.variants;
 
-	if is_c_like_enum(&variants) {
-		return generate_c_like_enum_def(variants);
+	if is_c_like_enum(variants) {
+		return generate_c_like_enum_def(variants, type_attrs);
 	}
 
-	let variants_def = variants.into_iter().map(|v| {
-		let ident = &v.ident;
-		let v_name = quote! {stringify!(#ident) };
-		match v.fields {
-			Fields::Named(ref fs) => {
-				let fields = generate_fields_def(&fs.named);
-				quote! {
-					_type_metadata::EnumVariantStruct::new(#v_name, #fields).into()
-				}
+	let mut errors = Errors::default();
+	let mut used_indices = Vec::new();
+	let mut variants_def = Vec::new();
+	// Skipped variants do not count towards the positions of the following variants
+	// just as for encoding.
+	let mut position = 0;
+	for v in variants {
+		let variant_attrs = match parse_variant_attrs(&v.attrs) {
+			Ok(variant_attrs) => variant_attrs,
+			Err(err) => {
+				errors.push(err);
+				continue;
 			}
-			Fields::Unnamed(ref fs) => {
-				let fields = generate_fields_def(&fs.unnamed);
-				quote! {
-					_type_metadata::EnumVariantTupleStruct::new(#v_name, #fields).into()
-				}
-			}
-			Fields::Unit => quote! {
-				_type_metadata::EnumVariantUnit::new(#v_name).into()
-			},
+		};
+		if variant_attrs.skip {
+			continue;
 		}
-	});
-	quote! {
-		_type_metadata::TypeDefEnum::new(__core::vec![#( #variants_def, )*])
+		match generate_variant_def(v, &variant_attrs, position, type_attrs, &mut used_indices) {
+			Ok(variant_def) => variants_def.push(variant_def),
+			Err(err) => errors.push(err),
+		}
+		position += 1;
 	}
+	errors.finish()?;
+	let reserved_indices = generate_reserved_indices(type_attrs);
+	Ok(quote! {
+		_type_metadata::TypeDefEnum::new(__core::vec![#( #variants_def, )*])#reserved_indices
+	})
 }
 
-fn generate_union_def(data_union: &DataUnion) -> TokenStream2 {
-	let fields = generate_fields_def(&data_union.fields.named);
-	quote! {
-		_type_metadata::TypeDefUnion::new(#fields)
+/// Generates the definition of the enum variant at the given position.
+///
+/// Records the index of the variant in the used indices.
+fn generate_variant_def(
+	v: &Variant,
+	variant_attrs: &VariantAttrs,
+	position: u64,
+	type_attrs: &TypeAttrs,
+	used_indices: &mut Vec<u64>,
+) -> Result<TokenStream2> {
+	// Variants without an explicit index are indexed by their position.
+	let index = variant_attrs.index.unwrap_or(position);
+	check_not_reserved(v, index, type_attrs)?;
+	if used_indices.contains(&index) {
+		return Err(Error::new_spanned(
+			v,
+			format!("index {} is used by multiple variants", index),
+		));
 	}
+	used_indices.push(index);
+	let v_name = generate_variant_name(v, variant_attrs);
+	let docs = generate_docs(&v.attrs);
+	let deprecated = generate_deprecated(&variant_attrs.deprecated);
+	let with_index = match variant_attrs.index {
+		Some(index) => quote! { .with_index(#index) },
+		None => quote! {},
+	};
+	Ok(match v.fields {
+		Fields::Named(ref fs) => {
+			let fields = generate_fields_def(&fs.named)?;
+			quote! {
+				_type_metadata::EnumVariantStruct::new(#v_name, #fields)#with_index#deprecated#docs.into()
+			}
+		}
+		Fields::Unnamed(ref fs) => {
+			let fields = generate_fields_def(&fs.unnamed)?;
+			quote! {
+				_type_metadata::EnumVariantTupleStruct::new(#v_name, #fields)#with_index#deprecated#docs.into()
+			}
+		}
+		Fields::Unit => quote! {
+			_type_metadata::EnumVariantUnit::new(#v_name)#with_index#deprecated#docs.into()
+		},
+	})
+}
+
+fn generate_union_def(data_union: &DataUnion) -> Result<TokenStream2> {
+	let fields = generate_fields_def(&data_union.fields.named)?;
+	Ok(quote! {
+		_type_metadata::TypeDefUnion::new(#fields)
+	})
 }
@@ -14,11 +14,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
 use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
-use syn::{parse::Result, parse_quote, DeriveInput};
+use quote::{quote, quote_spanned};
+use syn::{parse::Result, DeriveInput, Error};
 
-use crate::impl_wrapper::wrap;
+use crate::{
+	attrs::{parse_type_attrs, TypeAttrs},
+	bounds::{add_bounds, described_type_params, field_bounds},
+	impl_wrapper::wrap,
+	type_def::transparent_field_type,
+};
 
 pub fn generate(input: TokenStream2) -> TokenStream2 {
 	match generate_impl(input) {
@@ -30,31 +38,98 @@ pub fn generate(input: TokenStream2) -> TokenStream2 {
 pub fn generate_impl(input: TokenStream2) -> Result<TokenStream2> {
 	let mut ast: DeriveInput = syn::parse2(input)?;
 
-	ast.generics.type_params_mut().for_each(|p| {
-		p.bounds.push(parse_quote!(_type_metadata::Metadata));
-		p.bounds.push(parse_quote!('static));
-	});
+	let type_attrs = parse_type_attrs(&ast.attrs)?;
+	// A transparent type identifier is given by the field type, otherwise by the type parameters.
+	// Only the type parameters contained in described fields are required to implement `Metadata`.
+	let (bounded_params, bounded_types) = if type_attrs.transparent {
+		field_bounds(&ast, &[transparent_field_type(&ast)?])
+	} else {
+		(described_type_params(&ast), Vec::new())
+	};
+	add_bounds(&mut ast.generics, &type_attrs, &bounded_params, &bounded_types);
 
-	let ident = &ast.ident;
-	let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-	let generic_type_ids = ast.generics.type_params().map(|ty| {
-		let ty_ident = &ty.ident;
+	let type_id = if type_attrs.transparent {
+		if type_attrs.rename.is_some() || type_attrs.namespace.is_some() {
+			return Err(Error::new_spanned(
+				&ast.ident,
+				"`rename` and `namespace` cannot be combined with `transparent`",
+			));
+		}
+		let ty = transparent_field_type(&ast)?;
 		quote! {
-			<#ty_ident as _type_metadata::Metadata>::meta_type()
+			<#ty as _type_metadata::HasTypeId>::type_id()
 		}
-	});
+	} else {
+		generate_custom_type_id(&ast, &type_attrs)
+	};
+
+	let ident = &ast.ident;
+	let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 	let has_type_id_impl = quote! {
 		impl #impl_generics _type_metadata::HasTypeId for #ident #ty_generics #where_clause {
 			fn type_id() -> _type_metadata::TypeId {
-				_type_metadata::TypeIdCustom::new(
-					stringify!(#ident),
-					_type_metadata::Namespace::from_module_path(module_path!())
-						.expect("namespace from module path cannot fail"),
-					__core::vec![ #( #generic_type_ids ),* ],
-				).into()
+				#type_id
 			}
 		}
 	};
 
-	Ok(wrap(ident, "HAS_TYPE_ID", has_type_id_impl))
+	Ok(wrap(
+		ident,
+		"HAS_TYPE_ID",
+		type_attrs.crate_path.as_ref(),
+		has_type_id_impl,
+	))
+}
+
+fn generate_custom_type_id(ast: &DeriveInput, type_attrs: &TypeAttrs) -> TokenStream2 {
+	let ident = &ast.ident;
+	let name = match type_attrs.rename {
+		Some(ref rename) => quote! { #rename },
+		None => quote! { stringify!(#ident) },
+	};
+	let namespace = match type_attrs.namespace {
+		Some(ref segments) => quote! {
+			_type_metadata::Namespace::new(__core::vec![ #( #segments ),* ])
+				.expect("namespace segments are validated by the derive")
+		},
+		None => quote! {
+			_type_metadata::Namespace::from_module_path(module_path!())
+				.expect("namespace from module path cannot fail")
+		},
+	};
+	// Type parameters are emitted in their order of declaration. Those only used by
+	// markers or fields that are not described are recorded as `PhantomData` markers,
+	// which do not require them to implement `Metadata`.
+	let described_params = described_type_params(ast);
+	let generic_type_ids = ast.generics.type_params().map(|ty| {
+		let ty_ident = &ty.ident;
+		if !described_params.contains(ty_ident) {
+			return quote! {
+				<__core::marker::PhantomData<#ty_ident> as _type_metadata::Metadata>::meta_type()
+			};
+		}
+		// Point errors about type parameters not implementing `Metadata` to the parameter.
+		quote_spanned! { ty_ident.span() =>
+			<#ty_ident as _type_metadata::Metadata>::meta_type()
+		}
+	});
+	let generic_const_params = ast.generics.const_params().map(|param| {
+		let param_ident = &param.ident;
+		// Point errors about const parameters of unsupported types to the parameter.
+		quote_spanned! { param_ident.span() =>
+			_type_metadata::ConstParam::from(#param_ident)
+		}
+	});
+	let const_params = if ast.generics.const_params().next().is_some() {
+		quote! { .with_const_params(__core::vec![ #( #generic_const_params ),* ]) }
+	} else {
+		quote! {}
+	};
+	quote! {
+		_type_metadata::TypeIdCustom::new(
+			#name,
+			#namespace,
+			__core::vec![ #( #generic_type_ids ),* ],
+		)#const_params.into()
+	}
 }
@@ -0,0 +1,298 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interactive terminal browser over a registry serialized as JSON.
+//!
+//! Run with `cargo run --example explorer --features serde_json -- registry.json`.
+//! Without a path a small demo registry is browsed instead. Types are referred
+//! to by their identifiers within the registry, e.g. `show 3`, so references are
+//! followed by showing the types printed next to the fields.
+
+use std::{
+	collections::BTreeMap,
+	env, fs,
+	io::{self, BufRead, Write},
+	process,
+};
+use type_metadata::{
+	form::PortableForm, tuple_meta_type, EnumVariant, Registry, RegistryReadOnly, TypeDef, TypeId, TypeIdDef,
+};
+
+const HELP: &str = "\
+commands:
+  search <text>  lists the types whose names contain the text
+  show <id>      shows the type with its fields and variants
+  refs <id>      lists the types referring to the type
+  json <id>      prints the type as JSON
+  back           shows the previously shown type
+  help           prints this help
+  quit           exits the explorer";
+
+/// The types of the loaded registry.
+struct Explorer {
+	types: Vec<TypeIdDef<PortableForm>>,
+	/// The identifiers of the shown types from the first to the last one.
+	history: Vec<u32>,
+}
+
+impl Explorer {
+	/// Returns the type with the given identifier.
+	fn get(&self, id: u32) -> Option<&TypeIdDef<PortableForm>> {
+		id.checked_sub(1).and_then(|index| self.types.get(index as usize))
+	}
+
+	/// Renders the name of the type with the given identifier as a Rust type.
+	fn name(&self, id: u32) -> String {
+		let ty = match self.get(id) {
+			Some(ty) => ty,
+			None => return format!("<unknown #{}>", id),
+		};
+		match ty.id() {
+			TypeId::Custom(custom) => {
+				let mut path = custom.namespace().segments().to_vec();
+				path.push(custom.name().clone());
+				let params = custom
+					.type_params()
+					.iter()
+					.map(|param| self.name(param.id().get()))
+					.chain(custom.const_params().iter().map(ToString::to_string))
+					.collect::<Vec<_>>();
+				if params.is_empty() {
+					path.join("::")
+				} else {
+					format!("{}<{}>", path.join("::"), params.join(", "))
+				}
+			}
+			TypeId::Tuple(tuple) => {
+				let params = tuple
+					.type_params
+					.iter()
+					.map(|param| self.name(param.id().get()))
+					.collect::<Vec<_>>();
+				format!("({})", params.join(", "))
+			}
+			TypeId::Slice(slice) => format!("[{}]", self.name(slice.type_param().id().get())),
+			TypeId::Array(array) => format!("[{}; {}]", self.name(array.type_param.id().get()), array.len),
+			TypeId::Primitive(primitive) => primitive.name().to_string(),
+			_ => format!("<unsupported #{}>", id),
+		}
+	}
+
+	/// Renders a reference to the type with the given identifier.
+	fn reference(&self, id: u32) -> String {
+		format!("{} #{}", self.name(id), id)
+	}
+
+	/// Returns the identifiers of all types the given type refers to.
+	fn referred(&self, ty: &TypeIdDef<PortableForm>) -> Vec<u32> {
+		let mut referred = Vec::new();
+		match ty.id() {
+			TypeId::Custom(custom) => referred.extend(custom.type_params().iter().map(|param| param.id().get())),
+			TypeId::Tuple(tuple) => referred.extend(tuple.type_params.iter().map(|param| param.id().get())),
+			TypeId::Slice(slice) => referred.push(slice.type_param().id().get()),
+			TypeId::Array(array) => referred.push(array.type_param.id().get()),
+			_ => (),
+		}
+		match ty.def() {
+			TypeDef::Struct(r#struct) => referred.extend(r#struct.fields().iter().map(|field| field.ty().id().get())),
+			TypeDef::TupleStruct(tuple_struct) => {
+				referred.extend(tuple_struct.fields().iter().map(|field| field.ty().id().get()))
+			}
+			TypeDef::ClikeEnum(clike_enum) => referred.extend(clike_enum.discriminant_type().map(|ty| ty.id().get())),
+			TypeDef::Enum(r#enum) => {
+				for variant in r#enum.variants() {
+					match variant {
+						EnumVariant::Struct(r#struct) => {
+							referred.extend(r#struct.fields().iter().map(|field| field.ty().id().get()))
+						}
+						EnumVariant::TupleStruct(tuple_struct) => {
+							referred.extend(tuple_struct.fields().iter().map(|field| field.ty().id().get()))
+						}
+						_ => (),
+					}
+				}
+			}
+			TypeDef::Union(union) => referred.extend(union.fields().iter().map(|field| field.ty().id().get())),
+			_ => (),
+		}
+		referred
+	}
+
+	fn search(&self, text: &str) {
+		let text = text.to_lowercase();
+		for id in 1..=self.types.len() as u32 {
+			let name = self.name(id);
+			if name.to_lowercase().contains(&text) {
+				println!("  {} #{}", name, id);
+			}
+		}
+	}
+
+	fn show(&mut self, id: u32) {
+		let ty = match self.get(id) {
+			Some(ty) => ty,
+			None => return println!("no type #{}", id),
+		};
+		println!("{}", self.reference(id));
+		match ty.def() {
+			TypeDef::Builtin(_) => println!("  builtin"),
+			TypeDef::Struct(r#struct) => {
+				println!("  struct");
+				for field in r#struct.fields() {
+					println!("    {}: {}", field.name(), self.reference(field.ty().id().get()));
+				}
+			}
+			TypeDef::TupleStruct(tuple_struct) => {
+				println!("  tuple struct");
+				for (position, field) in tuple_struct.fields().iter().enumerate() {
+					println!("    {}: {}", position, self.reference(field.ty().id().get()));
+				}
+			}
+			TypeDef::ClikeEnum(clike_enum) => {
+				println!("  C-like enum");
+				for variant in clike_enum.variants() {
+					println!("    {} = {}", variant.name(), variant.discriminant());
+				}
+			}
+			TypeDef::Enum(r#enum) => {
+				println!("  enum");
+				for variant in r#enum.variants() {
+					match variant {
+						EnumVariant::Unit(unit) => println!("    {}", unit.name()),
+						EnumVariant::Struct(r#struct) => {
+							println!("    {} {{", r#struct.name());
+							for field in r#struct.fields() {
+								println!("      {}: {}", field.name(), self.reference(field.ty().id().get()));
+							}
+							println!("    }}");
+						}
+						EnumVariant::TupleStruct(tuple_struct) => {
+							let fields = tuple_struct
+								.fields()
+								.iter()
+								.map(|field| self.reference(field.ty().id().get()))
+								.collect::<Vec<_>>();
+							println!("    {}({})", tuple_struct.name(), fields.join(", "));
+						}
+						_ => println!("    unsupported variant"),
+					}
+				}
+			}
+			TypeDef::Union(union) => {
+				println!("  union");
+				for field in union.fields() {
+					println!("    {}: {}", field.name(), self.reference(field.ty().id().get()));
+				}
+			}
+			_ => println!("  unsupported definition"),
+		}
+		if self.history.last() != Some(&id) {
+			self.history.push(id);
+		}
+	}
+
+	fn refs(&self, id: u32) {
+		for (index, ty) in self.types.iter().enumerate() {
+			if self.referred(ty).contains(&id) {
+				println!("  {}", self.reference(index as u32 + 1));
+			}
+		}
+	}
+
+	fn json(&self, id: u32) {
+		match self.get(id) {
+			Some(ty) => println!(
+				"{}",
+				serde_json::to_string_pretty(ty).expect("types are always serializable")
+			),
+			None => println!("no type #{}", id),
+		}
+	}
+
+	fn back(&mut self) {
+		self.history.pop();
+		match self.history.pop() {
+			Some(id) => self.show(id),
+			None => println!("no previously shown type"),
+		}
+	}
+}
+
+/// Returns a registry of a few types to browse if no registry is given.
+fn demo_registry() -> RegistryReadOnly {
+	let registry = tuple_meta_type!(
+		Option<u32>,
+		Result<Vec<u8>, String>,
+		BTreeMap<String, (bool, [u16; 4])>
+	)
+	.into_iter()
+	.collect::<Registry>();
+	let json = serde_json::to_string(&registry).expect("registries are always serializable");
+	serde_json::from_str(&json).expect("serialized registries are always deserializable")
+}
+
+fn main() {
+	let registry: RegistryReadOnly = match env::args().nth(1) {
+		Some(path) => {
+			let json = fs::read_to_string(&path).unwrap_or_else(|err| {
+				eprintln!("cannot read {}: {}", path, err);
+				process::exit(1)
+			});
+			serde_json::from_str(&json).unwrap_or_else(|err| {
+				eprintln!("cannot load {}: {}", path, err);
+				process::exit(1)
+			})
+		}
+		None => demo_registry(),
+	};
+	let types = registry.portable_types().unwrap_or_else(|| {
+		eprintln!("the registry refers to strings it does not contain");
+		process::exit(1)
+	});
+	let mut explorer = Explorer {
+		types,
+		history: Vec::new(),
+	};
+
+	println!("{} types, type `help` for commands", explorer.types.len());
+	let stdin = io::stdin();
+	loop {
+		print!("> ");
+		io::stdout().flush().expect("stdout is writable");
+		let mut line = String::new();
+		if stdin.lock().read_line(&mut line).expect("stdin is readable") == 0 {
+			break;
+		}
+		let mut words = line.trim().splitn(2, ' ');
+		let command = words.next().unwrap_or_default();
+		let argument = words.next().unwrap_or_default().trim();
+		let id = || argument.trim_start_matches('#').parse::<u32>().ok();
+		match (command, id()) {
+			("", _) => (),
+			("search", _) => explorer.search(argument),
+			("show", Some(id)) => explorer.show(id),
+			("refs", Some(id)) => explorer.refs(id),
+			("json", Some(id)) => explorer.json(id),
+			("show", None) | ("refs", None) | ("json", None) => {
+				println!("expected a type identifier, e.g. `{} 1`", command)
+			}
+			("back", _) => explorer.back(),
+			("help", _) => println!("{}", HELP),
+			("quit", _) | ("exit", _) => break,
+			_ => println!("unknown command `{}`, type `help` for commands", command),
+		}
+	}
+}
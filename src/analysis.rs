@@ -0,0 +1,200 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Analyses over type identifiers and definitions.
+//!
+//! These are meant for library authors auditing their type definitions,
+//! e.g. in order to find generic type parameters that are only used as
+//! markers and thus do not need to implement `Metadata`.
+
+use crate::tm_std::*;
+use crate::{EnumVariant, MetaType, TypeDef, TypeId};
+
+/// Describes how a generic type parameter is used by a type definition.
+///
+/// Usages are ordered by their strength so that the strongest usage
+/// across all fields can be determined via `max`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum TypeParamUsage {
+	/// The parameter does not appear in any field.
+	Unused,
+	/// The parameter only appears within `PhantomData` markers or skipped fields.
+	///
+	/// Such parameters are recorded as `PhantomData` markers by the type identifier
+	/// since they need not implement `Metadata`.
+	PhantomOnly,
+	/// The parameter appears in at least one field outside of `PhantomData` markers.
+	Field,
+}
+
+/// Reports how each generic type parameter of the given type is used by its fields.
+///
+/// The report follows the order of the type parameters of the type identifier.
+/// Types that are not custom types have no generic type parameters to report.
+///
+/// # Note
+///
+/// Type parameters are identified by the concrete types they are instantiated with.
+/// For a precise report instantiate the analyzed type with pairwise distinct types
+/// that do not otherwise appear in its fields.
+///
+/// # Example
+///
+/// ```
+/// # use type_metadata::{analysis::{type_param_usage, TypeParamUsage}, Metadata};
+/// let report = type_param_usage(&<Option<u32>>::meta_type());
+/// assert_eq!(report, vec![(u32::meta_type(), TypeParamUsage::Field)]);
+/// ```
+pub fn type_param_usage(ty: &MetaType) -> Vec<(MetaType, TypeParamUsage)> {
+	let type_params = match ty.type_id() {
+		TypeId::Custom(custom) => custom.type_params().to_vec(),
+		_ => return Vec::new(),
+	};
+	let field_types = field_types(&ty.type_def());
+	type_params
+		.into_iter()
+		.map(|param| {
+			if is_phantom_data(&param) {
+				return (param, TypeParamUsage::PhantomOnly);
+			}
+			let usage = field_types
+				.iter()
+				.map(|field_type| usage_in(field_type, &param))
+				.max()
+				.unwrap_or(TypeParamUsage::Unused);
+			(param, usage)
+		})
+		.collect()
+}
+
+/// Returns the types of all fields of the given type definition.
+fn field_types(def: &TypeDef) -> Vec<MetaType> {
+	match def {
+		TypeDef::Builtin(_) | TypeDef::ClikeEnum(_) => Vec::new(),
+		TypeDef::Struct(r#struct) => r#struct.fields().iter().map(|field| *field.ty()).collect(),
+		TypeDef::TupleStruct(tuple_struct) => tuple_struct.fields().iter().map(|field| *field.ty()).collect(),
+		TypeDef::Union(union) => union.fields().iter().map(|field| *field.ty()).collect(),
+		TypeDef::Enum(r#enum) => r#enum
+			.variants()
+			.iter()
+			.flat_map(|variant| match variant {
+				EnumVariant::Unit(_) => Vec::new(),
+				EnumVariant::Struct(r#struct) => r#struct.fields().iter().map(|field| *field.ty()).collect(),
+				EnumVariant::TupleStruct(tuple_struct) => {
+					tuple_struct.fields().iter().map(|field| *field.ty()).collect()
+				}
+			})
+			.collect(),
+	}
+}
+
+/// Returns `true` if the given type is a `PhantomData` marker.
+fn is_phantom_data(ty: &MetaType) -> bool {
+	match ty.type_id() {
+		TypeId::Custom(custom) => custom.namespace().segments().is_empty() && *custom.name() == "PhantomData",
+		_ => false,
+	}
+}
+
+/// Returns how the type parameter is used within the given field type.
+fn usage_in(field_type: &MetaType, param: &MetaType) -> TypeParamUsage {
+	let type_id = field_type.type_id();
+	// Also compare the type identifiers to see through types such as `Box<T>`
+	// that forward their type identifier to their contained type.
+	if field_type == param || type_id == param.type_id() {
+		return TypeParamUsage::Field;
+	}
+	let usage_in_all = |types: &[MetaType]| {
+		types
+			.iter()
+			.map(|ty| usage_in(ty, param))
+			.max()
+			.unwrap_or(TypeParamUsage::Unused)
+	};
+	match type_id {
+		TypeId::Custom(custom) => usage_in_all(custom.type_params()),
+		TypeId::Slice(slice) => usage_in(slice.type_param(), param),
+		TypeId::Array(array) => usage_in(&array.type_param, param),
+		TypeId::Tuple(tuple) => usage_in_all(&tuple.type_params),
+		TypeId::Primitive(_) => TypeParamUsage::Unused,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::*;
+
+	#[allow(unused)]
+	struct Generic<A, B, C> {
+		a: Vec<(bool, A)>,
+		b: PhantomData<B>,
+		// Not part of the type definition below.
+		c: PhantomData<fn() -> C>,
+	}
+
+	impl<A, B, C> HasTypeId for Generic<A, B, C>
+	where
+		A: Metadata + 'static,
+		B: 'static,
+		C: Metadata + 'static,
+	{
+		fn type_id() -> TypeId {
+			TypeIdCustom::new(
+				"Generic",
+				Namespace::from_module_path(module_path!()).unwrap(),
+				tuple_meta_type!(A, PhantomData<B>, C),
+			)
+			.into()
+		}
+	}
+
+	impl<A, B, C> HasTypeDef for Generic<A, B, C>
+	where
+		A: Metadata + 'static,
+		B: 'static,
+		C: Metadata + 'static,
+	{
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![
+				NamedField::of::<Vec<(bool, A)>>("a"),
+				NamedField::of::<PhantomData<B>>("b"),
+			])
+			.into()
+		}
+	}
+
+	#[test]
+	fn reports_usage_per_type_param() {
+		assert_eq!(
+			type_param_usage(&<Generic<u8, u16, u32>>::meta_type()),
+			vec![
+				(u8::meta_type(), TypeParamUsage::Field),
+				(<PhantomData<u16>>::meta_type(), TypeParamUsage::PhantomOnly),
+				(u32::meta_type(), TypeParamUsage::Unused),
+			]
+		);
+	}
+
+	#[test]
+	fn sees_through_forwarding_types() {
+		assert_eq!(
+			type_param_usage(&<Option<Box<u8>>>::meta_type()),
+			vec![(<Box<u8>>::meta_type(), TypeParamUsage::Field)]
+		);
+		assert_eq!(type_param_usage(&<[u8; 4]>::meta_type()), vec![]);
+	}
+}
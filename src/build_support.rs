@@ -0,0 +1,180 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers to emit metadata artifacts from within build scripts.
+//!
+//! A build script registers the root types of its crate and writes the
+//! resulting registry into `OUT_DIR` from where it can be included or
+//! shipped alongside the compiled artifacts.
+//!
+//! The registry is emitted as JSON, SCALE encoded with the suffix `.scale` and as its
+//! fingerprint, see `Registry::hash`, in hexadecimal with the suffix `.hash` appended to
+//! the file name, e.g. as `metadata.json`, `metadata.json.scale` and `metadata.json.hash`.
+//!
+//! # Note
+//!
+//! Artifacts are only rewritten if their contents actually changed so that
+//! emitting metadata does not dirty otherwise unchanged builds.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use type_metadata::{build_support, tuple_meta_type};
+//! // Within `build.rs`:
+//! build_support::emit_metadata("metadata.json", tuple_meta_type!(u32, Option<bool>))
+//! 	.expect("failed to emit metadata");
+//! ```
+
+use crate::{tabular::Tables, MetaType, Registry};
+use parity_scale_codec::Encode;
+use std::{
+	env, fs, io,
+	path::{Path, PathBuf},
+};
+
+/// Registers the given root types and writes the serialized registry as JSON
+/// into the file with the given name inside of `OUT_DIR`.
+///
+/// The SCALE encoded registry and its fingerprint are written next to it, see `emit_metadata_to`.
+/// Returns the path to the JSON artifact.
+///
+/// # Errors
+///
+/// - If `OUT_DIR` is not set, e.g. if not called from a build script.
+/// - If writing the artifact fails.
+pub fn emit_metadata<R>(file_name: &str, roots: R) -> io::Result<PathBuf>
+where
+	R: IntoIterator<Item = MetaType>,
+{
+	let out_dir = env::var_os("OUT_DIR").ok_or_else(|| {
+		io::Error::new(
+			io::ErrorKind::NotFound,
+			"`OUT_DIR` is not set, metadata artifacts can only be emitted from build scripts",
+		)
+	})?;
+	let path = Path::new(&out_dir).join(file_name);
+	emit_metadata_to(&path, roots)?;
+	Ok(path)
+}
+
+/// Registers the given root types and writes the serialized registry as JSON
+/// to the given path.
+///
+/// The SCALE encoded registry and its hexadecimal fingerprint are written to the
+/// same path with the suffixes `.scale` and `.hash` appended respectively. The file
+/// name is kept as is, e.g. `metadata.v1` is accompanied by `metadata.v1.scale`.
+///
+/// Returns `true` if any of the artifacts has been (re-)written and `false` if
+/// they all already existed with the exact same contents.
+pub fn emit_metadata_to<P, R>(path: P, roots: R) -> io::Result<bool>
+where
+	P: AsRef<Path>,
+	R: IntoIterator<Item = MetaType>,
+{
+	let path = path.as_ref();
+	let registry = roots.into_iter().collect::<Registry>();
+	let json = serde_json::to_vec(&registry)?;
+	let hash = registry
+		.hash()
+		.iter()
+		.map(|byte| format!("{:02x}", byte))
+		.collect::<String>();
+	let mut written = write_if_changed(path, &json)?;
+	written |= write_if_changed(&with_suffix(path, ".scale"), &registry.encode())?;
+	written |= write_if_changed(&with_suffix(path, ".hash"), hash.as_bytes())?;
+	Ok(written)
+}
+
+/// Appends the suffix to the file name of the path.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+	let mut path = path.as_os_str().to_owned();
+	path.push(suffix);
+	PathBuf::from(path)
+}
+
+/// Registers the given root types and writes their tables as CSV files
+/// into the given directory, see the `tabular` module.
+///
+/// Returns `true` if any of the files has been (re-)written.
+pub fn emit_tables_to<P, R>(dir: P, roots: R) -> io::Result<bool>
+where
+	P: AsRef<Path>,
+	R: IntoIterator<Item = MetaType>,
+{
+	let registry = roots.into_iter().collect::<Registry>();
+	fs::create_dir_all(dir.as_ref())?;
+	let mut written = false;
+	for (file_name, csv) in Tables::from_registry(&registry).to_csv() {
+		written |= write_if_changed(&dir.as_ref().join(file_name), csv.as_bytes())?;
+	}
+	Ok(written)
+}
+
+/// Writes the contents to the given path unless the file already has them.
+fn write_if_changed(path: &Path, contents: &[u8]) -> io::Result<bool> {
+	if let Ok(existing) = fs::read(path) {
+		if existing == contents {
+			return Ok(false);
+		}
+	}
+	fs::write(path, contents)?;
+	Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn only_writes_changed_artifacts() {
+		let path = env::temp_dir().join(format!("type_metadata_build_support_{}.v1", std::process::id()));
+		let scale_path = env::temp_dir().join(format!("type_metadata_build_support_{}.v1.scale", std::process::id()));
+		let hash_path = env::temp_dir().join(format!("type_metadata_build_support_{}.v1.hash", std::process::id()));
+		let _ = fs::remove_file(&path);
+
+		assert!(emit_metadata_to(&path, tuple_meta_type!(u32, bool)).unwrap());
+		assert!(!emit_metadata_to(&path, tuple_meta_type!(u32, bool)).unwrap());
+		assert!(emit_metadata_to(&path, tuple_meta_type!(u32)).unwrap());
+
+		let registry = tuple_meta_type!(u32).into_iter().collect::<Registry>();
+		assert_eq!(fs::read(&scale_path).unwrap(), registry.encode());
+		let hash = registry
+			.hash()
+			.iter()
+			.map(|byte| format!("{:02x}", byte))
+			.collect::<String>();
+		assert_eq!(fs::read_to_string(&hash_path).unwrap(), hash);
+
+		fs::remove_file(scale_path).unwrap();
+		fs::remove_file(hash_path).unwrap();
+		fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn emits_tables() {
+		let dir = env::temp_dir().join(format!("type_metadata_build_support_tables_{}", std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+
+		assert!(emit_tables_to(&dir, tuple_meta_type!(Option<u32>)).unwrap());
+		assert!(!emit_tables_to(&dir, tuple_meta_type!(Option<u32>)).unwrap());
+		assert_eq!(
+			fs::read_to_string(dir.join("variants.csv")).unwrap(),
+			"type_id,position,name,index\n1,0,None,\n1,1,Some,\n"
+		);
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
@@ -0,0 +1,358 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Canonical encodings of compacted types for hashing.
+
+use crate::tm_std::*;
+use crate::{
+	content_id::ContentId, form::CompactForm, interner::UntrackedSymbol, ConstParam, EnumVariant, NamedField, TypeDef,
+	TypeId, TypeIdDef, UnnamedField,
+};
+use blake2::{
+	digest::{consts::U32, Digest},
+	Blake2b,
+};
+
+/// BLAKE2b with a 256-bit digest.
+type Blake2b256 = Blake2b<U32>;
+
+/// Returns the content identifiers of the given types in their order.
+///
+/// The type at index `i` is the one with symbol identifier `i + 1`, likewise for the strings.
+pub(crate) fn content_ids<S: AsRef<str>>(strings: &[S], types: &[&TypeIdDef]) -> Vec<ContentId> {
+	(1..=types.len() as u32)
+		.map(|id| {
+			let mut encoder = Encoder::new(Purpose::ContentId, strings, types);
+			encoder.encode_type(id);
+			ContentId(encoder.hasher.finalize().into())
+		})
+		.collect()
+}
+
+/// Returns the fingerprint of the given strings, types and capabilities.
+///
+/// The type at index `i` is the one with symbol identifier `i + 1`, likewise for the strings.
+pub(crate) fn fingerprint<'a, S, C>(strings: &[S], types: &[&TypeIdDef], capabilities: C) -> [u8; 32]
+where
+	S: AsRef<str>,
+	C: IntoIterator<Item = (&'a str, &'a [UntrackedSymbol<AnyTypeId>])>,
+{
+	let mut encoder = Encoder::new(Purpose::Fingerprint, strings, types);
+	encoder.encode_len(strings.len());
+	for string in strings {
+		encoder.encode_bytes(string.as_ref().as_bytes());
+	}
+	encoder.encode_len(types.len());
+	for id in 1..=types.len() as u32 {
+		encoder.encode_type(id);
+	}
+	for (capability, tagged) in capabilities {
+		encoder.encode_u8(1);
+		encoder.encode_bytes(capability.as_bytes());
+		encoder.encode_len(tagged.len());
+		for symbol in tagged {
+			encoder.encode_type_ref(symbol);
+		}
+	}
+	encoder.encode_u8(0);
+	encoder.hasher.finalize().into()
+}
+
+/// What a canonical encoding is used for.
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Purpose {
+	/// Identifies a single type by its contents.
+	///
+	/// Referred types are encoded in place upon their first occurrence and by their
+	/// position afterwards. Documentation and deprecation notes are left out.
+	ContentId,
+	/// Identifies the contents of a whole registry.
+	///
+	/// Referred types are encoded by their symbols since all types are encoded in order.
+	Fingerprint,
+}
+
+/// Feeds the canonical encoding of types into a hasher.
+struct Encoder<'a, S> {
+	purpose: Purpose,
+	strings: &'a [S],
+	types: &'a [&'a TypeIdDef],
+	hasher: Blake2b256,
+	/// The types encoded so far in the order of their first occurrence.
+	encoded: Vec<u32>,
+}
+
+impl<'a, S: AsRef<str>> Encoder<'a, S> {
+	fn new(purpose: Purpose, strings: &'a [S], types: &'a [&'a TypeIdDef]) -> Self {
+		Self {
+			purpose,
+			strings,
+			types,
+			hasher: Blake2b256::new(),
+			encoded: Vec::new(),
+		}
+	}
+
+	fn encode_u8(&mut self, value: u8) {
+		self.hasher.update([value]);
+	}
+
+	fn encode_u64(&mut self, value: u64) {
+		self.hasher.update(value.to_le_bytes());
+	}
+
+	fn encode_len(&mut self, len: usize) {
+		self.encode_u64(len as u64);
+	}
+
+	fn encode_bytes(&mut self, bytes: &[u8]) {
+		self.encode_len(bytes.len());
+		self.hasher.update(bytes);
+	}
+
+	fn encode_option_u64(&mut self, value: Option<u64>) {
+		match value {
+			Some(value) => {
+				self.encode_u8(1);
+				self.encode_u64(value);
+			}
+			None => self.encode_u8(0),
+		}
+	}
+
+	fn encode_string(&mut self, symbol: &UntrackedSymbol<String>) {
+		let strings = self.strings;
+		match strings.get(symbol.id().get() as usize - 1) {
+			Some(string) => {
+				self.encode_u8(0);
+				self.encode_bytes(string.as_ref().as_bytes());
+			}
+			None => {
+				self.encode_u8(1);
+				self.encode_u64(u64::from(symbol.id().get()));
+			}
+		}
+	}
+
+	fn encode_type_ref(&mut self, symbol: &UntrackedSymbol<AnyTypeId>) {
+		match self.purpose {
+			Purpose::ContentId => self.encode_type(symbol.id().get()),
+			Purpose::Fingerprint => {
+				self.encode_u8(3);
+				self.encode_u64(u64::from(symbol.id().get()));
+			}
+		}
+	}
+
+	/// Encodes the type in place upon its first occurrence and by its position afterwards.
+	fn encode_type(&mut self, id: u32) {
+		if let Some(position) = self.encoded.iter().position(|encoded| *encoded == id) {
+			self.encode_u8(1);
+			self.encode_len(position);
+			return;
+		}
+		let types = self.types;
+		match types.get(id as usize - 1) {
+			Some(type_id_def) => {
+				self.encoded.push(id);
+				self.encode_u8(0);
+				self.encode_type_id(type_id_def.id());
+				self.encode_type_def(type_id_def.def());
+			}
+			None => {
+				self.encode_u8(2);
+				self.encode_u64(u64::from(id));
+			}
+		}
+	}
+
+	fn encode_type_id(&mut self, type_id: &TypeId<CompactForm>) {
+		match type_id {
+			TypeId::Custom(custom) => {
+				self.encode_u8(0);
+				self.encode_string(custom.name());
+				self.encode_len(custom.namespace().segments().len());
+				for segment in custom.namespace().segments() {
+					self.encode_string(segment);
+				}
+				self.encode_len(custom.type_params().len());
+				for param in custom.type_params() {
+					self.encode_type_ref(param);
+				}
+				self.encode_len(custom.const_params().len());
+				for param in custom.const_params() {
+					match *param {
+						ConstParam::Unsigned(value) => {
+							self.encode_u8(0);
+							self.hasher.update(value.to_le_bytes());
+						}
+						ConstParam::Signed(value) => {
+							self.encode_u8(1);
+							self.hasher.update(value.to_le_bytes());
+						}
+					}
+				}
+			}
+			TypeId::Slice(slice) => {
+				self.encode_u8(1);
+				self.encode_type_ref(slice.type_param());
+			}
+			TypeId::Array(array) => {
+				self.encode_u8(2);
+				self.encode_u64(array.len);
+				self.encode_type_ref(&array.type_param);
+			}
+			TypeId::Tuple(tuple) => {
+				self.encode_u8(3);
+				self.encode_len(tuple.type_params.len());
+				for param in &tuple.type_params {
+					self.encode_type_ref(param);
+				}
+			}
+			TypeId::Primitive(primitive) => {
+				self.encode_u8(4);
+				self.encode_bytes(primitive.name().as_bytes());
+			}
+		}
+	}
+
+	fn encode_type_def(&mut self, type_def: &TypeDef<CompactForm>) {
+		match type_def {
+			TypeDef::Builtin(_) => self.encode_u8(0),
+			TypeDef::Struct(r#struct) => {
+				self.encode_u8(1);
+				self.encode_named_fields(r#struct.fields());
+				self.encode_docs(r#struct.docs());
+				self.encode_deprecated(r#struct.deprecated());
+			}
+			TypeDef::TupleStruct(tuple_struct) => {
+				self.encode_u8(2);
+				self.encode_unnamed_fields(tuple_struct.fields());
+				self.encode_docs(tuple_struct.docs());
+				self.encode_deprecated(tuple_struct.deprecated());
+			}
+			TypeDef::ClikeEnum(clike_enum) => {
+				self.encode_u8(3);
+				self.encode_len(clike_enum.variants().len());
+				for variant in clike_enum.variants() {
+					self.encode_string(variant.name());
+					self.encode_u64(variant.discriminant());
+					self.encode_docs(variant.docs());
+					self.encode_deprecated(variant.deprecated());
+				}
+				match clike_enum.discriminant_type() {
+					Some(discriminant_type) => {
+						self.encode_u8(1);
+						self.encode_type_ref(discriminant_type);
+					}
+					None => self.encode_u8(0),
+				}
+				self.encode_reserved_indices(clike_enum.reserved_indices());
+				self.encode_docs(clike_enum.docs());
+				self.encode_deprecated(clike_enum.deprecated());
+			}
+			TypeDef::Enum(r#enum) => {
+				self.encode_u8(4);
+				self.encode_len(r#enum.variants().len());
+				for variant in r#enum.variants() {
+					match variant {
+						EnumVariant::Unit(unit) => {
+							self.encode_u8(0);
+							self.encode_string(unit.name());
+							self.encode_option_u64(unit.index());
+							self.encode_docs(unit.docs());
+							self.encode_deprecated(unit.deprecated());
+						}
+						EnumVariant::Struct(r#struct) => {
+							self.encode_u8(1);
+							self.encode_string(r#struct.name());
+							self.encode_option_u64(r#struct.index());
+							self.encode_named_fields(r#struct.fields());
+							self.encode_docs(r#struct.docs());
+							self.encode_deprecated(r#struct.deprecated());
+						}
+						EnumVariant::TupleStruct(tuple_struct) => {
+							self.encode_u8(2);
+							self.encode_string(tuple_struct.name());
+							self.encode_option_u64(tuple_struct.index());
+							self.encode_unnamed_fields(tuple_struct.fields());
+							self.encode_docs(tuple_struct.docs());
+							self.encode_deprecated(tuple_struct.deprecated());
+						}
+					}
+				}
+				self.encode_reserved_indices(r#enum.reserved_indices());
+				self.encode_docs(r#enum.docs());
+				self.encode_deprecated(r#enum.deprecated());
+			}
+			TypeDef::Union(union) => {
+				self.encode_u8(5);
+				self.encode_named_fields(union.fields());
+				self.encode_docs(union.docs());
+				self.encode_deprecated(union.deprecated());
+			}
+		}
+	}
+
+	fn encode_named_fields(&mut self, fields: &[NamedField<CompactForm>]) {
+		self.encode_len(fields.len());
+		for field in fields {
+			self.encode_string(field.name());
+			self.encode_type_ref(field.ty());
+			self.encode_u8(field.is_compact() as u8);
+			self.encode_docs(field.docs());
+			self.encode_deprecated(field.deprecated());
+		}
+	}
+
+	fn encode_unnamed_fields(&mut self, fields: &[UnnamedField<CompactForm>]) {
+		self.encode_len(fields.len());
+		for field in fields {
+			self.encode_type_ref(field.ty());
+			self.encode_u8(field.is_compact() as u8);
+		}
+	}
+
+	fn encode_reserved_indices(&mut self, indices: &[u64]) {
+		self.encode_len(indices.len());
+		for index in indices {
+			self.encode_u64(*index);
+		}
+	}
+
+	/// Encodes the documentation of an item when fingerprinting.
+	fn encode_docs(&mut self, docs: &[UntrackedSymbol<String>]) {
+		if self.purpose == Purpose::Fingerprint {
+			self.encode_len(docs.len());
+			for doc in docs {
+				self.encode_string(doc);
+			}
+		}
+	}
+
+	/// Encodes the deprecation note of an item when fingerprinting.
+	fn encode_deprecated(&mut self, deprecated: Option<&UntrackedSymbol<String>>) {
+		if self.purpose == Purpose::Fingerprint {
+			match deprecated {
+				Some(note) => {
+					self.encode_u8(1);
+					self.encode_string(note);
+				}
+				None => self.encode_u8(0),
+			}
+		}
+	}
+}
@@ -0,0 +1,111 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Content-addressed type identifiers.
+//!
+//! Symbols identify types by the order of their registration, so the same type
+//! may have different symbols in different registries. Content identifiers are
+//! instead derived from the types themselves and thus survive reordering and
+//! merging of registries.
+//!
+//! The content identifier of a type is the BLAKE2b-256 digest of a canonical
+//! encoding of its identifier and definition including all types it refers to.
+//! Referred types are encoded in place upon their first occurrence and referred
+//! to by the position of their occurrence afterwards, so recursive types have
+//! content identifiers as well.
+//!
+//! Only properties affecting the encoding of a type are taken into account,
+//! whereas documentation and deprecation notes are not.
+//!
+//! # Example
+//!
+//! ```
+//! # use type_metadata::{Metadata, Registry};
+//! let mut registry = Registry::new();
+//! let symbol = registry.register_type(&Option::<u32>::meta_type());
+//!
+//! let mut other = Registry::new();
+//! other.register_type(&u64::meta_type());
+//! let other_symbol = other.register_type(&Option::<u32>::meta_type());
+//!
+//! assert_ne!(symbol, other_symbol);
+//! assert_eq!(
+//! 	registry.content_ids()[symbol.id().get() as usize - 1],
+//! 	other.content_ids()[other_symbol.id().get() as usize - 1],
+//! );
+//! ```
+//!
+//! # Compile-time identifiers
+//!
+//! Content identifiers are computed at runtime via `ContentId::of` and should be
+//! cached by hot paths, e.g. in a lazily initialized static. They cannot be emitted
+//! as constants by the derive: a constant per type would have to be computed from the
+//! constants of its field types, which is a cycle for recursive types that the compiler
+//! rejects, and type definitions themselves are built by non-const trait methods.
+
+use crate::tm_std::*;
+use crate::{Metadata, Registry};
+use serde::Serialize;
+
+/// A content-addressed type identifier.
+///
+/// Serialized and displayed as the hexadecimal representation of its bytes.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct ContentId(pub(crate) [u8; 32]);
+
+impl ContentId {
+	/// Returns the content identifier of the given type.
+	///
+	/// The type and all types it refers to are registered into a temporary registry,
+	/// so the content identifier should be computed once and cached by hot paths.
+	pub fn of<T>() -> Self
+	where
+		T: Metadata + ?Sized,
+	{
+		let mut registry = Registry::new();
+		let symbol = registry.register_type(&T::meta_type());
+		registry.content_ids()[symbol.id().get() as usize - 1]
+	}
+
+	/// Returns the bytes of the content identifier.
+	pub fn as_bytes(&self) -> &[u8; 32] {
+		&self.0
+	}
+}
+
+impl core::fmt::Display for ContentId {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		for byte in &self.0 {
+			write!(f, "{:02x}", byte)?;
+		}
+		Ok(())
+	}
+}
+
+impl Debug for ContentId {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		write!(f, "ContentId({})", self)
+	}
+}
+
+impl Serialize for ContentId {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		serializer.collect_str(self)
+	}
+}
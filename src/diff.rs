@@ -0,0 +1,540 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Differences between the types of two registries.
+//!
+//! Since type symbols are only meaningful within their registry, types are
+//! matched by their names, i.e. their type identifiers rendered as Rust paths
+//! such as `my_chain::Balance<u32>` or `[u8; 32]`.
+//!
+//! Only changes affecting the encoding of a type are reported, whereas changes
+//! of documentation and deprecation notes are not.
+//!
+//! # Example
+//!
+//! ```
+//! # use type_metadata::{tuple_meta_type, Registry};
+//! let old = tuple_meta_type!(Option<u32>).into_iter().collect::<Registry>();
+//! let new = tuple_meta_type!(Option<u64>).into_iter().collect::<Registry>();
+//! let diff = old.diff(&new);
+//! assert_eq!(diff.removed, vec!["Option<u32>", "u32"]);
+//! assert_eq!(diff.added, vec!["Option<u64>", "u64"]);
+//! assert!(diff.changed.is_empty());
+//! ```
+//!
+//! # Accepted changes
+//!
+//! Intentional breaking changes of an upgrade can be recorded as `AcceptedChanges`,
+//! e.g. in a JSON file committed along with the upgrade, so that only unexpected
+//! differences are reported by `RegistryDiff::unexpected`:
+//!
+//! ```json
+//! {
+//!   "removed": ["u32"],
+//!   "changed": [
+//!     { "name": "chain::Account", "changes": [{ "field_added": { "variant": null, "field": "reserved" } }] },
+//!     { "name": "chain::Event" }
+//!   ]
+//! }
+//! ```
+
+use crate::tm_std::*;
+use crate::{
+	form::CompactForm, interner::UntrackedSymbol, EnumVariant, NamedField, Registry, TypeDef, TypeDefKind, TypeId,
+	UnnamedField,
+};
+use core::fmt::Write;
+use serde::{Deserialize, Serialize};
+
+/// The differences between the types of two registries.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct RegistryDiff {
+	/// The names of the types only registered in the new registry.
+	pub added: Vec<String>,
+	/// The names of the types only registered in the old registry.
+	pub removed: Vec<String>,
+	/// The types registered in both registries whose definitions differ.
+	pub changed: Vec<TypeDiff>,
+}
+
+/// The changes of the definition of a type.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct TypeDiff {
+	/// The name of the type.
+	pub name: String,
+	/// The changes of the definition in the order of the old definition.
+	pub changes: Vec<Change>,
+}
+
+/// A change of a type definition.
+///
+/// Fields and variants are identified by their names. Unnamed fields are named
+/// by their positions. The variant a field belongs to is `None` for structs,
+/// tuple-structs and unions.
+///
+/// Changes are serialized in snake case, e.g. `{ "variant_added": { "variant": "Transfer" } }`.
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+	/// The kind of the definition changed, e.g. from a struct to an enum.
+	Kind {
+		/// The old kind of the definition.
+		from: TypeDefKind,
+		/// The new kind of the definition.
+		to: TypeDefKind,
+	},
+	/// A field has been added.
+	FieldAdded {
+		/// The variant of the field, if any.
+		variant: Option<String>,
+		/// The name of the field.
+		field: String,
+	},
+	/// A field has been removed.
+	FieldRemoved {
+		/// The variant of the field, if any.
+		variant: Option<String>,
+		/// The name of the field.
+		field: String,
+	},
+	/// A named field has been moved to another position.
+	FieldMoved {
+		/// The variant of the field, if any.
+		variant: Option<String>,
+		/// The name of the field.
+		field: String,
+		/// The old position of the field.
+		from: usize,
+		/// The new position of the field.
+		to: usize,
+	},
+	/// The type of a field changed.
+	FieldType {
+		/// The variant of the field, if any.
+		variant: Option<String>,
+		/// The name of the field.
+		field: String,
+		/// The name of the old type of the field.
+		from: String,
+		/// The name of the new type of the field.
+		to: String,
+	},
+	/// A field has been switched from or to the SCALE compact encoding.
+	FieldCompact {
+		/// The variant of the field, if any.
+		variant: Option<String>,
+		/// The name of the field.
+		field: String,
+		/// Whether the field is now encoded using the SCALE compact encoding.
+		compact: bool,
+	},
+	/// A variant has been added.
+	VariantAdded {
+		/// The name of the variant.
+		variant: String,
+	},
+	/// A variant has been removed.
+	VariantRemoved {
+		/// The name of the variant.
+		variant: String,
+	},
+	/// The index of an enum variant or the discriminant of a C-like enum variant changed.
+	VariantIndex {
+		/// The name of the variant.
+		variant: String,
+		/// The old index of the variant.
+		from: u64,
+		/// The new index of the variant.
+		to: u64,
+	},
+	/// The discriminant type of a C-like enum changed.
+	DiscriminantType {
+		/// The name of the old discriminant type, if any.
+		from: Option<String>,
+		/// The name of the new discriminant type, if any.
+		to: Option<String>,
+	},
+}
+
+impl RegistryDiff {
+	/// Computes the differences between the old and the new registry.
+	///
+	/// Added and changed types are reported in the order of their registration.
+	pub fn between(old: &Registry, new: &Registry) -> Self {
+		let old_types = named_types(old);
+		let new_types = named_types(new);
+		let mut diff = Self::default();
+		for (name, old_def) in &old_types {
+			match new_types.iter().find(|(new_name, _)| new_name == name) {
+				Some((_, new_def)) => {
+					let changes = diff_defs(old, old_def, new, new_def);
+					if !changes.is_empty() {
+						diff.changed.push(TypeDiff {
+							name: name.clone(),
+							changes,
+						});
+					}
+				}
+				None => diff.removed.push(name.clone()),
+			}
+		}
+		for (name, _) in &new_types {
+			if !old_types.iter().any(|(old_name, _)| old_name == name) {
+				diff.added.push(name.clone());
+			}
+		}
+		diff
+	}
+
+	/// Returns `true` if both registries define the same types.
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+	}
+
+	/// Returns the differences that are not accepted by the given accepted changes.
+	///
+	/// Types whose changes are all accepted are omitted from the changed types.
+	/// The result is empty if all differences are accepted.
+	pub fn unexpected(&self, accepted: &AcceptedChanges) -> Self {
+		let changed = self
+			.changed
+			.iter()
+			.filter_map(|type_diff| {
+				let accepted = accepted.changed.iter().find(|accepted| accepted.name == type_diff.name);
+				let changes = match accepted {
+					Some(AcceptedTypeChanges { changes: None, .. }) => return None,
+					Some(AcceptedTypeChanges {
+						changes: Some(changes), ..
+					}) => type_diff
+						.changes
+						.iter()
+						.filter(|change| !changes.contains(change))
+						.cloned()
+						.collect(),
+					None => type_diff.changes.clone(),
+				};
+				if changes.is_empty() {
+					None
+				} else {
+					Some(TypeDiff {
+						name: type_diff.name.clone(),
+						changes,
+					})
+				}
+			})
+			.collect();
+		Self {
+			added: unexpected_names(&self.added, &accepted.added),
+			removed: unexpected_names(&self.removed, &accepted.removed),
+			changed,
+		}
+	}
+}
+
+/// Intentional differences between two registries, e.g. of a specific upgrade.
+///
+/// Accepted changes are meant to be loaded from a file committed along with the
+/// upgrade, see the module documentation for an example in JSON. All lists may be
+/// omitted, in which case nothing is accepted.
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize, Debug, Default)]
+pub struct AcceptedChanges {
+	/// The names of the types that are accepted to be added.
+	#[serde(default)]
+	pub added: Vec<String>,
+	/// The names of the types that are accepted to be removed.
+	#[serde(default)]
+	pub removed: Vec<String>,
+	/// The accepted changes of the definitions of types.
+	#[serde(default)]
+	pub changed: Vec<AcceptedTypeChanges>,
+}
+
+/// The accepted changes of the definition of a type.
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize, Debug)]
+pub struct AcceptedTypeChanges {
+	/// The name of the type.
+	pub name: String,
+	/// The accepted changes, or `None` if all changes of the type are accepted.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub changes: Option<Vec<Change>>,
+}
+
+/// Returns the names that are not accepted.
+fn unexpected_names(names: &[String], accepted: &[String]) -> Vec<String> {
+	names.iter().filter(|name| !accepted.contains(name)).cloned().collect()
+}
+
+/// Returns all types of the registry together with their names.
+fn named_types(registry: &Registry) -> Vec<(String, &TypeDef<CompactForm>)> {
+	registry
+		.types()
+		.map(|(_, type_id, type_def)| (type_id_name(registry, type_id), type_def))
+		.collect()
+}
+
+/// Returns the name of the type referred to by the symbol.
+fn type_name(registry: &Registry, symbol: &UntrackedSymbol<AnyTypeId>) -> String {
+	match registry.resolve_type_id(symbol) {
+		Some(type_id) => type_id_name(registry, type_id),
+		None => String::from("?"),
+	}
+}
+
+/// Renders the type identifier as a Rust path, e.g. `my_chain::Balance<u32>`.
+fn type_id_name(registry: &Registry, type_id: &TypeId<CompactForm>) -> String {
+	let resolve = |symbol| registry.resolve_string(symbol).unwrap_or("?");
+	let mut name = String::new();
+	match type_id {
+		TypeId::Custom(custom) => {
+			for segment in custom.namespace().segments() {
+				name.push_str(resolve(segment));
+				name.push_str("::");
+			}
+			name.push_str(resolve(custom.name()));
+			let mut params = custom
+				.type_params()
+				.iter()
+				.map(|param| type_name(registry, param))
+				.collect::<Vec<_>>();
+			params.extend(custom.const_params().iter().map(ToString::to_string));
+			if !params.is_empty() {
+				let _ = write!(name, "<{}>", params.join(", "));
+			}
+		}
+		TypeId::Slice(slice) => {
+			let _ = write!(name, "[{}]", type_name(registry, slice.type_param()));
+		}
+		TypeId::Array(array) => {
+			let _ = write!(name, "[{}; {}]", type_name(registry, &array.type_param), array.len);
+		}
+		TypeId::Tuple(tuple) => {
+			let params = tuple
+				.type_params
+				.iter()
+				.map(|param| type_name(registry, param))
+				.collect::<Vec<_>>();
+			match params.len() {
+				1 => {
+					let _ = write!(name, "({},)", params[0]);
+				}
+				_ => {
+					let _ = write!(name, "({})", params.join(", "));
+				}
+			}
+		}
+		TypeId::Primitive(primitive) => name.push_str(primitive.name()),
+	}
+	name
+}
+
+/// A field reduced to the properties affecting its encoding.
+struct FieldInfo {
+	name: String,
+	ty: String,
+	compact: bool,
+}
+
+impl FieldInfo {
+	fn named(registry: &Registry, fields: &[NamedField<CompactForm>]) -> Vec<Self> {
+		fields
+			.iter()
+			.map(|field| Self {
+				name: String::from(registry.resolve_string(field.name()).unwrap_or("?")),
+				ty: type_name(registry, field.ty()),
+				compact: field.is_compact(),
+			})
+			.collect()
+	}
+
+	fn unnamed(registry: &Registry, fields: &[UnnamedField<CompactForm>]) -> Vec<Self> {
+		fields
+			.iter()
+			.enumerate()
+			.map(|(position, field)| Self {
+				name: position.to_string(),
+				ty: type_name(registry, field.ty()),
+				compact: field.is_compact(),
+			})
+			.collect()
+	}
+}
+
+/// A variant reduced to the properties affecting its encoding.
+struct VariantInfo {
+	name: String,
+	index: u64,
+	fields: Vec<FieldInfo>,
+}
+
+impl VariantInfo {
+	/// Returns the variants of the definition, which has none if it is neither an enum nor a C-like enum.
+	fn of(registry: &Registry, def: &TypeDef<CompactForm>) -> Vec<Self> {
+		let resolve = |symbol| String::from(registry.resolve_string(symbol).unwrap_or("?"));
+		match def {
+			TypeDef::ClikeEnum(clike_enum) => clike_enum
+				.variants()
+				.iter()
+				.map(|variant| Self {
+					name: resolve(variant.name()),
+					index: variant.discriminant(),
+					fields: Vec::new(),
+				})
+				.collect(),
+			TypeDef::Enum(r#enum) => r#enum
+				.variants()
+				.iter()
+				.enumerate()
+				.map(|(position, variant)| {
+					// Variants without an explicit index are indexed by their position.
+					let (name, index, fields) = match variant {
+						EnumVariant::Unit(unit) => (unit.name(), unit.index(), Vec::new()),
+						EnumVariant::Struct(r#struct) => (
+							r#struct.name(),
+							r#struct.index(),
+							FieldInfo::named(registry, r#struct.fields()),
+						),
+						EnumVariant::TupleStruct(tuple_struct) => (
+							tuple_struct.name(),
+							tuple_struct.index(),
+							FieldInfo::unnamed(registry, tuple_struct.fields()),
+						),
+					};
+					Self {
+						name: resolve(name),
+						index: index.unwrap_or(position as u64),
+						fields,
+					}
+				})
+				.collect(),
+			_ => Vec::new(),
+		}
+	}
+}
+
+/// Returns the fields of the definition, which has none if it is an enum.
+fn fields_of(registry: &Registry, def: &TypeDef<CompactForm>) -> Vec<FieldInfo> {
+	match def {
+		TypeDef::Struct(r#struct) => FieldInfo::named(registry, r#struct.fields()),
+		TypeDef::TupleStruct(tuple_struct) => FieldInfo::unnamed(registry, tuple_struct.fields()),
+		TypeDef::Union(union) => FieldInfo::named(registry, union.fields()),
+		_ => Vec::new(),
+	}
+}
+
+/// Returns the changes between the old and the new definition of a type.
+fn diff_defs(
+	old: &Registry,
+	old_def: &TypeDef<CompactForm>,
+	new: &Registry,
+	new_def: &TypeDef<CompactForm>,
+) -> Vec<Change> {
+	let mut changes = Vec::new();
+	if old_def.kind() != new_def.kind() {
+		changes.push(Change::Kind {
+			from: old_def.kind(),
+			to: new_def.kind(),
+		});
+		return changes;
+	}
+	if let (TypeDef::ClikeEnum(old_enum), TypeDef::ClikeEnum(new_enum)) = (old_def, new_def) {
+		let from = old_enum.discriminant_type().map(|ty| type_name(old, ty));
+		let to = new_enum.discriminant_type().map(|ty| type_name(new, ty));
+		if from != to {
+			changes.push(Change::DiscriminantType { from, to });
+		}
+	}
+	diff_fields(&mut changes, None, &fields_of(old, old_def), &fields_of(new, new_def));
+	let old_variants = VariantInfo::of(old, old_def);
+	let new_variants = VariantInfo::of(new, new_def);
+	for old_variant in &old_variants {
+		match new_variants.iter().find(|variant| variant.name == old_variant.name) {
+			Some(new_variant) => {
+				if old_variant.index != new_variant.index {
+					changes.push(Change::VariantIndex {
+						variant: old_variant.name.clone(),
+						from: old_variant.index,
+						to: new_variant.index,
+					});
+				}
+				diff_fields(
+					&mut changes,
+					Some(&old_variant.name),
+					&old_variant.fields,
+					&new_variant.fields,
+				);
+			}
+			None => changes.push(Change::VariantRemoved {
+				variant: old_variant.name.clone(),
+			}),
+		}
+	}
+	for new_variant in &new_variants {
+		if !old_variants.iter().any(|variant| variant.name == new_variant.name) {
+			changes.push(Change::VariantAdded {
+				variant: new_variant.name.clone(),
+			});
+		}
+	}
+	changes
+}
+
+/// Pushes the changes between the old and the new fields of a type or variant.
+fn diff_fields(changes: &mut Vec<Change>, variant: Option<&String>, old: &[FieldInfo], new: &[FieldInfo]) {
+	for (old_position, old_field) in old.iter().enumerate() {
+		let found = new.iter().enumerate().find(|(_, field)| field.name == old_field.name);
+		let (new_position, new_field) = match found {
+			Some(found) => found,
+			None => {
+				changes.push(Change::FieldRemoved {
+					variant: variant.cloned(),
+					field: old_field.name.clone(),
+				});
+				continue;
+			}
+		};
+		if old_position != new_position {
+			changes.push(Change::FieldMoved {
+				variant: variant.cloned(),
+				field: old_field.name.clone(),
+				from: old_position,
+				to: new_position,
+			});
+		}
+		if old_field.ty != new_field.ty {
+			changes.push(Change::FieldType {
+				variant: variant.cloned(),
+				field: old_field.name.clone(),
+				from: old_field.ty.clone(),
+				to: new_field.ty.clone(),
+			});
+		}
+		if old_field.compact != new_field.compact {
+			changes.push(Change::FieldCompact {
+				variant: variant.cloned(),
+				field: old_field.name.clone(),
+				compact: new_field.compact,
+			});
+		}
+	}
+	for new_field in new {
+		if !old.iter().any(|field| field.name == new_field.name) {
+			changes.push(Change::FieldAdded {
+				variant: variant.cloned(),
+				field: new_field.name.clone(),
+			});
+		}
+	}
+}
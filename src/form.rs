@@ -29,12 +29,33 @@
 //! can no longer be used in order to retrieve information from the
 //! original registry easily. Its sole purpose is for compact serialization.
 //!
+//! The portable form is `PortableForm` and owns its strings instead of referring
+//! to them by symbols. It does not depend on `'static` strings and can thus be
+//! constructed at runtime, e.g. by deserializing metadata produced by a parser or
+//! received over FFI. Types are converted between the compact and the portable
+//! form via `Registry::portable_types` and `RegistryReadOnly::from_portable_types`.
+//!
+//! The display form is `DisplayForm` and inlines the full definitions of all
+//! referred types instead of referring to them by symbols. It is produced by
+//! `Registry::expand` for debugging and for consumers that do not want to chase
+//! symbols through the registry. Recursive types are kept finite by reference
+//! nodes, which refer back to their labelled enclosing types. Standalone type definitions are converted between
+//! the compact and the display form via `Registry::expand_type_def` and
+//! `Registry::compact_type_def`.
+//!
+//! The frozen form is `FrozenForm` and refers to its strings by their offsets
+//! and lengths within a single string buffer, see `RegistryFrozen`. The buffer
+//! can also be kept apart from the types, e.g. memory-mapped on embedded devices.
+//!
 //! Other forms, such as a compact form that is still bound to the registry
 //! (also via lifetime tracking) are possible but current not needed.
 
 use crate::tm_std::*;
-use crate::{interner::UntrackedSymbol, meta_type::MetaType};
-use serde::Serialize;
+use crate::{content_id::ContentId, interner::UntrackedSymbol, meta_type::MetaType, registry::TypeIdDef};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "codec")]
+use parity_scale_codec::{Decode, Encode};
 
 /// Trait to control the internal structures of type identifiers and definitions.
 ///
@@ -57,11 +78,14 @@ pub trait Form {
 ///
 /// Allows to be converted into other forms such as compact form
 /// through the registry and `IntoCompact`.
+///
+/// Strings are either static literals, e.g. as emitted by the derive macros, or
+/// built at runtime, e.g. upon dynamic registration. Constructors accept both.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Debug)]
 pub enum MetaForm {}
 
 impl Form for MetaForm {
-	type String = &'static str;
+	type String = Cow<'static, str>;
 	type TypeId = MetaType;
 	type IndirectTypeId = MetaType;
 }
@@ -73,11 +97,172 @@ impl Form for MetaForm {
 /// This resolves some lifetime issues with self-referential structs (such as
 /// the registry itself) but can no longer be used to resolve to the original
 /// underlying data.
+///
+/// Compact types are fully owned and free of lifetimes. They can be deserialized
+/// and, with the `codec` feature, decoded again, e.g. as part of a `RegistryReadOnly`.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Debug)]
 pub enum CompactForm {}
 
 impl Form for CompactForm {
-	type String = UntrackedSymbol<&'static str>;
+	type String = UntrackedSymbol<String>;
+	type TypeId = UntrackedSymbol<AnyTypeId>;
+	type IndirectTypeId = Self::TypeId;
+}
+
+/// Portable form that owns its strings.
+///
+/// Type identifiers refer to the types of the same registry by their symbols
+/// just as within the compact form.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Debug)]
+pub enum PortableForm {}
+
+impl Form for PortableForm {
+	type String = String;
 	type TypeId = UntrackedSymbol<AnyTypeId>;
 	type IndirectTypeId = Self::TypeId;
 }
+
+/// Frozen form that refers to its strings within a single string buffer.
+///
+/// Type identifiers refer to the types of the same registry by their symbols
+/// just as within the compact form.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Debug)]
+pub enum FrozenForm {}
+
+impl Form for FrozenForm {
+	type String = StrRef;
+	type TypeId = UntrackedSymbol<AnyTypeId>;
+	type IndirectTypeId = Self::TypeId;
+}
+
+/// A string of the frozen form given by its byte offset and length within the string buffer.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+pub struct StrRef {
+	/// The byte offset of the string within the buffer.
+	offset: u32,
+	/// The byte length of the string.
+	len: u32,
+}
+
+impl StrRef {
+	/// Creates a reference to the string at the given byte offset with the given byte length.
+	pub fn new(offset: u32, len: u32) -> Self {
+		Self { offset, len }
+	}
+
+	/// Returns the byte offset of the string within the buffer.
+	pub fn offset(&self) -> u32 {
+		self.offset
+	}
+
+	/// Returns the byte length of the string.
+	pub fn len(&self) -> u32 {
+		self.len
+	}
+
+	/// Returns `true` if the string is empty.
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Returns the string within the given buffer.
+	///
+	/// Returns `None` if the string is out of bounds of the buffer or does not
+	/// start or end at a character boundary.
+	pub fn resolve<'a>(&self, buffer: &'a str) -> Option<&'a str> {
+		let start = self.offset as usize;
+		buffer.get(start..start.checked_add(self.len as usize)?)
+	}
+}
+
+/// Display form that inlines the definitions of all referred types.
+///
+/// Recursive types are inlined up to their first recurrence which refers back
+/// to the enclosing expansion instead, see `Expanded`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Debug)]
+pub enum DisplayForm {}
+
+impl Form for DisplayForm {
+	type String = String;
+	type TypeId = Expanded;
+	type IndirectTypeId = Self::TypeId;
+}
+
+/// A type referred to within the display form.
+///
+/// Types are labelled by their content identifiers, see the `content_id` module,
+/// which are stable across registries. Only types that are referred back to by
+/// a recurrence carry their label, which is serialized as `$id`, whereas recurrences
+/// serialize the label of the type they refer to as `$ref`. Recurrences of the type
+/// passed to `Registry::expand` itself refer to its content identifier.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
+#[serde(untagged)]
+pub enum Expanded {
+	/// The inlined identifier and definition of the type.
+	Type(Box<TypeIdDef<DisplayForm>>),
+	/// The inlined identifier and definition of a type that recurs within its expansion.
+	Labelled {
+		/// The label of the type.
+		#[serde(rename = "$id")]
+		label: ContentId,
+		/// The identifier and definition of the type.
+		#[serde(flatten)]
+		type_id_def: Box<TypeIdDef<DisplayForm>>,
+	},
+	/// A recurrence of an enclosing type that is already being expanded.
+	Recursive {
+		/// The label of the type.
+		#[serde(rename = "$ref")]
+		label: ContentId,
+		/// The number of expansions to go up in order to reach the type,
+		/// where `1` refers to the directly enclosing expansion.
+		#[serde(rename = "recursive")]
+		depth: usize,
+	},
+}
+
+/// Maps the strings and type identifiers of the form `F` onto the form `G`.
+pub(crate) trait FormMap<F: Form, G: Form> {
+	/// Maps the given string.
+	fn map_string(&mut self, string: F::String) -> G::String;
+
+	/// Maps the given type identifier.
+	fn map_type_id(&mut self, type_id: F::TypeId) -> G::TypeId;
+
+	/// Maps the given type identifier with indirection.
+	fn map_indirect_type_id(&mut self, type_id: F::IndirectTypeId) -> G::IndirectTypeId;
+}
+
+/// Maps only the strings between two forms sharing their type identifiers.
+///
+/// Used to convert between the `CompactForm`, the `PortableForm` and the `FrozenForm`.
+pub(crate) struct MapStrings<M>(pub M);
+
+impl<F, G, M> FormMap<F, G> for MapStrings<M>
+where
+	F: Form,
+	G: Form<TypeId = F::TypeId, IndirectTypeId = F::IndirectTypeId>,
+	M: FnMut(F::String) -> G::String,
+{
+	fn map_string(&mut self, string: F::String) -> G::String {
+		(self.0)(string)
+	}
+
+	fn map_type_id(&mut self, type_id: F::TypeId) -> G::TypeId {
+		type_id
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: F::IndirectTypeId) -> G::IndirectTypeId {
+		type_id
+	}
+}
+
+/// Converts the implementor from the form `F` into the form `G` using the given map.
+pub(crate) trait ConvertForm<F: Form, G: Form> {
+	/// The output type after conversion.
+	type Output;
+
+	/// Converts the implementor into the form `G`.
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output;
+}
@@ -0,0 +1,77 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A process-wide registry shared by all libraries.
+//!
+//! Libraries can contribute their types to the global registry from anywhere
+//! instead of threading a `&mut Registry` through their APIs. The registry is
+//! created upon first use and guarded by a mutex.
+//!
+//! # Example
+//!
+//! ```
+//! # use type_metadata::global;
+//! let symbol = global::register_global::<Option<u32>>();
+//! assert!(global::global_registry().resolve(&symbol).is_some());
+//! ```
+
+use crate::{interner::UntrackedSymbol, Metadata, Registry};
+use std::{
+	any::TypeId as AnyTypeId,
+	sync::{Mutex, MutexGuard, OnceLock},
+};
+
+/// Returns exclusive access to the global registry.
+///
+/// # Panics
+///
+/// If a panic occurred while the global registry has been accessed
+/// since it might have been left inconsistent.
+pub fn global_registry() -> MutexGuard<'static, Registry> {
+	static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+	REGISTRY
+		.get_or_init(|| Mutex::new(Registry::new()))
+		.lock()
+		.expect("the global registry is poisoned by a panic")
+}
+
+/// Registers the given type into the global registry and returns its associated type ID symbol.
+pub fn register_global<T>() -> UntrackedSymbol<AnyTypeId>
+where
+	T: Metadata + ?Sized,
+{
+	global_registry().register_type(&T::meta_type())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::thread;
+
+	#[test]
+	fn registers_from_multiple_threads() {
+		let threads = (0..4)
+			.map(|_| thread::spawn(register_global::<Vec<Option<u16>>>))
+			.collect::<Vec<_>>();
+		let symbols = threads
+			.into_iter()
+			.map(|thread| thread.join().unwrap())
+			.collect::<Vec<_>>();
+		assert!(symbols.windows(2).all(|pair| pair[0] == pair[1]));
+		assert_eq!(register_global::<Vec<Option<u16>>>(), symbols[0]);
+		assert!(global_registry().resolve(&symbols[0]).is_some());
+	}
+}
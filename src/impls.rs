@@ -48,33 +48,107 @@ impl_metadata_for_primitives!(
 	i128 => TypeIdPrimitive::I128,
 );
 
-macro_rules! impl_metadata_for_array {
-	( $( $n:expr )* ) => {
-		$(
-			impl<T: Metadata + 'static> HasTypeId for [T; $n] {
-				fn type_id() -> TypeId {
-					TypeIdArray::new($n, MetaType::new::<T>()).into()
-				}
+// Floats are opt-in since chains commonly forbid them in their types.
+#[cfg(feature = "floats")]
+impl_metadata_for_primitives!(
+	f32 => TypeIdPrimitive::F32,
+	f64 => TypeIdPrimitive::F64,
+);
+
+macro_rules! impl_metadata_for_integer_wrappers {
+	( $( $t:ident => $inner:ty $( ; lossy = $lossy:expr )?, )* ) => { $(
+		impl HasTypeId for $t {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new(stringify!($t), Namespace::prelude(), tuple_meta_type!()).into()
 			}
+		}
 
-			impl<T: Metadata> HasTypeDef for [T; $n] {
-				fn type_def() -> TypeDef {
-					TypeDef::builtin()
+		impl HasTypeDef for $t {
+			fn type_def() -> TypeDef {
+				TypeDefTupleStruct::new(vec![UnnamedField::of::<$inner>()]).into()
+			}
+			$(
+				fn lossy() -> Option<&'static str> {
+					Some($lossy)
 				}
+			)?
+		}
+	)* }
+}
+
+// Pointer-sized integers are described as 64-bit integers independent of the
+// target platform while keeping their own identifiers to record the mapping.
+impl_metadata_for_integer_wrappers!(
+	usize => u64; lossy = "pointer-sized integers are described as 64-bit integers",
+	isize => i64; lossy = "pointer-sized integers are described as 64-bit integers",
+);
+
+// Non-zero integers get their own identifiers so that decoders can enforce the invariant.
+impl_metadata_for_integer_wrappers!(
+	NonZeroU8 => u8,
+	NonZeroU16 => u16,
+	NonZeroU32 => u32,
+	NonZeroU64 => u64,
+	NonZeroU128 => u128,
+	NonZeroI8 => i8,
+	NonZeroI16 => i16,
+	NonZeroI32 => i32,
+	NonZeroI64 => i64,
+	NonZeroI128 => i128,
+);
+
+#[cfg(feature = "primitive-types")]
+macro_rules! impl_metadata_for_primitive_types {
+	( $( $t:ident => $inner:ty, )* ) => { $(
+		impl HasTypeId for primitive_types::$t {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new(
+					stringify!($t),
+					Namespace::new(vec!["primitive_types"]).unwrap(),
+					tuple_meta_type!(),
+				)
+				.into()
 			}
-		)*
-	}
+		}
+
+		impl HasTypeDef for primitive_types::$t {
+			fn type_def() -> TypeDef {
+				TypeDefTupleStruct::new(vec![UnnamedField::of::<$inner>()]).into()
+			}
+		}
+	)* }
 }
 
-#[rustfmt::skip]
-impl_metadata_for_array!(
-        1  2  3  4  5  6  7  8  9
-    10 11 12 13 14 15 16 17 18 19
-    20 21 22 23 24 25 26 27 28 29
-    30 31 32
-	40 48 56 64 72 96 128 160 192 224 256
+// Big integers and fixed hashes are described by their well-known array
+// representations which encode to the same little-endian bytes.
+#[cfg(feature = "primitive-types")]
+impl_metadata_for_primitive_types!(
+	U128 => [u64; 2],
+	U256 => [u64; 4],
+	U512 => [u64; 8],
+	H160 => [u8; 20],
+	H256 => [u8; 32],
+	H512 => [u8; 64],
 );
 
+impl<T, const N: usize> HasTypeId for [T; N]
+where
+	T: Metadata + 'static,
+{
+	fn type_id() -> TypeId {
+		TypeIdArray::new(N as u64, MetaType::new::<T>()).into()
+	}
+}
+
+impl<T, const N: usize> HasTypeDef for [T; N]
+where
+	T: Metadata,
+{
+	fn type_def() -> TypeDef {
+		TypeDef::builtin()
+	}
+}
+
 macro_rules! impl_metadata_for_tuple {
     ( $($ty:ident),* ) => {
 		impl<$($ty),*> HasTypeId for ($($ty,)*)
@@ -131,6 +205,30 @@ where
 	}
 }
 
+macro_rules! impl_metadata_for_sequences {
+	( $( $name:ident ),* ) => { $(
+		impl<T> HasTypeId for $name<T>
+		where
+			T: Metadata + 'static,
+		{
+			fn type_id() -> TypeId {
+				TypeIdCustom::new(stringify!($name), Namespace::prelude(), tuple_meta_type![T]).into()
+			}
+		}
+
+		impl<T> HasTypeDef for $name<T>
+		where
+			T: Metadata + 'static,
+		{
+			fn type_def() -> TypeDef {
+				<Vec<T>>::type_def()
+			}
+		}
+	)* }
+}
+
+impl_metadata_for_sequences!(VecDeque, LinkedList, BinaryHeap, BTreeSet);
+
 impl<T> HasTypeId for Option<T>
 where
 	T: Metadata + 'static,
@@ -197,6 +295,50 @@ where
 	}
 }
 
+/// Hash maps share the definition of `BTreeMap` and ignore their hasher.
+#[cfg(feature = "std")]
+impl<K, V, S> HasTypeId for HashMap<K, V, S>
+where
+	K: Metadata + 'static,
+	V: Metadata + 'static,
+{
+	fn type_id() -> TypeId {
+		TypeIdCustom::new("HashMap", Namespace::prelude(), tuple_meta_type!(K, V)).into()
+	}
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> HasTypeDef for HashMap<K, V, S>
+where
+	K: Metadata + 'static,
+	V: Metadata + 'static,
+{
+	fn type_def() -> TypeDef {
+		<BTreeMap<K, V>>::type_def()
+	}
+}
+
+/// Hash sets share the definition of `BTreeSet` and ignore their hasher.
+#[cfg(feature = "std")]
+impl<T, S> HasTypeId for HashSet<T, S>
+where
+	T: Metadata + 'static,
+{
+	fn type_id() -> TypeId {
+		TypeIdCustom::new("HashSet", Namespace::prelude(), tuple_meta_type!(T)).into()
+	}
+}
+
+#[cfg(feature = "std")]
+impl<T, S> HasTypeDef for HashSet<T, S>
+where
+	T: Metadata + 'static,
+{
+	fn type_def() -> TypeDef {
+		<BTreeSet<T>>::type_def()
+	}
+}
+
 impl<T> HasTypeId for Box<T>
 where
 	T: HasTypeId + ?Sized,
@@ -213,6 +355,76 @@ where
 	fn type_def() -> TypeDef {
 		T::type_def()
 	}
+
+	fn lossy() -> Option<&'static str> {
+		T::lossy()
+	}
+}
+
+impl<T> HasTypeId for Rc<T>
+where
+	T: HasTypeId + ?Sized,
+{
+	fn type_id() -> TypeId {
+		T::type_id()
+	}
+}
+
+impl<T> HasTypeDef for Rc<T>
+where
+	T: Metadata + ?Sized,
+{
+	fn type_def() -> TypeDef {
+		T::type_def()
+	}
+
+	fn lossy() -> Option<&'static str> {
+		T::lossy()
+	}
+}
+
+impl<T> HasTypeId for Arc<T>
+where
+	T: HasTypeId + ?Sized,
+{
+	fn type_id() -> TypeId {
+		T::type_id()
+	}
+}
+
+impl<T> HasTypeDef for Arc<T>
+where
+	T: Metadata + ?Sized,
+{
+	fn type_def() -> TypeDef {
+		T::type_def()
+	}
+
+	fn lossy() -> Option<&'static str> {
+		T::lossy()
+	}
+}
+
+impl<T> HasTypeId for Cow<'static, T>
+where
+	T: HasTypeId + ToOwned + ?Sized,
+{
+	fn type_id() -> TypeId {
+		T::type_id()
+	}
+}
+
+impl<T> HasTypeDef for Cow<'static, T>
+where
+	T: Metadata + ToOwned + ?Sized,
+{
+	fn type_def() -> TypeDef {
+		T::type_def()
+	}
+
+	fn lossy() -> Option<&'static str> {
+		T::lossy()
+	}
 }
 
 impl<T> HasTypeId for &T
@@ -231,6 +443,10 @@ where
 	fn type_def() -> TypeDef {
 		T::type_def()
 	}
+
+	fn lossy() -> Option<&'static str> {
+		T::lossy()
+	}
 }
 
 impl<T> HasTypeId for &mut T
@@ -249,6 +465,32 @@ where
 	fn type_def() -> TypeDef {
 		T::type_def()
 	}
+
+	fn lossy() -> Option<&'static str> {
+		T::lossy()
+	}
+}
+
+impl<T> HasTypeId for ManuallyDrop<T>
+where
+	T: HasTypeId,
+{
+	fn type_id() -> TypeId {
+		T::type_id()
+	}
+}
+
+impl<T> HasTypeDef for ManuallyDrop<T>
+where
+	T: Metadata,
+{
+	fn type_def() -> TypeDef {
+		T::type_def()
+	}
+
+	fn lossy() -> Option<&'static str> {
+		T::lossy()
+	}
 }
 
 impl<T> HasTypeId for [T]
@@ -293,18 +535,28 @@ impl HasTypeDef for String {
 	}
 }
 
+/// Markers carry no data, so neither their identifier nor their definition depends on
+/// their type parameter. All instantiations thus share a single registry entry and do
+/// not require their type parameter to implement `Metadata`.
 impl<T> HasTypeId for PhantomData<T>
 where
-	T: Metadata + ?Sized,
+	T: ?Sized,
 {
 	fn type_id() -> TypeId {
-		TypeIdCustom::new("PhantomData", Namespace::prelude(), vec![T::meta_type()]).into()
+		TypeIdCustom::new("PhantomData", Namespace::prelude(), Vec::new()).into()
+	}
+
+	fn any_id() -> AnyTypeId
+	where
+		Self: 'static,
+	{
+		AnyTypeId::of::<PhantomData<()>>()
 	}
 }
 
 impl<T> HasTypeDef for PhantomData<T>
 where
-	T: Metadata + ?Sized,
+	T: ?Sized,
 {
 	fn type_def() -> TypeDef {
 		TypeDefTupleStruct::new(vec![]).into()
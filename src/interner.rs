@@ -24,13 +24,16 @@
 //! and is later used for compact serialization within the registry.
 
 use crate::tm_std::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "codec")]
+use parity_scale_codec::{Decode, Encode, EncodeLike, Error, Input, Output};
 
 /// A symbol that is not lifetime tracked.
 ///
 /// This can be used by self-referential types but
 /// can no longer be used to resolve instances.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct UntrackedSymbol<T> {
 	id: NonZeroU32,
@@ -38,6 +41,15 @@ pub struct UntrackedSymbol<T> {
 	marker: PhantomData<fn() -> T>,
 }
 
+/// Symbols are copyable independent of the type of their interned elements.
+impl<T> Clone for UntrackedSymbol<T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<T> Copy for UntrackedSymbol<T> {}
+
 /// A symbol from an interner.
 ///
 /// Can be used to resolve to the associated instance.
@@ -49,6 +61,45 @@ pub struct Symbol<'a, T> {
 	marker: PhantomData<fn() -> &'a T>,
 }
 
+impl<T> UntrackedSymbol<T> {
+	/// Returns the unique identifier of the symbol.
+	///
+	/// Identifiers are assigned in the order in which symbols are interned.
+	pub fn id(&self) -> NonZeroU32 {
+		self.id
+	}
+
+	/// Creates the symbol with the given unique identifier.
+	pub(crate) fn from_id(id: NonZeroU32) -> Self {
+		Self {
+			id,
+			marker: PhantomData,
+		}
+	}
+}
+
+/// Symbols are encoded as their plain `u32` identifier.
+#[cfg(feature = "codec")]
+impl<T> Encode for UntrackedSymbol<T> {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		self.id.get().encode_to(dest)
+	}
+}
+
+#[cfg(feature = "codec")]
+impl<T> EncodeLike for UntrackedSymbol<T> {}
+
+#[cfg(feature = "codec")]
+impl<T> Decode for UntrackedSymbol<T> {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+		let id = NonZeroU32::new(u32::decode(input)?).ok_or("symbol identifiers must not be zero")?;
+		Ok(Self {
+			id,
+			marker: PhantomData,
+		})
+	}
+}
+
 impl<T> Symbol<'_, T> {
 	/// Removes the lifetime tracking for this symbol.
 	///
@@ -140,14 +191,41 @@ where
 		)
 	}
 
+	/// Associates the given element with the symbol of an already interned element.
+	///
+	/// The alias resolves to the symbol via `get` and `intern_or_get` but is not listed
+	/// by `elements`. Elements that have already been interned are left unchanged.
+	pub(crate) fn alias(&mut self, s: T, sym: UntrackedSymbol<T>) {
+		self.map.entry(s).or_insert(sym.id.get() as usize - 1);
+	}
+
+	/// Returns all aliases along with the symbols they are associated with.
+	pub(crate) fn aliases(&self) -> impl Iterator<Item = (&T, UntrackedSymbol<T>)> {
+		self.map
+			.iter()
+			.filter(move |(s, &id)| self.vec[id] != **s)
+			.map(|(s, &id)| (s, UntrackedSymbol::from_id(NonZeroU32::new((id + 1) as u32).unwrap())))
+	}
+
 	/// Returns the symbol of the given element or `None` if it hasn't been interned already.
 	pub fn get(&self, s: &T) -> Option<Symbol<T>> {
 		self.map.get(s).map(|&id| Symbol {
-			id: NonZeroU32::new(id as u32).unwrap(),
+			id: NonZeroU32::new((id + 1) as u32).unwrap(),
 			marker: PhantomData,
 		})
 	}
 
+	/// Resolves the original element given the identifier of its associated
+	/// symbol or returns `None` if it has not been interned yet.
+	pub(crate) fn resolve_id(&self, id: NonZeroU32) -> Option<&T> {
+		self.vec.get((id.get() - 1) as usize)
+	}
+
+	/// Returns all interned elements in the order of their symbols.
+	pub(crate) fn elements(&self) -> &[T] {
+		&self.vec
+	}
+
 	/// Resolves the original element given its associated symbol or
 	/// returns `None` if it has not been interned yet.
 	pub fn resolve(&self, sym: Symbol<T>) -> Option<&T> {
@@ -193,5 +271,9 @@ mod tests {
 		assert_resolve(&mut interner, 2, ", World!");
 		assert_resolve(&mut interner, 3, "1 2 3");
 		assert_resolve(&mut interner, 4, None);
+
+		assert_eq!(interner.get(&"Hello").map(|sym| sym.id.get()), Some(1));
+		assert_eq!(interner.get(&"1 2 3").map(|sym| sym.id.get()), Some(3));
+		assert_eq!(interner.get(&"unknown"), None);
 	}
 }
@@ -109,29 +109,130 @@ macro_rules! tuple_meta_type {
 	}
 }
 
+/// Implements `HasTypeId` and `HasTypeDef` for newtype wrappers around a single type.
+///
+/// The wrapper is identified by its own name within the namespace of the invoking
+/// module and defined as a tuple-struct with the wrapped type as its single field.
+/// Use `#[metadata(transparent)]` instead in order to forward to the wrapped type entirely.
+///
+/// # Example
+///
+/// ```
+/// # use type_metadata::{impl_metadata_newtype, HasTypeDef, TypeDefTupleStruct, UnnamedField};
+/// pub struct Balance(u128);
+/// pub struct Nonce(u64);
+///
+/// impl_metadata_newtype!(Balance(u128); Nonce(u64));
+///
+/// assert_eq!(
+/// 	Balance::type_def(),
+/// 	TypeDefTupleStruct::new(vec![UnnamedField::of::<u128>()]).into(),
+/// );
+/// ```
+#[macro_export]
+macro_rules! impl_metadata_newtype {
+	( $( $name:ident ( $inner:ty ) );* $(;)? ) => {
+		$(
+			impl $crate::HasTypeId for $name {
+				fn type_id() -> $crate::TypeId {
+					$crate::TypeIdCustom::new(
+						stringify!($name),
+						$crate::Namespace::from_module_path(module_path!())
+							.expect("namespace from module path cannot fail"),
+						::core::iter::empty(),
+					)
+					.into()
+				}
+			}
+
+			impl $crate::HasTypeDef for $name {
+				fn type_def() -> $crate::TypeDef {
+					$crate::TypeDefTupleStruct::new(::core::iter::once($crate::UnnamedField::of::<$inner>())).into()
+				}
+			}
+		)*
+	}
+}
+
+/// Implements `HasTypeId` and `HasTypeDef` for types by describing them as other types.
+///
+/// Each type is described by the type identifier and definition of the given type just
+/// as by `#[metadata(transparent)]`, e.g. for local wrappers around types that cannot
+/// implement `Metadata`, such as raw pointers, but are encoded as the given type.
+///
+/// # Example
+///
+/// ```
+/// # use type_metadata::{impl_metadata_for, HasTypeDef, HasTypeId};
+/// pub struct Handle(*const u8);
+///
+/// impl_metadata_for!(Handle => u64);
+///
+/// assert_eq!(Handle::type_id(), u64::type_id());
+/// assert_eq!(Handle::type_def(), u64::type_def());
+/// ```
+#[macro_export]
+macro_rules! impl_metadata_for {
+	( $( $ty:ty => $described:ty );* $(;)? ) => {
+		$(
+			impl $crate::HasTypeId for $ty {
+				fn type_id() -> $crate::TypeId {
+					<$described as $crate::HasTypeId>::type_id()
+				}
+			}
+
+			impl $crate::HasTypeDef for $ty {
+				fn type_def() -> $crate::TypeDef {
+					<$described as $crate::HasTypeDef>::type_def()
+				}
+
+				fn lossy() -> ::core::option::Option<&'static str> {
+					<$described as $crate::HasTypeDef>::lossy()
+				}
+			}
+		)*
+	}
+}
+
 mod tm_std;
 
+pub mod analysis;
+#[cfg(feature = "build-support")]
+pub mod build_support;
+mod canonical;
+pub mod content_id;
+pub mod diff;
 pub mod form;
+#[cfg(feature = "static-registry")]
+pub mod global;
 mod impls;
 pub mod interner;
 mod meta_type;
 mod registry;
+#[cfg(feature = "stats")]
+pub mod stats;
+pub mod tabular;
 mod type_def;
 mod type_id;
 mod utils;
+pub mod validate;
+pub mod visit;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::{
 	meta_type::MetaType,
-	registry::{IntoCompact, Registry},
+	registry::{
+		IntoCompact, NamespaceFilter, RegisterError, RegisterTypes, Registry, RegistryFrozen, RegistryReadOnly,
+		TypeIdDef,
+	},
 	type_def::*,
 	type_id::*,
 };
 
 #[cfg(feature = "derive")]
-pub use type_metadata_derive::{Metadata, TypeDef, TypeId};
+pub use type_metadata_derive::{HasTypeDef, HasTypeId, Metadata, TypeDef, TypeId};
 
 /// A super trait that shall be implemented by all types implementing
 /// `HasTypeId` and `HasTypedef` in order to more easily manage them.
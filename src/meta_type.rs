@@ -15,7 +15,7 @@
 // limitations under the License.
 
 use crate::tm_std::*;
-use crate::{form::MetaForm, HasTypeDef, HasTypeId, Metadata, TypeDef, TypeId};
+use crate::{form::MetaForm, HasTypeDef, HasTypeId, Metadata, Namespace, TypeDef, TypeId, TypeIdCustom};
 
 /// A metatype abstraction.
 ///
@@ -30,6 +30,8 @@ pub struct MetaType {
 	fn_type_id: fn() -> TypeId<MetaForm>,
 	/// Function pointer to type definition.
 	fn_type_def: fn() -> TypeDef<MetaForm>,
+	/// Function pointer to the reason why the type definition is lossy.
+	fn_lossy: fn() -> Option<&'static str>,
 	// The standard type ID (ab)used in order to provide
 	// cheap implementations of the standard traits
 	// such as `PartialEq`, `PartialOrd`, `Debug` and `Hash`.
@@ -80,7 +82,23 @@ impl MetaType {
 		Self {
 			fn_type_id: <T as HasTypeId>::type_id,
 			fn_type_def: <T as HasTypeDef>::type_def,
-			any_id: AnyTypeId::of::<T>(),
+			fn_lossy: <T as HasTypeDef>::lossy,
+			any_id: <T as HasTypeId>::any_id(),
+		}
+	}
+
+	/// Creates the meta type standing in for types that do not implement `Metadata`.
+	///
+	/// This is used for fields whose types cannot be described. All such types are
+	/// identified as the single `Opaque` type within the root namespace with a builtin
+	/// definition, since describing them any further would depend on the unstable Rust
+	/// type names. The opaque meta type is lossy and thus refused by strict registries.
+	pub fn opaque() -> Self {
+		Self {
+			fn_type_id: opaque_type_id,
+			fn_type_def: TypeDef::builtin,
+			fn_lossy: opaque_lossy,
+			any_id: AnyTypeId::of::<Opaque>(),
 		}
 	}
 
@@ -102,8 +120,29 @@ impl MetaType {
 		(self.fn_type_def)()
 	}
 
+	/// Returns why the meta type definition is lossy, if it is.
+	///
+	/// See `HasTypeDef::lossy` for details.
+	pub fn lossy(&self) -> Option<&'static str> {
+		(self.fn_lossy)()
+	}
+
 	/// Returns the type identifier provided by `core::any`.
 	pub fn any_id(&self) -> AnyTypeId {
 		self.any_id
 	}
 }
+
+/// Marks the type identifier provided by `core::any` of the opaque meta type
+/// in order to tell it apart from the meta types of actual types.
+struct Opaque;
+
+/// Returns the type identifier of the opaque meta type.
+fn opaque_type_id() -> TypeId {
+	TypeIdCustom::new("Opaque", Namespace::prelude(), Vec::new()).into()
+}
+
+/// Returns why the type definition of the opaque meta type is lossy.
+fn opaque_lossy() -> Option<&'static str> {
+	Some("opaque types carry no metadata")
+}
@@ -33,12 +33,24 @@
 
 use crate::tm_std::*;
 use crate::{
-	form::CompactForm,
+	canonical,
+	content_id::ContentId,
+	diff::RegistryDiff,
+	form::{
+		CompactForm, ConvertForm, DisplayForm, Expanded, Form, FormMap, FrozenForm, MapStrings, MetaForm, PortableForm,
+		StrRef,
+	},
 	interner::{Interner, UntrackedSymbol},
 	meta_type::MetaType,
-	TypeDef, TypeId,
+	validate::{self, ValidationError},
+	Namespace, TypeDef, TypeId, TypeIdCustom,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "stats")]
+use crate::stats::{self, RegistryStats};
+#[cfg(feature = "codec")]
+use parity_scale_codec::{Decode, Encode, Output};
 
 /// Compacts the implementor using a registry.
 pub trait IntoCompact {
@@ -46,18 +58,606 @@ pub trait IntoCompact {
 	type Output;
 
 	/// Compacts `self` by using the registry for caching and compaction.
-	fn into_compact(self, registry: &mut Registry) -> Self::Output;
+	///
+	/// Borrows `self` so that the same definitions can be compacted into
+	/// multiple registries without cloning them.
+	///
+	/// Returns an error if the registry refuses any type referred to, see
+	/// `Registry::try_register_type`. No strings or types are registered then.
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError>;
+
+	/// Compacts `self` by using the registry for caching and compaction.
+	fn into_compact(self, registry: &mut Registry) -> Result<Self::Output, RegisterError>
+	where
+		Self: Sized,
+	{
+		self.to_compact(registry)
+	}
+}
+
+/// Maps the string and type symbols of one registry to the symbols of another.
+pub(crate) trait SymbolMap {
+	/// Returns the symbol the given string symbol is mapped to.
+	fn map_string(&mut self, symbol: UntrackedSymbol<String>) -> UntrackedSymbol<String>;
+
+	/// Returns the symbol the given type symbol is mapped to.
+	fn map_type(&mut self, symbol: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId>;
+}
+
+/// Replaces the symbols of compacted type identifiers and definitions.
+///
+/// This allows to move compacted types from one registry into another.
+pub(crate) trait MapSymbols {
+	/// Replaces all string and type symbols using the given symbol map.
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M);
+}
+
+impl MapSymbols for UntrackedSymbol<String> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		*self = map.map_string(*self);
+	}
+}
+
+impl MapSymbols for UntrackedSymbol<AnyTypeId> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		*self = map.map_type(*self);
+	}
+}
+
+impl<T: MapSymbols> MapSymbols for Vec<T> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		for elem in self {
+			elem.map_symbols(map);
+		}
+	}
+}
+
+impl<T: MapSymbols> MapSymbols for Option<T> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		if let Some(elem) = self {
+			elem.map_symbols(map);
+		}
+	}
+}
+
+/// Maps the symbols of moved types to their new symbols, e.g. upon merging registries.
+///
+/// The new symbols are indexed by the identifiers of the original symbols.
+/// Only the symbols referred to by moved types need to be mapped.
+#[derive(Default)]
+struct Remap {
+	strings: Vec<Option<UntrackedSymbol<String>>>,
+	types: Vec<Option<UntrackedSymbol<AnyTypeId>>>,
+}
+
+impl Remap {
+	/// Returns the new symbol of the given type symbol if it has been mapped.
+	fn get_type(&self, symbol: UntrackedSymbol<AnyTypeId>) -> Option<UntrackedSymbol<AnyTypeId>> {
+		self.types
+			.get(symbol.id().get() as usize - 1)
+			.and_then(|symbol| *symbol)
+	}
+}
+
+impl SymbolMap for Remap {
+	fn map_string(&mut self, symbol: UntrackedSymbol<String>) -> UntrackedSymbol<String> {
+		self.strings[symbol.id().get() as usize - 1].expect("strings of moved types are mapped")
+	}
+
+	fn map_type(&mut self, symbol: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		self.get_type(symbol)
+			.expect("types referred to by moved types are mapped")
+	}
+}
+
+/// Marks the strings and types referred to by compacted types as reachable.
+///
+/// Leaves the symbols unchanged.
+struct Reachable {
+	strings: Vec<bool>,
+	types: Vec<bool>,
+	/// The reachable types whose references have not been visited yet.
+	pending: Vec<UntrackedSymbol<AnyTypeId>>,
+}
+
+impl SymbolMap for Reachable {
+	fn map_string(&mut self, symbol: UntrackedSymbol<String>) -> UntrackedSymbol<String> {
+		self.strings[symbol.id().get() as usize - 1] = true;
+		symbol
+	}
+
+	fn map_type(&mut self, symbol: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		let reachable = &mut self.types[symbol.id().get() as usize - 1];
+		if !*reachable {
+			*reachable = true;
+			self.pending.push(symbol);
+		}
+		symbol
+	}
+}
+
+/// Maps type symbols to the first type of their class of structurally equal types.
+///
+/// Leaves string symbols unchanged since equal strings are interned only once.
+struct Canonical<'a> {
+	/// The first type of the class of each type, indexed by the identifiers of the types.
+	classes: &'a [UntrackedSymbol<AnyTypeId>],
+}
+
+impl SymbolMap for Canonical<'_> {
+	fn map_string(&mut self, symbol: UntrackedSymbol<String>) -> UntrackedSymbol<String> {
+		symbol
+	}
+
+	fn map_type(&mut self, symbol: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		self.classes[symbol.id().get() as usize - 1]
+	}
 }
 
 /// The pair of associated type identifier and structure.
 ///
-/// This exists only as compactified version and is part of the registry.
-#[derive(Debug, PartialEq, Eq, Serialize)]
-pub struct TypeIdDef {
+/// This exists as compactified version as part of the registry and as portable
+/// version owning its strings, see `Registry::portable_types`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize, F::IndirectTypeId: Serialize",
+	deserialize = "
+		F::String: Deserialize<'de>,
+		F::TypeId: Deserialize<'de>,
+		F::IndirectTypeId: Deserialize<'de>
+	"
+))]
+pub struct TypeIdDef<F: Form = CompactForm> {
 	/// The identifier of the type.
-	id: TypeId<CompactForm>,
+	id: TypeId<F>,
 	/// The definition (aka internal structure) of the type.
-	def: TypeDef<CompactForm>,
+	def: TypeDef<F>,
+}
+
+impl MapSymbols for TypeIdDef {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.id.map_symbols(map);
+		self.def.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeIdDef<F> {
+	type Output = TypeIdDef<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
+		TypeIdDef {
+			id: self.id.convert_form(map),
+			def: self.def.convert_form(map),
+		}
+	}
+}
+
+impl<F: Form> TypeIdDef<F> {
+	/// Creates a new pair of type identifier and definition.
+	pub fn new(id: TypeId<F>, def: TypeDef<F>) -> Self {
+		Self { id, def }
+	}
+
+	/// Returns the identifier of the type.
+	pub fn id(&self) -> &TypeId<F> {
+		&self.id
+	}
+
+	/// Returns the definition of the type.
+	pub fn def(&self) -> &TypeDef<F> {
+		&self.def
+	}
+}
+
+/// Converts the given types into the portable form by resolving their string symbols.
+///
+/// Returns `None` if a string symbol cannot be resolved.
+fn portable_types<'a, S, I>(strings: &[S], types: I) -> Option<Vec<TypeIdDef<PortableForm>>>
+where
+	S: AsRef<str>,
+	I: IntoIterator<Item = &'a TypeIdDef>,
+{
+	let mut resolved = true;
+	let types = types
+		.into_iter()
+		.map(|type_id_def| {
+			type_id_def
+				.clone()
+				.convert_form(&mut MapStrings(|symbol: UntrackedSymbol<String>| {
+					match strings.get(symbol.id().get() as usize - 1) {
+						Some(string) => string.as_ref().to_string(),
+						None => {
+							resolved = false;
+							String::new()
+						}
+					}
+				}))
+		})
+		.collect();
+	if resolved {
+		Some(types)
+	} else {
+		None
+	}
+}
+
+/// Inlines the types referred to by compact types of a registry.
+struct Expander<'a> {
+	registry: &'a Registry,
+	/// The labels of all registered types in the order of their symbols.
+	labels: Vec<ContentId>,
+	/// The types being expanded from the outermost to the innermost
+	/// and whether they are referred back to.
+	enclosing: Vec<(UntrackedSymbol<AnyTypeId>, bool)>,
+	/// Whether all symbols could be resolved so far.
+	resolved: bool,
+}
+
+impl<'a> Expander<'a> {
+	/// Creates an expander of types that are not enclosed by any other type.
+	fn new(registry: &'a Registry) -> Self {
+		Self {
+			registry,
+			labels: registry.content_ids(),
+			enclosing: Vec::new(),
+			resolved: true,
+		}
+	}
+
+	/// Returns the label of the given registered type.
+	fn label(&self, symbol: UntrackedSymbol<AnyTypeId>) -> ContentId {
+		self.labels[symbol.id().get() as usize - 1]
+	}
+}
+
+impl FormMap<CompactForm, DisplayForm> for Expander<'_> {
+	fn map_string(&mut self, string: UntrackedSymbol<String>) -> String {
+		match self.registry.resolve_string(&string) {
+			Some(string) => string.to_string(),
+			None => {
+				self.resolved = false;
+				String::new()
+			}
+		}
+	}
+
+	fn map_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> Expanded {
+		if let Some(position) = self.enclosing.iter().rposition(|(enclosing, _)| *enclosing == type_id) {
+			self.enclosing[position].1 = true;
+			return Expanded::Recursive {
+				label: self.label(type_id),
+				depth: self.enclosing.len() - position,
+			};
+		}
+		let type_id_def = match self.registry.types.get(&type_id) {
+			Some(type_id_def) => type_id_def.clone(),
+			None => {
+				self.resolved = false;
+				return Expanded::Recursive {
+					label: ContentId([0; 32]),
+					depth: 0,
+				};
+			}
+		};
+		self.enclosing.push((type_id, false));
+		let expanded = Box::new(type_id_def.convert_form(self));
+		match self.enclosing.pop() {
+			Some((_, true)) => Expanded::Labelled {
+				label: self.label(type_id),
+				type_id_def: expanded,
+			},
+			_ => Expanded::Type(expanded),
+		}
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> Expanded {
+		self.map_type_id(type_id)
+	}
+}
+
+/// Replaces the types inlined by the display form with the symbols of the registered
+/// types they are expanded from, i.e. the inverse of the `Expander`.
+///
+/// Inlined types are only matched against the registered types of the same shape,
+/// see `Shape`, without expanding any registered type.
+struct Compactor<'a> {
+	registry: &'a Registry,
+	/// The distinct shapes of the registered types.
+	shapes: BTreeMap<TypeIdDef, usize>,
+	/// The registered types of each shape in the order of their symbols.
+	by_shape: Vec<Vec<UntrackedSymbol<AnyTypeId>>>,
+	/// The shape of each registered type and the types it refers to in order,
+	/// indexed by the identifiers of the types.
+	types: Vec<(usize, Vec<UntrackedSymbol<AnyTypeId>>)>,
+	/// Whether all strings and types could be resolved so far.
+	resolved: bool,
+}
+
+impl<'a> Compactor<'a> {
+	/// Creates a compactor indexing all types of the registry by their shapes.
+	fn new(registry: &'a Registry) -> Self {
+		let mut shapes = BTreeMap::new();
+		let mut by_shape = Vec::<Vec<_>>::new();
+		let mut types = Vec::with_capacity(registry.types.len());
+		for (symbol, type_id_def) in &registry.types {
+			let mut shape = Shape::default();
+			let mut key = type_id_def.clone();
+			key.map_symbols(&mut shape);
+			let next = shapes.len();
+			let index = *shapes.entry(key).or_insert(next);
+			if index == by_shape.len() {
+				by_shape.push(Vec::new());
+			}
+			by_shape[index].push(*symbol);
+			types.push((index, shape.0));
+		}
+		Self {
+			registry,
+			shapes,
+			by_shape,
+			types,
+			resolved: true,
+		}
+	}
+
+	/// Flattens the inlined type into the given nodes and returns its reference.
+	fn flatten(&mut self, expanded: Expanded, nodes: &mut Vec<Inlined>) -> InlinedRef {
+		let type_id_def = match expanded {
+			Expanded::Type(type_id_def) | Expanded::Labelled { type_id_def, .. } => type_id_def,
+			Expanded::Recursive { depth, .. } => return InlinedRef::Recursive(depth),
+		};
+		let mut shape = InlinedShape {
+			compactor: self,
+			inlined: Vec::new(),
+		};
+		let key = type_id_def.convert_form(&mut shape);
+		let inlined = shape.inlined;
+		let index = nodes.len();
+		nodes.push(Inlined {
+			shape: self.shapes.get(&key).copied(),
+			inlined: Vec::new(),
+		});
+		let inlined = inlined
+			.into_iter()
+			.map(|expanded| self.flatten(expanded, nodes))
+			.collect();
+		nodes[index].inlined = inlined;
+		InlinedRef::Type(index)
+	}
+
+	/// Returns `true` if the registered type expands to the given flattened node.
+	///
+	/// The types being expanded are given from the outermost to the innermost
+	/// just as for the `Expander`.
+	fn matches(
+		&self,
+		nodes: &[Inlined],
+		node: usize,
+		symbol: UntrackedSymbol<AnyTypeId>,
+		enclosing: &mut Vec<UntrackedSymbol<AnyTypeId>>,
+	) -> bool {
+		let (shape, referred) = &self.types[symbol.id().get() as usize - 1];
+		if nodes[node].shape != Some(*shape) {
+			return false;
+		}
+		enclosing.push(symbol);
+		let matches = referred.iter().zip(&nodes[node].inlined).all(|(referred, inlined)| {
+			match enclosing.iter().rposition(|enclosing| enclosing == referred) {
+				Some(position) => *inlined == InlinedRef::Recursive(enclosing.len() - position),
+				None => match inlined {
+					InlinedRef::Type(inlined) => self.matches(nodes, *inlined, *referred, enclosing),
+					InlinedRef::Recursive(_) => false,
+				},
+			}
+		});
+		enclosing.pop();
+		matches
+	}
+}
+
+impl FormMap<DisplayForm, CompactForm> for Compactor<'_> {
+	fn map_string(&mut self, string: String) -> UntrackedSymbol<String> {
+		match self.registry.string_table.get(&Cow::Owned(string)) {
+			Some(symbol) => UntrackedSymbol::from_id(symbol.into_untracked().id()),
+			None => {
+				self.resolved = false;
+				unresolved()
+			}
+		}
+	}
+
+	fn map_type_id(&mut self, type_id: Expanded) -> UntrackedSymbol<AnyTypeId> {
+		let mut nodes = Vec::new();
+		let symbol = match self.flatten(type_id, &mut nodes) {
+			InlinedRef::Type(root) => nodes[root].shape.and_then(|shape| {
+				self.by_shape[shape]
+					.iter()
+					.find(|symbol| self.matches(&nodes, root, **symbol, &mut Vec::new()))
+					.copied()
+			}),
+			// Recurrences only refer to types enclosed by the compacted type definition.
+			InlinedRef::Recursive(_) => None,
+		};
+		match symbol {
+			Some(symbol) => symbol,
+			None => {
+				self.resolved = false;
+				unresolved()
+			}
+		}
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: Expanded) -> UntrackedSymbol<AnyTypeId> {
+		self.map_type_id(type_id)
+	}
+}
+
+/// Replaces the types referred to by a compact type with a placeholder and collects
+/// them in order.
+///
+/// Types differing only in the types they refer to thus share their shape.
+#[derive(Default)]
+struct Shape(Vec<UntrackedSymbol<AnyTypeId>>);
+
+impl SymbolMap for Shape {
+	fn map_string(&mut self, symbol: UntrackedSymbol<String>) -> UntrackedSymbol<String> {
+		symbol
+	}
+
+	fn map_type(&mut self, symbol: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		self.0.push(symbol);
+		unresolved()
+	}
+}
+
+/// Converts an inlined type into its shape, see `Shape`, and collects the types
+/// it inlines in order.
+struct InlinedShape<'c, 'a> {
+	compactor: &'c mut Compactor<'a>,
+	inlined: Vec<Expanded>,
+}
+
+impl FormMap<DisplayForm, CompactForm> for InlinedShape<'_, '_> {
+	fn map_string(&mut self, string: String) -> UntrackedSymbol<String> {
+		self.compactor.map_string(string)
+	}
+
+	fn map_type_id(&mut self, type_id: Expanded) -> UntrackedSymbol<AnyTypeId> {
+		self.inlined.push(type_id);
+		unresolved()
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: Expanded) -> UntrackedSymbol<AnyTypeId> {
+		self.map_type_id(type_id)
+	}
+}
+
+/// An inlined type flattened into its shape and the types it inlines in order.
+struct Inlined {
+	/// The index of the shape, or `None` if no registered type has this shape.
+	shape: Option<usize>,
+	inlined: Vec<InlinedRef>,
+}
+
+/// A type inlined by a flattened type.
+#[derive(PartialEq, Eq)]
+enum InlinedRef {
+	/// The index of the flattened type.
+	Type(usize),
+	/// A recurrence of an enclosing type, see `Expanded::Recursive`.
+	Recursive(usize),
+}
+
+/// Returns the placeholder for symbols that cannot be resolved.
+///
+/// Conversions yielding this symbol are discarded as a whole.
+fn unresolved<T>() -> UntrackedSymbol<T> {
+	UntrackedSymbol::from_id(NonZeroU32::new(u32::MAX).expect("`u32::MAX` is not zero"))
+}
+
+/// Collects the types referred to by type identifiers and definitions.
+///
+/// Leaves the type identifiers and definitions unchanged.
+#[derive(Default)]
+struct Referred(Vec<MetaType>);
+
+impl FormMap<MetaForm, MetaForm> for Referred {
+	fn map_string(&mut self, string: Cow<'static, str>) -> Cow<'static, str> {
+		string
+	}
+
+	fn map_type_id(&mut self, type_id: MetaType) -> MetaType {
+		self.0.push(type_id);
+		type_id
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: MetaType) -> MetaType {
+		self.map_type_id(type_id)
+	}
+}
+
+/// An error upon registering a type the registry refuses.
+#[derive(PartialEq, Eq, Debug)]
+pub enum RegisterError {
+	/// The custom type is rejected by the namespace filter.
+	Rejected {
+		/// The identifier of the rejected type.
+		type_id: TypeIdCustom,
+	},
+	/// The type is described lossily which strict registries refuse.
+	Lossy {
+		/// The identifier of the refused type.
+		type_id: TypeId,
+		/// Why the type is described lossily.
+		reason: &'static str,
+	},
+}
+
+/// Restricts the namespaces of the custom types that may be registered.
+///
+/// Patterns are module paths such as `my_chain::api` that match exactly this
+/// namespace, or end with `::*` such as `my_chain::*` to also match all nested namespaces.
+///
+/// A namespace is accepted if it matches any allowed pattern, or no patterns are
+/// allowed at all, and if it matches no denied pattern.
+/// Types of the prelude (root) namespace are always accepted.
+///
+/// # Example
+///
+/// ```
+/// # use type_metadata::NamespaceFilter;
+/// let filter = NamespaceFilter::new().allow("my_chain::*").deny("my_chain::tests::*");
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct NamespaceFilter {
+	allowed: Vec<&'static str>,
+	denied: Vec<&'static str>,
+}
+
+impl NamespaceFilter {
+	/// Creates a new filter that accepts all namespaces.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Allows the namespaces matching the given pattern.
+	pub fn allow(mut self, pattern: &'static str) -> Self {
+		self.allowed.push(pattern);
+		self
+	}
+
+	/// Denies the namespaces matching the given pattern.
+	pub fn deny(mut self, pattern: &'static str) -> Self {
+		self.denied.push(pattern);
+		self
+	}
+
+	/// Returns `true` if types of the given namespace may be registered.
+	pub fn accepts(&self, namespace: &Namespace) -> bool {
+		let segments = namespace.segments();
+		if segments.is_empty() {
+			return true;
+		}
+		let matches = |pattern: &&'static str| Self::matches(pattern, segments);
+		(self.allowed.is_empty() || self.allowed.iter().any(matches)) && !self.denied.iter().any(matches)
+	}
+
+	/// Returns `true` if the pattern matches the namespace given by its segments.
+	fn matches(pattern: &str, segments: &[Cow<'static, str>]) -> bool {
+		let prefix = pattern.trim_end_matches("::*");
+		let nested = prefix.len() != pattern.len();
+		let mut pattern_segments = prefix.split("::");
+		let mut namespace_segments = segments.iter();
+		loop {
+			match (pattern_segments.next(), namespace_segments.next()) {
+				(Some(expected), Some(segment)) if expected == segment => (),
+				(None, None) => return true,
+				(None, Some(_)) => return nested,
+				_ => return false,
+			}
+		}
+	}
 }
 
 /// The registry for compaction of type identifiers and definitions.
@@ -73,11 +673,29 @@ pub struct TypeIdDef {
 ///
 /// A type can be a sub-type of itself. In this case the registry has a builtin
 /// mechanism to stop recursion before going into an infinite loop.
-#[derive(Debug, PartialEq, Eq, Serialize)]
+///
+/// # Ordering
+///
+/// Strings and types are serialized in the order in which they have first been
+/// registered, which is also the order of their symbols. Sub-types are registered
+/// depth-first in the order of the type identifier and definition referring to them.
+/// Capabilities are serialized sorted by their names.
+///
+/// Therefore registering the same types in the same order always yields byte-identical
+/// serialized registries, e.g. to hash them. Registering them in a different order may
+/// assign different symbols.
+///
+/// # Symbols
+///
+/// Symbols are serialized as their plain identifier which starts at 1.
+/// The string symbol with identifier `n` refers to the string at index `n - 1` of the
+/// serialized `strings`, the type symbol with identifier `n` to the type at index
+/// `n - 1` of the serialized `types`.
+#[derive(Debug, Serialize)]
 pub struct Registry {
 	/// The cache for already registered strings.
 	#[serde(rename = "strings")]
-	string_table: Interner<&'static str>,
+	string_table: Interner<Cow<'static, str>>,
 	/// The cache for already registered types.
 	///
 	/// This is just an accessor to the actual database
@@ -89,6 +707,31 @@ pub struct Registry {
 	/// This is going to be serialized upon serlialization.
 	#[serde(serialize_with = "serialize_registry_types")]
 	types: BTreeMap<UntrackedSymbol<core::any::TypeId>, TypeIdDef>,
+	/// The filter for the namespaces of registered custom types.
+	#[serde(skip)]
+	namespace_filter: NamespaceFilter,
+	/// Whether lossily described types are refused.
+	#[serde(skip)]
+	strict: bool,
+	/// The sequence numbers of the registered types, indexed by the identifiers of their symbols.
+	#[serde(skip)]
+	sequences: Vec<u64>,
+	/// The sequence number of the most recently registered type.
+	#[serde(skip)]
+	sequence: u64,
+	/// The identifiers and definitions of the types that have been checked not to be
+	/// refused but are not registered yet.
+	///
+	/// Allows to check all types referred to by a type before registering any of them
+	/// without describing them again upon their registration.
+	#[serde(skip)]
+	checked: BTreeMap<AnyTypeId, (TypeId, TypeDef)>,
+	/// The index of types by the capabilities they have been tagged with.
+	///
+	/// Allows renderers to look up all types requiring special treatment
+	/// such as `"timestamp"` without scanning every registered type.
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	capabilities: BTreeMap<&'static str, Vec<UntrackedSymbol<AnyTypeId>>>,
 }
 
 /// Serializes the types of the registry by removing their unique IDs
@@ -104,6 +747,39 @@ where
 	types.serialize(serializer)
 }
 
+/// Encodes the registry just as it is serialized: the strings, then the types
+/// in order of their symbols and finally the capability index.
+///
+/// # Note
+///
+/// There is no `Decode` counterpart since the registry caches Rust type identifiers
+/// and static strings that cannot be restored from their encoding. Decode it as
+/// `RegistryReadOnly` instead.
+#[cfg(feature = "codec")]
+impl Encode for Registry {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		self.string_table.elements().encode_to(dest);
+		self.types.values().collect::<Vec<_>>().encode_to(dest);
+		self.capabilities.encode_to(dest);
+	}
+}
+
+/// Registries are equal if they store the same strings, types and capabilities and
+/// refuse the same types, independent of the sequence numbers of their types and
+/// of the Rust types of removed duplicates, see `dedup_types`.
+impl PartialEq for Registry {
+	fn eq(&self, other: &Self) -> bool {
+		self.string_table == other.string_table
+			&& self.type_table.elements() == other.type_table.elements()
+			&& self.types == other.types
+			&& self.namespace_filter == other.namespace_filter
+			&& self.strict == other.strict
+			&& self.capabilities == other.capabilities
+	}
+}
+
+impl Eq for Registry {}
+
 impl Default for Registry {
 	fn default() -> Self {
 		Self::new()
@@ -117,13 +793,44 @@ impl Registry {
 			string_table: Interner::new(),
 			type_table: Interner::new(),
 			types: BTreeMap::new(),
+			namespace_filter: NamespaceFilter::new(),
+			strict: false,
+			sequences: Vec::new(),
+			sequence: 0,
+			checked: BTreeMap::new(),
+			capabilities: BTreeMap::new(),
 		}
 	}
 
+	/// Restricts the namespaces of the custom types that may be registered.
+	///
+	/// The registry refuses to register custom types rejected by the filter, see
+	/// `try_register_type`, so that they never end up in the registry or its serialized form.
+	pub fn with_namespace_filter(mut self, namespace_filter: NamespaceFilter) -> Self {
+		self.namespace_filter = namespace_filter;
+		self
+	}
+
+	/// Refuses to register types that are described lossily.
+	///
+	/// Lossy types are types whose type definitions omit or approximate parts of them,
+	/// such as skipped fields or variants, fields described as opaque types and pointer-sized
+	/// integers described as 64-bit integers, see `HasTypeDef::lossy`. Types containing lossy
+	/// types are refused as well. `PhantomData` markers are not lossy since they encode to nothing.
+	/// A strict registry thus guarantees that all of its types are faithful descriptions of their
+	/// encoding. Use `try_register_type` in order to handle refused types.
+	pub fn strict(mut self) -> Self {
+		self.strict = true;
+		self
+	}
+
 	/// Registeres the given string into the registry and returns
 	/// its respective associated string symbol.
-	pub fn register_string(&mut self, string: &'static str) -> UntrackedSymbol<&'static str> {
-		self.string_table.intern_or_get(string).1.into_untracked()
+	pub fn register_string<S>(&mut self, string: S) -> UntrackedSymbol<String>
+	where
+		S: Into<Cow<'static, str>>,
+	{
+		UntrackedSymbol::from_id(self.string_table.intern_or_get(string.into()).1.into_untracked().id())
 	}
 
 	/// Registeres the given type ID into the registry.
@@ -137,23 +844,55 @@ impl Registry {
 	/// This is an internal API and should not be called directly from the outside.
 	fn intern_type_id(&mut self, any_type_id: AnyTypeId) -> (bool, UntrackedSymbol<AnyTypeId>) {
 		let (inserted, symbol) = self.type_table.intern_or_get(any_type_id);
+		if inserted {
+			self.sequence += 1;
+			self.sequences.push(self.sequence);
+		}
 		(inserted, symbol.into_untracked())
 	}
 
 	/// Registers the given type into the registry and returns
 	/// its associated type ID symbol.
 	///
-	/// # Note
+	/// The returned symbol can be resolved back to the type identifier and
+	/// definition via `resolve_type_id` and `resolve`. Its identifier is the index
+	/// by which the type is referred to in the serialized registry.
+	///
+	/// The type identifier and definition are only compacted upon the first
+	/// registration of a type. Later registrations, including all references from
+	/// other types, look up the cached symbol by the type's `any_id`, so compacting
+	/// a type graph takes time linear in the number of distinct types.
 	///
-	/// Due to safety requirements the returns type ID symbol cannot
-	/// be used later to resolve back to the associated type definition.
-	/// However, since this facility is going to be used for serialization
-	/// purposes this functionality isn't needed anyway.
+	/// # Panics
+	///
+	/// If the registry refuses the type or any type it refers to, see `try_register_type`.
+	/// No type is registered then.
 	pub fn register_type(&mut self, ty: &MetaType) -> UntrackedSymbol<AnyTypeId> {
+		match self.try_register_type(ty) {
+			Ok(symbol) => symbol,
+			Err(err) => panic!("registry refuses to register type: {:?}", err),
+		}
+	}
+
+	/// Registers the given type into the registry and returns its associated type ID symbol.
+	///
+	/// Returns an error instead if the registry refuses the type or any type it refers to,
+	/// i.e. custom types rejected by the namespace filter and lossily described types
+	/// refused by strict registries. No type is registered then.
+	pub fn try_register_type(&mut self, ty: &MetaType) -> Result<UntrackedSymbol<AnyTypeId>, RegisterError> {
+		self.check_types(vec![*ty])?;
 		let (inserted, symbol) = self.intern_type_id(ty.any_id());
 		if inserted {
-			let compact_id = ty.type_id().into_compact(self);
-			let compact_def = ty.type_def().into_compact(self);
+			let (type_id, type_def) = self
+				.checked
+				.remove(&ty.any_id())
+				.expect("unregistered types have been checked above");
+			let compact_id = type_id
+				.into_compact(self)
+				.expect("types referred to by checked types are checked as well");
+			let compact_def = type_def
+				.into_compact(self)
+				.expect("types referred to by checked types are checked as well");
 			self.types.insert(
 				symbol,
 				TypeIdDef {
@@ -162,6 +901,838 @@ impl Registry {
 				},
 			);
 		}
-		symbol
+		Ok(symbol)
+	}
+
+	/// Returns an error if the registry refuses any type referred to by the given
+	/// type identifier or definition, or any type these refer to in turn.
+	pub(crate) fn check_referred<T>(&mut self, value: &T) -> Result<(), RegisterError>
+	where
+		T: ConvertForm<MetaForm, MetaForm> + Clone,
+	{
+		let mut referred = Referred::default();
+		value.clone().convert_form(&mut referred);
+		self.check_types(referred.0)
+	}
+
+	/// Returns an error if the registry refuses any of the given types or any type they refer to.
+	///
+	/// Types that have been registered or checked already are not checked again,
+	/// so that checking all types of a type graph takes time linear in their number.
+	fn check_types(&mut self, types: Vec<MetaType>) -> Result<(), RegisterError> {
+		let mut visited = BTreeMap::new();
+		let mut pending = types;
+		while let Some(ty) = pending.pop() {
+			let any_id = ty.any_id();
+			if self.type_table.get(&any_id).is_some()
+				|| self.checked.contains_key(&any_id)
+				|| visited.contains_key(&any_id)
+			{
+				continue;
+			}
+			let type_id = ty.type_id();
+			self.check_refused(&ty, &type_id)?;
+			let type_def = ty.type_def();
+			let mut referred = Referred::default();
+			type_id.clone().convert_form(&mut referred);
+			type_def.clone().convert_form(&mut referred);
+			pending.extend(referred.0);
+			visited.insert(any_id, (type_id, type_def));
+		}
+		self.checked.extend(visited);
+		Ok(())
+	}
+
+	/// Returns an error if the registry refuses the given type itself.
+	fn check_refused(&self, ty: &MetaType, type_id: &TypeId) -> Result<(), RegisterError> {
+		if let TypeId::Custom(custom) = type_id {
+			if !self.namespace_filter.accepts(custom.namespace()) {
+				return Err(RegisterError::Rejected {
+					type_id: custom.clone(),
+				});
+			}
+		}
+		if self.strict {
+			if let Some(reason) = ty.lossy() {
+				return Err(RegisterError::Lossy {
+					type_id: type_id.clone(),
+					reason,
+				});
+			}
+		}
+		Ok(())
+	}
+
+	/// Registers all given types into the registry.
+	///
+	/// Returns the associated type ID symbols in the order of the types.
+	/// Returns an error instead if the registry refuses any of the types or any type
+	/// they refer to, see `try_register_type`. No type is registered then.
+	pub fn register_types<I>(&mut self, types: I) -> Result<Vec<UntrackedSymbol<AnyTypeId>>, RegisterError>
+	where
+		I: IntoIterator<Item = MetaType>,
+	{
+		let types = types.into_iter().collect::<Vec<_>>();
+		self.check_types(types.clone())?;
+		types.iter().map(|ty| self.try_register_type(ty)).collect()
+	}
+
+	/// Registers the given type and tags it with the given capability.
+	///
+	/// Capabilities are free-form tags such as `"encode-as-hex"` or `"timestamp"`
+	/// that signal consumers of the registry to treat the type specially.
+	/// Returns an error if the registry refuses the type, see `try_register_type`.
+	pub fn tag_type(
+		&mut self,
+		ty: &MetaType,
+		capability: &'static str,
+	) -> Result<UntrackedSymbol<AnyTypeId>, RegisterError> {
+		let symbol = self.try_register_type(ty)?;
+		let tagged = self.capabilities.entry(capability).or_default();
+		if !tagged.contains(&symbol) {
+			tagged.push(symbol);
+		}
+		Ok(symbol)
+	}
+
+	/// Returns the symbols of all types tagged with the given capability
+	/// in the order in which they have been tagged.
+	pub fn types_with_capability(&self, capability: &str) -> &[UntrackedSymbol<AnyTypeId>] {
+		self.capabilities.get(capability).map_or(&[], Vec::as_slice)
+	}
+
+	/// Resolves the given type symbol of this registry to the definition of the type.
+	///
+	/// Returns `None` if no type has been registered for the symbol.
+	pub fn resolve(&self, symbol: &UntrackedSymbol<AnyTypeId>) -> Option<&TypeDef<CompactForm>> {
+		self.types.get(symbol).map(TypeIdDef::def)
+	}
+
+	/// Resolves the given type symbol of this registry to the identifier of the type.
+	///
+	/// Returns `None` if no type has been registered for the symbol.
+	pub fn resolve_type_id(&self, symbol: &UntrackedSymbol<AnyTypeId>) -> Option<&TypeId<CompactForm>> {
+		self.types.get(symbol).map(TypeIdDef::id)
+	}
+
+	/// Returns the definition of the type with the given symbol identifier.
+	///
+	/// This is the numeric identifier by which the type is referred to in the
+	/// serialized registry. Returns `None` if no type has been registered for it.
+	pub fn type_def_of(&self, id: u32) -> Option<&TypeDef<CompactForm>> {
+		self.resolve(&UntrackedSymbol::from_id(NonZeroU32::new(id)?))
+	}
+
+	/// Returns the identifier of the type with the given symbol identifier.
+	///
+	/// This is the numeric identifier by which the type is referred to in the
+	/// serialized registry. Returns `None` if no type has been registered for it.
+	pub fn type_id_of(&self, id: u32) -> Option<&TypeId<CompactForm>> {
+		self.resolve_type_id(&UntrackedSymbol::from_id(NonZeroU32::new(id)?))
+	}
+
+	/// Resolves the given string symbol of this registry.
+	///
+	/// Returns `None` if no string has been registered for the symbol.
+	pub fn resolve_string(&self, symbol: &UntrackedSymbol<String>) -> Option<&str> {
+		self.string_table.resolve_id(symbol.id()).map(AsRef::as_ref)
+	}
+
+	/// Returns all registered strings in the order of their symbols.
+	///
+	/// The string at index `i` is referred to by the symbol with identifier `i + 1`.
+	pub fn strings(&self) -> &[Cow<'static, str>] {
+		self.string_table.elements()
+	}
+
+	/// Returns all registered types in the order of their registration.
+	///
+	/// Every type is yielded together with its symbol by which it is referred to
+	/// within the type identifiers and definitions of the registry.
+	pub fn types(
+		&self,
+	) -> impl Iterator<Item = (UntrackedSymbol<AnyTypeId>, &TypeId<CompactForm>, &TypeDef<CompactForm>)> {
+		self.types
+			.iter()
+			.map(|(symbol, type_id_def)| (*symbol, type_id_def.id(), type_id_def.def()))
+	}
+
+	/// Returns the content identifiers of all registered types in the order of their symbols.
+	///
+	/// See the `content_id` module for how content identifiers are derived.
+	pub fn content_ids(&self) -> Vec<ContentId> {
+		canonical::content_ids(self.strings(), &self.types.values().collect::<Vec<_>>())
+	}
+
+	/// Returns the given type in the display form with all referred types inlined.
+	///
+	/// Returns `None` if the type has not been registered into this registry.
+	pub fn expand(&self, ty: &UntrackedSymbol<AnyTypeId>) -> Option<TypeIdDef<DisplayForm>> {
+		let type_id_def = self.types.get(ty)?.clone();
+		let mut expander = Expander::new(self);
+		expander.enclosing.push((*ty, false));
+		Some(type_id_def.convert_form(&mut expander))
+	}
+
+	/// Returns the given compact type definition in the display form with all referred types inlined.
+	///
+	/// In contrast to `expand` the type definition does not need to be registered itself,
+	/// e.g. if it has been compacted separately or loaded from elsewhere.
+	/// Returns `None` if it refers to strings or types not registered into this registry.
+	pub fn expand_type_def(&self, type_def: &TypeDef<CompactForm>) -> Option<TypeDef<DisplayForm>> {
+		let mut expander = Expander::new(self);
+		let expanded = type_def.clone().convert_form(&mut expander);
+		if expander.resolved {
+			Some(expanded)
+		} else {
+			None
+		}
+	}
+
+	/// Returns the given display form type definition in the compact form of this registry.
+	///
+	/// This is the inverse of `expand_type_def`: every inlined type is replaced by the
+	/// symbol of the registered type it is expanded from. Together they allow to normalize
+	/// type definitions of mixed forms, e.g. to compare a `MetaForm` definition compacted
+	/// via `IntoCompact` against a stored one.
+	/// Returns `None` if it refers to strings or types not registered into this registry.
+	pub fn compact_type_def(&self, type_def: TypeDef<DisplayForm>) -> Option<TypeDef<CompactForm>> {
+		let mut compactor = Compactor::new(self);
+		let compacted = type_def.convert_form(&mut compactor);
+		if compactor.resolved {
+			Some(compacted)
+		} else {
+			None
+		}
+	}
+
+	/// Returns the given compact type identifier in the display form with all referred types inlined.
+	///
+	/// See `expand_type_def` for details.
+	pub fn expand_type_id(&self, type_id: &TypeId<CompactForm>) -> Option<TypeId<DisplayForm>> {
+		let mut expander = Expander::new(self);
+		let expanded = type_id.clone().convert_form(&mut expander);
+		if expander.resolved {
+			Some(expanded)
+		} else {
+			None
+		}
+	}
+
+	/// Returns the given display form type identifier in the compact form of this registry.
+	///
+	/// This is the inverse of `expand_type_id`, see `compact_type_def` for details.
+	pub fn compact_type_id(&self, type_id: TypeId<DisplayForm>) -> Option<TypeId<CompactForm>> {
+		let mut compactor = Compactor::new(self);
+		let compacted = type_id.convert_form(&mut compactor);
+		if compactor.resolved {
+			Some(compacted)
+		} else {
+			None
+		}
+	}
+
+	/// Returns all registered types in the portable form in the order of their symbols.
+	///
+	/// The portable types own their strings and refer to other types by the same
+	/// symbols as the registered types. They can be turned into a registry again
+	/// via `RegistryReadOnly::from_portable_types`.
+	pub fn portable_types(&self) -> Vec<TypeIdDef<PortableForm>> {
+		portable_types(self.strings(), self.types.values()).expect("registered strings are always resolvable")
+	}
+
+	/// Freezes the registry into a form that stores all strings in a single buffer.
+	///
+	/// The strings are concatenated in the order of their symbols and the frozen
+	/// types refer to them by their offsets and lengths, see `RegistryFrozen`.
+	///
+	/// # Panics
+	///
+	/// If the strings of the registry exceed `u32::MAX` bytes in total.
+	pub fn freeze(&self) -> RegistryFrozen {
+		let len = self.strings().iter().map(|string| string.len()).sum::<usize>();
+		assert!(len <= u32::MAX as usize, "frozen strings are limited to 4 GiB");
+		let mut strings = String::with_capacity(len);
+		let refs = self
+			.strings()
+			.iter()
+			.map(|string| {
+				let offset = strings.len() as u32;
+				strings.push_str(string);
+				StrRef::new(offset, string.len() as u32)
+			})
+			.collect::<Vec<_>>();
+		let types = self
+			.types
+			.values()
+			.map(|type_id_def| {
+				type_id_def
+					.clone()
+					.convert_form(&mut MapStrings(|symbol: UntrackedSymbol<String>| {
+						refs[symbol.id().get() as usize - 1]
+					}))
+			})
+			.collect();
+		RegistryFrozen { strings, types }
+	}
+
+	/// Returns the fingerprint of the registry.
+	///
+	/// The fingerprint is the BLAKE2b-256 digest of a canonical encoding of all strings,
+	/// types and capabilities in their serialized order. It can be published instead of
+	/// the registry itself in order to verify registries loaded from their serialized
+	/// form via `RegistryReadOnly::hash`, independent of the format they are serialized in.
+	/// Documentation is always encoded, it is merely left empty by the derive unless the
+	/// `docs` feature is enabled.
+	pub fn hash(&self) -> [u8; 32] {
+		canonical::fingerprint(
+			self.strings(),
+			&self.types.values().collect::<Vec<_>>(),
+			self.capabilities
+				.iter()
+				.map(|(capability, tagged)| (*capability, tagged.as_slice())),
+		)
+	}
+
+	/// Merges the other registry into this registry.
+	///
+	/// Strings and types that have already been registered into this registry
+	/// are deduplicated, all others are appended in the order of the other registry.
+	/// The symbols within the absorbed type identifiers and definitions are remapped
+	/// accordingly. The capabilities of the other registry are carried over whereas its
+	/// namespace filter is discarded. The absorbed types are not checked again against
+	/// the namespace filter of this registry or its strict mode.
+	pub fn merge(&mut self, other: Registry) {
+		let mut symbols = Remap::default();
+		for string in other.string_table.elements() {
+			symbols.strings.push(Some(self.register_string(string.clone())));
+		}
+		let mut inserted = Vec::new();
+		for any_type_id in other.type_table.elements() {
+			let (is_new, symbol) = self.intern_type_id(*any_type_id);
+			inserted.push(is_new);
+			symbols.types.push(Some(symbol));
+		}
+		self.alias_types(&other.type_table, &symbols);
+		for (symbol, mut type_id_def) in other.types {
+			let index = symbol.id().get() as usize - 1;
+			if inserted[index] {
+				let symbol = symbols.map_type(symbol);
+				type_id_def.map_symbols(&mut symbols);
+				self.types.insert(symbol, type_id_def);
+			}
+		}
+		for (capability, tagged) in other.capabilities {
+			let merged = self.capabilities.entry(capability).or_default();
+			for symbol in tagged {
+				let symbol = symbols.map_type(symbol);
+				if !merged.contains(&symbol) {
+					merged.push(symbol);
+				}
+			}
+		}
+	}
+
+	/// Retains only the types reachable from the given root types and the strings they use.
+	///
+	/// Root types that have not been registered yet are registered first.
+	/// The remaining strings and types keep their relative order but are assigned
+	/// new consecutive symbols, so symbols obtained before pruning must not be used
+	/// with the pruned registry. Capabilities of removed types are removed as well.
+	///
+	/// Returns an error if the registry refuses a root type, see `register_types`.
+	/// The registry is left unchanged then.
+	pub fn retain_reachable<I>(&mut self, roots: I) -> Result<(), RegisterError>
+	where
+		I: IntoIterator<Item = MetaType>,
+	{
+		let roots = self.register_types(roots)?;
+		let mut reachable = Reachable {
+			strings: vec![false; self.string_table.elements().len()],
+			types: vec![false; self.type_table.elements().len()],
+			pending: Vec::new(),
+		};
+		for root in roots {
+			reachable.map_type(root);
+		}
+		while let Some(symbol) = reachable.pending.pop() {
+			if let Some(type_id_def) = self.types.get_mut(&symbol) {
+				type_id_def.map_symbols(&mut reachable);
+			}
+		}
+
+		let mut pruned = Registry::new();
+		let mut symbols = Remap::default();
+		for (string, is_reachable) in self.string_table.elements().iter().zip(reachable.strings) {
+			symbols.strings.push(if is_reachable {
+				Some(pruned.register_string(string.clone()))
+			} else {
+				None
+			});
+		}
+		let mut sequences = Vec::new();
+		for ((any_type_id, sequence), is_reachable) in self
+			.type_table
+			.elements()
+			.iter()
+			.zip(&self.sequences)
+			.zip(reachable.types)
+		{
+			symbols.types.push(if is_reachable {
+				sequences.push(*sequence);
+				Some(pruned.intern_type_id(*any_type_id).1)
+			} else {
+				None
+			});
+		}
+		pruned.alias_types(&self.type_table, &symbols);
+		for (symbol, mut type_id_def) in mem::take(&mut self.types) {
+			if let Some(symbol) = symbols.get_type(symbol) {
+				type_id_def.map_symbols(&mut symbols);
+				pruned.types.insert(symbol, type_id_def);
+			}
+		}
+		for (capability, tagged) in mem::take(&mut self.capabilities) {
+			let tagged = tagged
+				.into_iter()
+				.filter_map(|symbol| symbols.get_type(symbol))
+				.collect::<Vec<_>>();
+			if !tagged.is_empty() {
+				pruned.capabilities.insert(capability, tagged);
+			}
+		}
+		pruned.namespace_filter = mem::replace(&mut self.namespace_filter, NamespaceFilter::new());
+		pruned.strict = self.strict;
+		pruned.sequences = sequences;
+		pruned.sequence = self.sequence;
+		*self = pruned;
+		Ok(())
+	}
+
+	/// Removes types that are structurally equal to a previously registered type.
+	///
+	/// Types are registered per Rust type, so e.g. the same type of two versions of a
+	/// crate is registered twice. Two types are structurally equal if their compacted
+	/// identifiers and definitions are equal when all types they refer to are replaced
+	/// by the first type structurally equal to them. This also covers recursive types.
+	///
+	/// The remaining types keep their relative order but are assigned new consecutive
+	/// symbols, so symbols obtained before deduplication must not be used with the
+	/// deduplicated registry. Registering the Rust type of a removed type afterwards
+	/// returns the symbol of the remaining type, whereas other types registered
+	/// afterwards are not deduplicated again.
+	pub fn dedup_types(&mut self) {
+		let classes = self.structural_classes();
+
+		let mut deduped = Registry::new();
+		let mut symbols = Remap::default();
+		for string in self.string_table.elements() {
+			symbols.strings.push(Some(deduped.register_string(string.clone())));
+		}
+		let mut sequences = Vec::new();
+		for (((symbol, any_type_id), sequence), class) in self
+			.types
+			.keys()
+			.zip(self.type_table.elements())
+			.zip(&self.sequences)
+			.zip(&classes)
+		{
+			let deduped_symbol = if symbol == class {
+				sequences.push(*sequence);
+				deduped.intern_type_id(*any_type_id).1
+			} else {
+				// The removed type resolves to the remaining one upon later registrations.
+				let deduped_symbol = symbols.map_type(*class);
+				deduped.type_table.alias(*any_type_id, deduped_symbol);
+				deduped_symbol
+			};
+			symbols.types.push(Some(deduped_symbol));
+		}
+		deduped.alias_types(&self.type_table, &symbols);
+		for (symbol, mut type_id_def) in mem::take(&mut self.types) {
+			if symbol == classes[symbol.id().get() as usize - 1] {
+				type_id_def.map_symbols(&mut symbols);
+				deduped.types.insert(symbols.map_type(symbol), type_id_def);
+			}
+		}
+		for (capability, tagged) in mem::take(&mut self.capabilities) {
+			let merged = deduped.capabilities.entry(capability).or_default();
+			for symbol in tagged {
+				let symbol = symbols.map_type(symbol);
+				if !merged.contains(&symbol) {
+					merged.push(symbol);
+				}
+			}
+		}
+		deduped.namespace_filter = mem::replace(&mut self.namespace_filter, NamespaceFilter::new());
+		deduped.strict = self.strict;
+		deduped.sequences = sequences;
+		deduped.sequence = self.sequence;
+		*self = deduped;
+	}
+
+	/// Carries over the aliases of the given type table onto the remapped symbols.
+	///
+	/// Aliases of types that have not been remapped are dropped.
+	fn alias_types(&mut self, type_table: &Interner<AnyTypeId>, symbols: &Remap) {
+		for (any_type_id, symbol) in type_table.aliases() {
+			if let Some(symbol) = symbols.get_type(symbol) {
+				self.type_table.alias(*any_type_id, symbol);
+			}
+		}
+	}
+
+	/// Returns the first type structurally equal to each type, in order of the types.
+	///
+	/// Starts with all types in a single class and splits classes until all types of
+	/// a class are equal after mapping the types they refer to onto their classes.
+	fn structural_classes(&self) -> Vec<UntrackedSymbol<AnyTypeId>> {
+		let first = match self.types.keys().next() {
+			Some(first) => *first,
+			None => return Vec::new(),
+		};
+		let mut classes = vec![first; self.types.len()];
+		loop {
+			let mut refined = Vec::with_capacity(classes.len());
+			// The refined class of each type by its current class and its canonical shape.
+			let mut shapes = BTreeMap::new();
+			for (index, (symbol, type_id_def)) in self.types.iter().enumerate() {
+				let mut shape = type_id_def.clone();
+				shape.map_symbols(&mut Canonical { classes: &classes });
+				refined.push(*shapes.entry((classes[index], shape)).or_insert(*symbol));
+			}
+			if refined == classes {
+				return classes;
+			}
+			classes = refined;
+		}
+	}
+
+	/// Returns the differences between the types of this registry and the given newer registry.
+	///
+	/// See the `diff` module for how types are matched between the registries.
+	pub fn diff(&self, new: &Registry) -> RegistryDiff {
+		RegistryDiff::between(self, new)
+	}
+
+	/// Returns statistics about the contents of the registry.
+	///
+	/// See the `stats` module for how sizes are measured.
+	#[cfg(feature = "stats")]
+	pub fn stats(&self) -> RegistryStats {
+		stats::stats(
+			self.strings(),
+			&self.types.values().collect::<Vec<_>>(),
+			stats::serialized_len(self),
+		)
+	}
+
+	/// Checks the integrity of the registry and returns all errors found.
+	///
+	/// See the `validate` module for what makes a registry valid.
+	pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+		validate::validate(
+			self.string_table.elements().len(),
+			&self.types.values().collect::<Vec<_>>(),
+			self.capabilities
+				.iter()
+				.map(|(capability, tagged)| (*capability, tagged.as_slice())),
+		)
+	}
+
+	/// Returns the sequence number of the most recently registered type.
+	///
+	/// Every newly registered type is assigned the next higher sequence number
+	/// starting at `1`. A sequence number of `0` denotes an empty registry.
+	///
+	/// Sequence numbers are never reused. Types keep their sequence numbers when
+	/// others are removed via `retain_reachable` or `dedup_types` although their
+	/// symbols change, and the sequence number of the registry does not decrease.
+	pub fn sequence(&self) -> u64 {
+		self.sequence
+	}
+
+	/// Returns all types that have been registered after the given sequence number
+	/// together with their own sequence numbers in ascending order.
+	///
+	/// This allows to mirror the registry incrementally by remembering the latest
+	/// observed `sequence` and only syncing the newly registered types thereafter.
+	/// Removed types are not reported.
+	pub fn entries_since(&self, sequence: u64) -> impl Iterator<Item = (u64, &TypeIdDef)> {
+		let sequences = &self.sequences;
+		self.types
+			.iter()
+			.map(move |(symbol, type_id_def)| (sequences[symbol.id().get() as usize - 1], type_id_def))
+			.skip_while(move |&(entry_sequence, _)| entry_sequence <= sequence)
+	}
+}
+
+/// Collects the types into a new registry which accepts all types since it has
+/// no namespace filter and is not strict. Use `register_types` for other registries.
+impl iter::FromIterator<MetaType> for Registry {
+	fn from_iter<I>(iter: I) -> Self
+	where
+		I: IntoIterator<Item = MetaType>,
+	{
+		let mut registry = Self::new();
+		registry
+			.register_types(iter)
+			.expect("registries without namespace filter that are not strict accept all types");
+		registry
+	}
+}
+
+/// A registry loaded back from its serialized form.
+///
+/// Since the Rust types behind the serialized metadata are unknown no further types
+/// can be registered. Symbols still resolve just as in the originating `Registry`.
+///
+/// With the `codec` feature it can also be decoded from an encoded `Registry`.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+pub struct RegistryReadOnly {
+	/// The registered strings in order of their symbols.
+	strings: Vec<String>,
+	/// The registered types in order of their symbols.
+	types: Vec<TypeIdDef>,
+	/// The index of types by the capabilities they have been tagged with.
+	#[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+	capabilities: BTreeMap<String, Vec<UntrackedSymbol<AnyTypeId>>>,
+}
+
+impl RegistryReadOnly {
+	/// Returns all types in the order of their symbols.
+	///
+	/// The type at index `i` is referred to by the symbol with identifier `i + 1`.
+	pub fn types(&self) -> &[TypeIdDef] {
+		&self.types
+	}
+
+	/// Creates a registry from the given types in the portable form.
+	///
+	/// The type at index `i` is referred to by the symbol with identifier `i + 1`.
+	/// Strings are deduplicated and ordered by their first occurrence. The registry
+	/// has no capabilities and should be checked via `validate` if the types stem
+	/// from an untrusted source.
+	pub fn from_portable_types<I>(types: I) -> Self
+	where
+		I: IntoIterator<Item = TypeIdDef<PortableForm>>,
+	{
+		let mut strings = Interner::new();
+		let types = types
+			.into_iter()
+			.map(|type_id_def| {
+				type_id_def.convert_form(&mut MapStrings(|string: String| {
+					UntrackedSymbol::from_id(strings.intern_or_get(string).1.into_untracked().id())
+				}))
+			})
+			.collect();
+		Self {
+			strings: strings.elements().to_vec(),
+			types,
+			capabilities: BTreeMap::new(),
+		}
+	}
+
+	/// Returns all types in the portable form in the order of their symbols.
+	///
+	/// Returns `None` if a type refers to a string that is not part of the registry.
+	pub fn portable_types(&self) -> Option<Vec<TypeIdDef<PortableForm>>> {
+		portable_types(&self.strings, &self.types)
+	}
+
+	/// Returns the content identifiers of all types in the order of their symbols.
+	///
+	/// See the `content_id` module for how content identifiers are derived.
+	pub fn content_ids(&self) -> Vec<ContentId> {
+		canonical::content_ids(&self.strings, &self.types.iter().collect::<Vec<_>>())
+	}
+
+	/// Returns the fingerprint of the registry.
+	///
+	/// Equals the fingerprint of the `Registry` it has been serialized from.
+	/// See `Registry::hash` for how the fingerprint is derived.
+	pub fn hash(&self) -> [u8; 32] {
+		canonical::fingerprint(
+			&self.strings,
+			&self.types.iter().collect::<Vec<_>>(),
+			self.capabilities
+				.iter()
+				.map(|(capability, tagged)| (capability.as_str(), tagged.as_slice())),
+		)
+	}
+
+	/// Resolves the given type symbol to the definition of the type.
+	///
+	/// Returns `None` if no type is stored for the symbol.
+	pub fn resolve(&self, symbol: &UntrackedSymbol<AnyTypeId>) -> Option<&TypeDef<CompactForm>> {
+		self.type_def_of(symbol.id().get())
+	}
+
+	/// Resolves the given type symbol to the identifier of the type.
+	///
+	/// Returns `None` if no type is stored for the symbol.
+	pub fn resolve_type_id(&self, symbol: &UntrackedSymbol<AnyTypeId>) -> Option<&TypeId<CompactForm>> {
+		self.type_id_of(symbol.id().get())
+	}
+
+	/// Returns the definition of the type with the given symbol identifier.
+	///
+	/// Returns `None` if no type is stored for the identifier.
+	pub fn type_def_of(&self, id: u32) -> Option<&TypeDef<CompactForm>> {
+		self.type_id_def(id).map(TypeIdDef::def)
+	}
+
+	/// Returns the identifier of the type with the given symbol identifier.
+	///
+	/// Returns `None` if no type is stored for the identifier.
+	pub fn type_id_of(&self, id: u32) -> Option<&TypeId<CompactForm>> {
+		self.type_id_def(id).map(TypeIdDef::id)
+	}
+
+	/// Resolves the given string symbol.
+	///
+	/// Returns `None` if no string is stored for the symbol.
+	pub fn resolve_string(&self, symbol: &UntrackedSymbol<String>) -> Option<&str> {
+		self.strings.get(symbol.id().get() as usize - 1).map(String::as_str)
+	}
+
+	/// Returns all strings in the order of their symbols.
+	///
+	/// The string at index `i` is referred to by the symbol with identifier `i + 1`.
+	pub fn strings(&self) -> &[String] {
+		&self.strings
+	}
+
+	/// Returns the symbols of all types tagged with the given capability.
+	pub fn types_with_capability(&self, capability: &str) -> &[UntrackedSymbol<AnyTypeId>] {
+		self.capabilities.get(capability).map_or(&[], Vec::as_slice)
+	}
+
+	/// Checks the integrity of the registry and returns all errors found.
+	///
+	/// See the `validate` module for what makes a registry valid.
+	pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+		validate::validate(
+			self.strings.len(),
+			&self.types.iter().collect::<Vec<_>>(),
+			self.capabilities
+				.iter()
+				.map(|(capability, tagged)| (capability.as_str(), tagged.as_slice())),
+		)
+	}
+
+	fn type_id_def(&self, id: u32) -> Option<&TypeIdDef> {
+		self.types.get((id as usize).checked_sub(1)?)
+	}
+}
+
+/// A registry whose strings are stored in a single buffer, see `Registry::freeze`.
+///
+/// The frozen types refer to their strings by their byte offsets and lengths within
+/// the buffer instead of owning them. Thus the buffer can be kept apart from the
+/// types, e.g. memory-mapped on embedded devices, and resolved via `StrRef::resolve`.
+/// Capabilities are not carried over.
+///
+/// With the `codec` feature it can also be encoded and decoded.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+pub struct RegistryFrozen {
+	/// All strings concatenated in the order of their symbols.
+	strings: String,
+	/// The frozen types in order of their symbols.
+	types: Vec<TypeIdDef<FrozenForm>>,
+}
+
+impl RegistryFrozen {
+	/// Returns the buffer of all strings.
+	pub fn strings(&self) -> &str {
+		&self.strings
+	}
+
+	/// Returns all types in the order of their symbols.
+	///
+	/// The type at index `i` is referred to by the symbol with identifier `i + 1`.
+	pub fn types(&self) -> &[TypeIdDef<FrozenForm>] {
+		&self.types
+	}
+
+	/// Resolves the given string reference.
+	///
+	/// Returns `None` if the string is not within the buffer.
+	pub fn resolve_string(&self, string: &StrRef) -> Option<&str> {
+		string.resolve(&self.strings)
+	}
+
+	/// Resolves the given type symbol to the definition of the type.
+	///
+	/// Returns `None` if no type is stored for the symbol.
+	pub fn resolve(&self, symbol: &UntrackedSymbol<AnyTypeId>) -> Option<&TypeDef<FrozenForm>> {
+		self.type_id_def(symbol).map(TypeIdDef::def)
+	}
+
+	/// Resolves the given type symbol to the identifier of the type.
+	///
+	/// Returns `None` if no type is stored for the symbol.
+	pub fn resolve_type_id(&self, symbol: &UntrackedSymbol<AnyTypeId>) -> Option<&TypeId<FrozenForm>> {
+		self.type_id_def(symbol).map(TypeIdDef::id)
+	}
+
+	/// Returns all types in the portable form in the order of their symbols.
+	///
+	/// Returns `None` if a type refers to a string that is not within the buffer.
+	pub fn portable_types(&self) -> Option<Vec<TypeIdDef<PortableForm>>> {
+		let mut resolved = true;
+		let types = self
+			.types
+			.iter()
+			.map(|type_id_def| {
+				type_id_def
+					.clone()
+					.convert_form(&mut MapStrings(|string: StrRef| match self.resolve_string(&string) {
+						Some(string) => string.to_string(),
+						None => {
+							resolved = false;
+							String::new()
+						}
+					}))
+			})
+			.collect();
+		if resolved {
+			Some(types)
+		} else {
+			None
+		}
+	}
+
+	fn type_id_def(&self, symbol: &UntrackedSymbol<AnyTypeId>) -> Option<&TypeIdDef<FrozenForm>> {
+		self.types.get(symbol.id().get() as usize - 1)
+	}
+}
+
+/// Extension trait to register all types of an iterator into a registry.
+///
+/// # Example
+///
+/// ```
+/// # use type_metadata::{tuple_meta_type, Registry, RegisterTypes as _};
+/// let mut registry = Registry::new();
+/// let symbols = tuple_meta_type!(u32, Option<bool>).register_into(&mut registry).unwrap();
+/// assert_eq!(symbols.len(), 2);
+/// ```
+pub trait RegisterTypes {
+	/// Registers all types into the given registry.
+	///
+	/// Returns the associated type ID symbols in the order of the types, or an error
+	/// if the registry refuses any of them, see `Registry::register_types`.
+	fn register_into(self, registry: &mut Registry) -> Result<Vec<UntrackedSymbol<AnyTypeId>>, RegisterError>;
+}
+
+impl<I> RegisterTypes for I
+where
+	I: IntoIterator<Item = MetaType>,
+{
+	fn register_into(self, registry: &mut Registry) -> Result<Vec<UntrackedSymbol<AnyTypeId>>, RegisterError> {
+		registry.register_types(self)
 	}
 }
@@ -0,0 +1,172 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics about the contents of a registry.
+//!
+//! Statistics allow to track the size of the metadata, e.g. per pallet across releases.
+//! Sizes are measured in bytes of the JSON serialization. The sizes per namespace only
+//! cover the serialized types of the namespace and not the strings they refer to, since
+//! strings are shared between all types.
+//!
+//! Documentation is interned into the string table just as names, so that documentation
+//! shared by many fields or variants is only stored once. The documentation statistics
+//! report the bytes this saves compared to repeating the documentation at every use.
+//!
+//! # Example
+//!
+//! ```
+//! # use type_metadata::{tuple_meta_type, Registry};
+//! let registry = tuple_meta_type!(Option<u32>, bool).into_iter().collect::<Registry>();
+//! let stats = registry.stats();
+//! assert_eq!(stats.types, 3);
+//! assert_eq!(stats.namespaces[""].types, 1);
+//! ```
+
+use crate::tm_std::*;
+use crate::{form::CompactForm, interner::UntrackedSymbol, EnumVariant, TypeDef, TypeId, TypeIdDef};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Statistics about the contents of a registry.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct RegistryStats {
+	/// The number of registered types.
+	pub types: usize,
+	/// The number of registered strings.
+	pub strings: usize,
+	/// The number of bytes of the serialized registry.
+	pub serialized_bytes: usize,
+	/// The statistics of the custom types per namespace.
+	///
+	/// Namespaces are rendered as Rust paths such as `my_chain::balances`.
+	/// The root namespace is rendered as the empty string.
+	pub namespaces: BTreeMap<String, NamespaceStats>,
+	/// The statistics of the documentation of all types.
+	pub docs: DocStats,
+}
+
+/// Statistics about the documentation of all types, i.e. of the types themselves,
+/// their fields and their variants.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct DocStats {
+	/// The number of documentation lines referred to by types.
+	pub lines: usize,
+	/// The number of distinct documentation lines within the string table.
+	pub strings: usize,
+	/// The number of bytes of the serialized documentation lines if every type
+	/// stored its documentation itself.
+	pub inlined_bytes: usize,
+	/// The number of bytes of the serialized distinct documentation lines and
+	/// the symbols referring to them.
+	pub interned_bytes: usize,
+}
+
+impl DocStats {
+	/// Returns the number of bytes saved by interning the documentation.
+	pub fn saved_bytes(&self) -> usize {
+		self.inlined_bytes.saturating_sub(self.interned_bytes)
+	}
+}
+
+/// Statistics about the custom types of a single namespace.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct NamespaceStats {
+	/// The number of types within the namespace.
+	pub types: usize,
+	/// The number of bytes of the serialized types within the namespace.
+	pub serialized_bytes: usize,
+}
+
+/// Computes the statistics of the given strings and types.
+///
+/// The type at index `i` is the one with symbol identifier `i + 1`, likewise for the strings.
+pub(crate) fn stats<S: AsRef<str>>(strings: &[S], types: &[&TypeIdDef], serialized_bytes: usize) -> RegistryStats {
+	let mut stats = RegistryStats {
+		types: types.len(),
+		strings: strings.len(),
+		serialized_bytes,
+		namespaces: BTreeMap::new(),
+		docs: DocStats::default(),
+	};
+	let mut doc_strings = BTreeSet::new();
+	for type_id_def in types {
+		for doc in docs(type_id_def.def()) {
+			let line_bytes = strings
+				.get(doc.id().get() as usize - 1)
+				.map_or(0, |line| serialized_len(line.as_ref()));
+			stats.docs.lines += 1;
+			stats.docs.inlined_bytes += line_bytes;
+			stats.docs.interned_bytes += serialized_len(doc);
+			if doc_strings.insert(doc.id()) {
+				stats.docs.strings += 1;
+				stats.docs.interned_bytes += line_bytes;
+			}
+		}
+		if let TypeId::Custom(custom) = type_id_def.id() {
+			let namespace = custom
+				.namespace()
+				.segments()
+				.iter()
+				.filter_map(|segment| strings.get(segment.id().get() as usize - 1).map(AsRef::as_ref))
+				.collect::<Vec<_>>()
+				.join("::");
+			let namespace_stats = stats.namespaces.entry(namespace).or_default();
+			namespace_stats.types += 1;
+			namespace_stats.serialized_bytes += serialized_len(type_id_def);
+		}
+	}
+	stats
+}
+
+/// Returns the documentation lines of the given type definition, its fields and its variants.
+fn docs(def: &TypeDef<CompactForm>) -> Vec<&UntrackedSymbol<String>> {
+	let mut docs = Vec::new();
+	match def {
+		TypeDef::Builtin(_) => (),
+		TypeDef::Struct(r#struct) => {
+			docs.extend(r#struct.docs());
+			docs.extend(r#struct.fields().iter().flat_map(|field| field.docs()));
+		}
+		TypeDef::TupleStruct(tuple_struct) => docs.extend(tuple_struct.docs()),
+		TypeDef::ClikeEnum(clike_enum) => {
+			docs.extend(clike_enum.docs());
+			docs.extend(clike_enum.variants().iter().flat_map(|variant| variant.docs()));
+		}
+		TypeDef::Enum(r#enum) => {
+			docs.extend(r#enum.docs());
+			for variant in r#enum.variants() {
+				match variant {
+					EnumVariant::Unit(unit) => docs.extend(unit.docs()),
+					EnumVariant::Struct(r#struct) => {
+						docs.extend(r#struct.docs());
+						docs.extend(r#struct.fields().iter().flat_map(|field| field.docs()));
+					}
+					EnumVariant::TupleStruct(tuple_struct) => docs.extend(tuple_struct.docs()),
+				}
+			}
+		}
+		TypeDef::Union(union) => {
+			docs.extend(union.docs());
+			docs.extend(union.fields().iter().flat_map(|field| field.docs()));
+		}
+	}
+	docs
+}
+
+/// Returns the number of bytes of the JSON serialization of the given value.
+pub(crate) fn serialized_len<T: Serialize + ?Sized>(value: &T) -> usize {
+	serde_json::to_vec(value).map_or(0, |json| json.len())
+}
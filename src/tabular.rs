@@ -0,0 +1,348 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Relational representation of the types of a registry.
+//!
+//! The registered types are flattened into tables of types, type parameters,
+//! fields and variants which can be exported as CSV in order to query the
+//! type graph with SQL or spreadsheet tools.
+//!
+//! Types are referred to by their sequence numbers as returned by
+//! `Registry::entries_since`.
+//!
+//! # Example
+//!
+//! ```
+//! # use type_metadata::{tabular::Tables, tuple_meta_type, Registry};
+//! let registry = tuple_meta_type!(Option<u32>).into_iter().collect::<Registry>();
+//! let tables = Tables::from_registry(&registry);
+//! assert_eq!(tables.types.len(), 2);
+//! assert_eq!(tables.variants.len(), 2);
+//! ```
+
+use crate::tm_std::*;
+use crate::{
+	form::CompactForm, interner::UntrackedSymbol, EnumVariant, NamedField, Registry, TypeDef, TypeId, UnnamedField,
+};
+use core::fmt::Write;
+
+/// A registered type.
+#[derive(PartialEq, Eq, Debug)]
+pub struct TypeRow {
+	/// The sequence number of the type.
+	pub id: u64,
+	/// The kind of the type identifier, e.g. `custom` or `tuple`.
+	pub id_kind: &'static str,
+	/// The name of custom or primitive types.
+	pub name: Option<String>,
+	/// The namespace of custom types with segments separated by `::`.
+	pub namespace: Option<String>,
+	/// The length of array types.
+	pub len: Option<u64>,
+	/// The kind of the type definition, e.g. `struct` or `enum`.
+	pub def_kind: &'static str,
+}
+
+/// A type parameter of a custom type or an element type of a tuple, array or slice.
+#[derive(PartialEq, Eq, Debug)]
+pub struct ParamRow {
+	/// The sequence number of the parameterized type.
+	pub type_id: u64,
+	/// The position of the parameter.
+	pub position: usize,
+	/// The sequence number of the parameter type.
+	pub param_type: u64,
+}
+
+/// A field of a struct, tuple-struct, union or enum variant.
+#[derive(PartialEq, Eq, Debug)]
+pub struct FieldRow {
+	/// The sequence number of the type the field belongs to.
+	pub type_id: u64,
+	/// The position of the enum variant the field belongs to, if any.
+	pub variant: Option<usize>,
+	/// The position of the field.
+	pub position: usize,
+	/// The name of named fields.
+	pub name: Option<String>,
+	/// The sequence number of the field type.
+	pub field_type: u64,
+	/// Whether the field is encoded using the SCALE compact encoding.
+	pub compact: bool,
+}
+
+/// A variant of a C-like enum or enum.
+#[derive(PartialEq, Eq, Debug)]
+pub struct VariantRow {
+	/// The sequence number of the enum type.
+	pub type_id: u64,
+	/// The position of the variant.
+	pub position: usize,
+	/// The name of the variant.
+	pub name: String,
+	/// The discriminant of C-like enum variants or the explicit index of enum variants.
+	pub index: Option<u64>,
+}
+
+/// The tables of all types of a registry.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct Tables {
+	/// The registered types.
+	pub types: Vec<TypeRow>,
+	/// The type parameters and element types.
+	pub params: Vec<ParamRow>,
+	/// The fields of all types and variants.
+	pub fields: Vec<FieldRow>,
+	/// The variants of all enums.
+	pub variants: Vec<VariantRow>,
+}
+
+impl Tables {
+	/// Flattens all types of the registry into tables.
+	pub fn from_registry(registry: &Registry) -> Self {
+		let mut tables = Self::default();
+		for (id, type_id_def) in registry.entries_since(0) {
+			tables.push_type_id(registry, id, type_id_def.id(), type_id_def.def());
+			tables.push_type_def(registry, id, type_id_def.def());
+		}
+		tables
+	}
+
+	fn push_type_id(
+		&mut self,
+		registry: &Registry,
+		id: u64,
+		type_id: &TypeId<CompactForm>,
+		def: &TypeDef<CompactForm>,
+	) {
+		let mut row = TypeRow {
+			id,
+			id_kind: "",
+			name: None,
+			namespace: None,
+			len: None,
+			def_kind: def_kind(def),
+		};
+		let params: Vec<u64> = match type_id {
+			TypeId::Custom(custom) => {
+				row.id_kind = "custom";
+				row.name = registry.resolve_string(custom.name()).map(ToString::to_string);
+				let segments = custom
+					.namespace()
+					.segments()
+					.iter()
+					.filter_map(|segment| registry.resolve_string(segment))
+					.collect::<Vec<_>>();
+				row.namespace = Some(segments.join("::"));
+				custom.type_params().iter().map(sequence).collect()
+			}
+			TypeId::Slice(slice) => {
+				row.id_kind = "slice";
+				vec![sequence(slice.type_param())]
+			}
+			TypeId::Array(array) => {
+				row.id_kind = "array";
+				row.len = Some(array.len);
+				vec![sequence(&array.type_param)]
+			}
+			TypeId::Tuple(tuple) => {
+				row.id_kind = "tuple";
+				tuple.type_params.iter().map(sequence).collect()
+			}
+			TypeId::Primitive(primitive) => {
+				row.id_kind = "primitive";
+				row.name = Some(primitive.name().to_string());
+				Vec::new()
+			}
+		};
+		self.types.push(row);
+		self.params
+			.extend(params.into_iter().enumerate().map(|(position, param_type)| ParamRow {
+				type_id: id,
+				position,
+				param_type,
+			}));
+	}
+
+	fn push_type_def(&mut self, registry: &Registry, id: u64, def: &TypeDef<CompactForm>) {
+		match def {
+			TypeDef::Builtin(_) => (),
+			TypeDef::Struct(r#struct) => self.push_named_fields(registry, id, None, r#struct.fields()),
+			TypeDef::TupleStruct(tuple_struct) => self.push_unnamed_fields(id, None, tuple_struct.fields()),
+			TypeDef::Union(union) => self.push_named_fields(registry, id, None, union.fields()),
+			TypeDef::ClikeEnum(clike_enum) => {
+				for (position, variant) in clike_enum.variants().iter().enumerate() {
+					self.push_variant(registry, id, position, variant.name(), Some(variant.discriminant()));
+				}
+			}
+			TypeDef::Enum(r#enum) => {
+				for (position, variant) in r#enum.variants().iter().enumerate() {
+					match variant {
+						EnumVariant::Unit(unit) => {
+							self.push_variant(registry, id, position, unit.name(), unit.index());
+						}
+						EnumVariant::Struct(r#struct) => {
+							self.push_variant(registry, id, position, r#struct.name(), r#struct.index());
+							self.push_named_fields(registry, id, Some(position), r#struct.fields());
+						}
+						EnumVariant::TupleStruct(tuple_struct) => {
+							self.push_variant(registry, id, position, tuple_struct.name(), tuple_struct.index());
+							self.push_unnamed_fields(id, Some(position), tuple_struct.fields());
+						}
+					}
+				}
+			}
+		}
+	}
+
+	fn push_named_fields(
+		&mut self,
+		registry: &Registry,
+		id: u64,
+		variant: Option<usize>,
+		fields: &[NamedField<CompactForm>],
+	) {
+		self.fields
+			.extend(fields.iter().enumerate().map(|(position, field)| FieldRow {
+				type_id: id,
+				variant,
+				position,
+				name: registry.resolve_string(field.name()).map(ToString::to_string),
+				field_type: sequence(field.ty()),
+				compact: field.is_compact(),
+			}));
+	}
+
+	fn push_unnamed_fields(&mut self, id: u64, variant: Option<usize>, fields: &[UnnamedField<CompactForm>]) {
+		self.fields
+			.extend(fields.iter().enumerate().map(|(position, field)| FieldRow {
+				type_id: id,
+				variant,
+				position,
+				name: None,
+				field_type: sequence(field.ty()),
+				compact: field.is_compact(),
+			}));
+	}
+
+	fn push_variant(
+		&mut self,
+		registry: &Registry,
+		id: u64,
+		position: usize,
+		name: &UntrackedSymbol<String>,
+		index: Option<u64>,
+	) {
+		self.variants.push(VariantRow {
+			type_id: id,
+			position,
+			name: registry.resolve_string(name).unwrap_or_default().to_string(),
+			index,
+		});
+	}
+
+	/// Returns the tables as CSV documents with a header row, named by their file names.
+	pub fn to_csv(&self) -> Vec<(&'static str, String)> {
+		let mut types = String::from("id,id_kind,name,namespace,len,def_kind\n");
+		for row in &self.types {
+			push_csv_row(
+				&mut types,
+				&[
+					&row.id.to_string(),
+					row.id_kind,
+					row.name.as_deref().unwrap_or_default(),
+					row.namespace.as_ref().map_or("", String::as_str),
+					&row.len.map(|len| len.to_string()).unwrap_or_default(),
+					row.def_kind,
+				],
+			);
+		}
+		let mut params = String::from("type_id,position,param_type\n");
+		for row in &self.params {
+			push_csv_row(
+				&mut params,
+				&[
+					&row.type_id.to_string(),
+					&row.position.to_string(),
+					&row.param_type.to_string(),
+				],
+			);
+		}
+		let mut fields = String::from("type_id,variant,position,name,field_type,compact\n");
+		for row in &self.fields {
+			push_csv_row(
+				&mut fields,
+				&[
+					&row.type_id.to_string(),
+					&row.variant.map(|variant| variant.to_string()).unwrap_or_default(),
+					&row.position.to_string(),
+					row.name.as_deref().unwrap_or_default(),
+					&row.field_type.to_string(),
+					&row.compact.to_string(),
+				],
+			);
+		}
+		let mut variants = String::from("type_id,position,name,index\n");
+		for row in &self.variants {
+			push_csv_row(
+				&mut variants,
+				&[
+					&row.type_id.to_string(),
+					&row.position.to_string(),
+					&row.name,
+					&row.index.map(|index| index.to_string()).unwrap_or_default(),
+				],
+			);
+		}
+		vec![
+			("types.csv", types),
+			("params.csv", params),
+			("fields.csv", fields),
+			("variants.csv", variants),
+		]
+	}
+}
+
+/// Returns the sequence number of the type with the given symbol.
+fn sequence(symbol: &UntrackedSymbol<AnyTypeId>) -> u64 {
+	u64::from(symbol.id().get())
+}
+
+fn def_kind(def: &TypeDef<CompactForm>) -> &'static str {
+	match def {
+		TypeDef::Builtin(_) => "builtin",
+		TypeDef::Struct(_) => "struct",
+		TypeDef::TupleStruct(_) => "tuple_struct",
+		TypeDef::ClikeEnum(_) => "clike_enum",
+		TypeDef::Enum(_) => "enum",
+		TypeDef::Union(_) => "union",
+	}
+}
+
+/// Appends the row to the CSV document, quoting values where required.
+fn push_csv_row(csv: &mut String, values: &[&str]) {
+	for (i, value) in values.iter().enumerate() {
+		if i > 0 {
+			csv.push(',');
+		}
+		if value.contains(&[',', '"', '\n'][..]) {
+			let _ = write!(csv, "\"{}\"", value.replace('"', "\"\""));
+		} else {
+			csv.push_str(value);
+		}
+	}
+	csv.push('\n');
+}
@@ -14,7 +14,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::*;
+use crate::tm_std::{Arc, Cow, Rc};
+use crate::{validate::ValidationError, *};
 use core::marker::PhantomData;
 
 #[cfg(not(feature = "std"))]
@@ -31,7 +32,7 @@ where
 macro_rules! assert_type_id {
 	( $ty:ty, $expected:expr ) => {{
 		assert_type_id::<$ty, _>($expected)
-		}};
+	}};
 }
 
 #[test]
@@ -42,10 +43,22 @@ fn primitives() {
 	assert_type_id!(i8, TypeIdPrimitive::I8);
 
 	assert_type_id!(Box<String>, TypeIdPrimitive::Str);
+	assert_type_id!(Rc<String>, TypeIdPrimitive::Str);
+	assert_type_id!(Arc<[bool]>, TypeIdSlice::new(bool::meta_type()));
+	assert_type_id!(Cow<'static, str>, TypeIdPrimitive::Str);
+	assert_type_id!(Cow<'static, [u8]>, TypeIdSlice::new(u8::meta_type()));
 	assert_type_id!(&String, TypeIdPrimitive::Str);
 	assert_type_id!([bool], TypeIdSlice::new(bool::meta_type()));
 }
 
+#[test]
+fn smart_pointers() {
+	assert_eq!(<Box<Option<u8>>>::type_def(), <Option<u8>>::type_def());
+	assert_eq!(<Rc<Option<u8>>>::type_def(), <Option<u8>>::type_def());
+	assert_eq!(<Arc<Option<u8>>>::type_def(), <Option<u8>>::type_def());
+	assert_eq!(<Cow<'static, [u8]>>::type_def(), <[u8]>::type_def());
+}
+
 #[test]
 fn prelude_items() {
 	assert_type_id!(
@@ -58,10 +71,133 @@ fn prelude_items() {
 	);
 	assert_type_id!(
 		PhantomData<i32>,
-		TypeIdCustom::new("PhantomData", Namespace::prelude(), tuple_meta_type!(i32))
+		TypeIdCustom::new("PhantomData", Namespace::prelude(), tuple_meta_type!())
 	)
 }
 
+#[test]
+fn phantom_data() {
+	// Does not implement `Metadata`.
+	struct NoMetadata;
+
+	let meta_type = <PhantomData<NoMetadata>>::meta_type();
+	assert_eq!(
+		meta_type.type_id(),
+		TypeIdCustom::new("PhantomData", Namespace::prelude(), tuple_meta_type!()).into()
+	);
+	assert_eq!(meta_type.type_def(), TypeDefTupleStruct::new(vec![]).into());
+
+	// Markers of all types share a single registry entry.
+	assert_eq!(meta_type, <PhantomData<u8>>::meta_type());
+	let mut registry = Registry::new();
+	let symbols = registry
+		.register_types(vec![
+			meta_type,
+			<PhantomData<u8>>::meta_type(),
+			<PhantomData<u16>>::meta_type(),
+		])
+		.unwrap();
+	assert_eq!(symbols[0], symbols[1]);
+	assert_eq!(symbols[1], symbols[2]);
+	assert_eq!(registry.types().count(), 1);
+	assert_eq!(registry.validate(), Ok(()));
+}
+
+#[test]
+fn sequence_collections() {
+	use crate::tm_std::{BTreeSet, BinaryHeap, LinkedList, VecDeque};
+
+	assert_type_id!(
+		VecDeque<u8>,
+		TypeIdCustom::new("VecDeque", Namespace::prelude(), tuple_meta_type!(u8))
+	);
+	assert_type_id!(
+		LinkedList<u8>,
+		TypeIdCustom::new("LinkedList", Namespace::prelude(), tuple_meta_type!(u8))
+	);
+	assert_type_id!(
+		BinaryHeap<u8>,
+		TypeIdCustom::new("BinaryHeap", Namespace::prelude(), tuple_meta_type!(u8))
+	);
+	assert_eq!(<VecDeque<u8>>::type_def(), <Vec<u8>>::type_def());
+	assert_eq!(<LinkedList<u8>>::type_def(), <Vec<u8>>::type_def());
+	assert_eq!(<BinaryHeap<u8>>::type_def(), <Vec<u8>>::type_def());
+
+	assert_type_id!(
+		BTreeSet<u8>,
+		TypeIdCustom::new("BTreeSet", Namespace::prelude(), tuple_meta_type!(u8))
+	);
+	assert_eq!(<BTreeSet<u8>>::type_def(), <Vec<u8>>::type_def());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hash_collections() {
+	use std::collections::{hash_map::RandomState, BTreeMap, BTreeSet, HashMap, HashSet};
+
+	assert_type_id!(
+		HashMap<String, u32>,
+		TypeIdCustom::new("HashMap", Namespace::prelude(), tuple_meta_type!(String, u32))
+	);
+	assert_eq!(
+		<HashMap<String, u32>>::type_id(),
+		<HashMap<String, u32, RandomState>>::type_id()
+	);
+	assert_eq!(<HashMap<String, u32>>::type_def(), <BTreeMap<String, u32>>::type_def());
+
+	assert_type_id!(
+		HashSet<u8>,
+		TypeIdCustom::new("HashSet", Namespace::prelude(), tuple_meta_type!(u8))
+	);
+	assert_eq!(<HashSet<u8>>::type_def(), <BTreeSet<u8>>::type_def());
+}
+
+#[cfg(feature = "floats")]
+#[test]
+fn float_primitives() {
+	assert_type_id!(f32, TypeIdPrimitive::F32);
+	assert_type_id!(f64, TypeIdPrimitive::F64);
+	assert_eq!(TypeIdPrimitive::F64.name(), "f64");
+}
+
+#[test]
+fn pointer_sized_integers() {
+	assert_type_id!(
+		usize,
+		TypeIdCustom::new("usize", Namespace::prelude(), tuple_meta_type!())
+	);
+	assert_type_id!(
+		isize,
+		TypeIdCustom::new("isize", Namespace::prelude(), tuple_meta_type!())
+	);
+	assert_eq!(
+		usize::type_def(),
+		TypeDefTupleStruct::new(vec![UnnamedField::of::<u64>()]).into()
+	);
+	assert_eq!(
+		isize::type_def(),
+		TypeDefTupleStruct::new(vec![UnnamedField::of::<i64>()]).into()
+	);
+}
+
+#[test]
+fn non_zero_integers() {
+	use core::num::{NonZeroI64, NonZeroU32};
+
+	assert_type_id!(
+		NonZeroU32,
+		TypeIdCustom::new("NonZeroU32", Namespace::prelude(), tuple_meta_type!())
+	);
+	assert_eq!(
+		NonZeroU32::type_def(),
+		TypeDefTupleStruct::new(vec![UnnamedField::of::<u32>()]).into()
+	);
+	assert_eq!(
+		NonZeroI64::type_def(),
+		TypeDefTupleStruct::new(vec![UnnamedField::of::<i64>()]).into()
+	);
+}
+
 #[test]
 fn tuple_primitives() {
 	// unit
@@ -86,6 +222,10 @@ fn array_primitives() {
 	assert_type_id!([bool; 3], TypeIdArray::new(3, bool::meta_type()));
 	// nested
 	assert_type_id!([[i32; 5]; 5], TypeIdArray::new(5, <[i32; 5]>::meta_type()));
+	// larger than `u16::MAX`
+	assert_type_id!([u8; 65536], TypeIdArray::new(65536, u8::meta_type()));
+	#[cfg(target_pointer_width = "64")]
+	assert_type_id!([u8; 1 << 32], TypeIdArray::new(1 << 32, u8::meta_type()));
 	// slice
 	assert_type_id!([bool], TypeIdSlice::new(bool::meta_type()));
 	// vec
@@ -149,3 +289,1244 @@ fn struct_with_generics() {
 		TypeDefStruct::new(vec![NamedField::new("data", <Box<MyStruct<bool>>>::meta_type()),]).into(),
 	);
 }
+
+#[test]
+fn clike_enum_lookups() {
+	let clike = TypeDefClikeEnum::new(vec![
+		ClikeEnumVariant::new("Monday", 0u64),
+		ClikeEnumVariant::new("Thursday", 42u64),
+		ClikeEnumVariant::new("Friday", 43u64),
+	]);
+
+	assert_eq!(clike.variant_name(42).map(AsRef::as_ref), Some("Thursday"));
+	assert_eq!(clike.variant_name(1), None);
+	assert_eq!(clike.discriminant("Friday"), Some(43));
+	assert_eq!(clike.discriminant("Sunday"), None);
+}
+
+#[test]
+fn type_def_kinds() {
+	let option_def = <Option<bool>>::type_def();
+	assert_eq!(option_def.kind(), TypeDefKind::Enum);
+	assert_eq!(option_def.as_enum().map(|def| def.variants().len()), Some(2));
+	assert!(option_def.as_struct().is_none());
+	assert!(option_def.as_clike_enum().is_none());
+
+	let vec_def = <Vec<bool>>::type_def();
+	assert_eq!(vec_def.kind(), TypeDefKind::Struct);
+	assert!(vec_def.as_struct().is_some());
+
+	assert_eq!(bool::type_def().kind(), TypeDefKind::Builtin);
+}
+
+#[test]
+fn registry_entries_since() {
+	let mut registry = Registry::new();
+	assert_eq!(registry.sequence(), 0);
+
+	registry.register_type(&bool::meta_type());
+	let synced = registry.sequence();
+	assert_eq!(synced, 1);
+
+	registry.register_type(&<Option<u8>>::meta_type());
+	registry.register_type(&bool::meta_type());
+	assert_eq!(registry.sequence(), 3);
+
+	let new_entries = registry.entries_since(synced).collect::<Vec<_>>();
+	assert_eq!(
+		new_entries.iter().map(|(sequence, _)| *sequence).collect::<Vec<_>>(),
+		vec![2, 3]
+	);
+	assert_eq!(new_entries[1].1.id(), &TypeId::Primitive(TypeIdPrimitive::U8));
+	assert_eq!(registry.entries_since(registry.sequence()).count(), 0);
+
+	// Sequence numbers are not reused after removing types.
+	registry.retain_reachable(tuple_meta_type!(u8)).unwrap();
+	assert_eq!(registry.sequence(), 3);
+	registry.register_type(&u16::meta_type());
+	assert_eq!(registry.sequence(), 4);
+	assert_eq!(
+		registry
+			.entries_since(0)
+			.map(|(sequence, _)| sequence)
+			.collect::<Vec<_>>(),
+		vec![3, 4]
+	);
+	assert_eq!(registry.entries_since(synced).count(), 2);
+}
+
+#[test]
+fn registry_from_iterators() {
+	let mut registry = Registry::new();
+	let symbols = tuple_meta_type!(bool, Option<u8>, bool)
+		.register_into(&mut registry)
+		.unwrap();
+	assert_eq!(symbols.len(), 3);
+	assert_eq!(symbols[0], symbols[2]);
+	assert_eq!(registry.sequence(), 3);
+
+	tuple_meta_type!(u8, u16).register_into(&mut registry).unwrap();
+	assert_eq!(registry.sequence(), 4);
+
+	let symbols = registry.register_types(tuple_meta_type!(u16, u32)).unwrap();
+	assert_eq!(
+		symbols.iter().map(|symbol| symbol.id().get()).collect::<Vec<_>>(),
+		vec![4, 5]
+	);
+
+	let collected = tuple_meta_type!(bool, Option<u8>, u16)
+		.into_iter()
+		.collect::<Registry>();
+	assert_eq!(collected.sequence(), 4);
+}
+
+#[test]
+fn namespace_filter_patterns() {
+	let namespace = |path| Namespace::from_module_path(path).unwrap();
+	let filter = NamespaceFilter::new()
+		.allow("my_chain::*")
+		.allow("sp_core")
+		.deny("my_chain::tests::*");
+
+	assert!(filter.accepts(&namespace("my_chain")));
+	assert!(filter.accepts(&namespace("my_chain::api")));
+	assert!(filter.accepts(&namespace("sp_core")));
+	assert!(!filter.accepts(&namespace("sp_core::crypto")));
+	assert!(!filter.accepts(&namespace("my_chain::tests")));
+	assert!(!filter.accepts(&namespace("my_chain_ext")));
+	assert!(filter.accepts(&Namespace::prelude()));
+	assert!(NamespaceFilter::new().accepts(&namespace("anything::goes")));
+}
+
+#[test]
+fn registry_rejects_filtered_namespaces() {
+	struct Internal;
+
+	impl HasTypeId for Internal {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Internal", Namespace::from_module_path(module_path!()).unwrap(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Internal {
+		fn type_def() -> TypeDef {
+			TypeDefTupleStruct::unit().into()
+		}
+	}
+
+	let mut registry = Registry::new().with_namespace_filter(NamespaceFilter::new().allow("my_chain::*"));
+	assert!(registry.try_register_type(&<Option<u8>>::meta_type()).is_ok());
+	let types = registry.types().count();
+
+	assert_eq!(
+		registry.try_register_type(&<Option<Internal>>::meta_type()),
+		Err(RegisterError::Rejected {
+			type_id: TypeIdCustom::new(
+				"Internal",
+				Namespace::new(vec!["type_metadata", "tests"]).unwrap(),
+				vec![]
+			)
+		})
+	);
+	// Rejected types are kept out of the registry along with the types referring to them.
+	assert_eq!(registry.types().count(), types);
+}
+
+#[test]
+fn newtype_metadata() {
+	struct Balance(u128);
+
+	impl_metadata_newtype!(Balance(u128));
+
+	assert_type_id!(
+		Balance,
+		TypeIdCustom::new(
+			"Balance",
+			Namespace::new(vec!["type_metadata", "tests"]).unwrap(),
+			vec![]
+		)
+	);
+	assert_eq!(
+		Balance::type_def(),
+		TypeDefTupleStruct::new(vec![UnnamedField::of::<u128>()]).into()
+	);
+}
+
+#[test]
+fn described_metadata() {
+	struct Handle(*const u8);
+
+	impl_metadata_for!(Handle => u64);
+
+	assert_type_id!(Handle, TypeIdPrimitive::U64);
+	assert_eq!(Handle::type_def(), u64::type_def());
+	assert_eq!(Handle::lossy(), None);
+}
+
+#[test]
+fn registry_tables() {
+	use crate::tabular::{FieldRow, ParamRow, Tables, TypeRow};
+
+	let registry = tuple_meta_type!(Option<[u8; 4]>).into_iter().collect::<Registry>();
+	let tables = Tables::from_registry(&registry);
+	assert_eq!(
+		tables.types,
+		vec![
+			TypeRow {
+				id: 1,
+				id_kind: "custom",
+				name: Some("Option".into()),
+				namespace: Some(String::new()),
+				len: None,
+				def_kind: "enum",
+			},
+			TypeRow {
+				id: 2,
+				id_kind: "array",
+				name: None,
+				namespace: None,
+				len: Some(4),
+				def_kind: "builtin",
+			},
+			TypeRow {
+				id: 3,
+				id_kind: "primitive",
+				name: Some("u8".into()),
+				namespace: None,
+				len: None,
+				def_kind: "builtin",
+			},
+		]
+	);
+	assert_eq!(
+		tables.params,
+		vec![
+			ParamRow {
+				type_id: 1,
+				position: 0,
+				param_type: 2,
+			},
+			ParamRow {
+				type_id: 2,
+				position: 0,
+				param_type: 3,
+			},
+		]
+	);
+	assert_eq!(
+		tables.fields,
+		vec![FieldRow {
+			type_id: 1,
+			variant: Some(1),
+			position: 0,
+			name: None,
+			field_type: 2,
+			compact: false,
+		}]
+	);
+	assert_eq!(tables.to_csv()[0].1.lines().nth(1), Some("1,custom,Option,,,enum"));
+}
+
+#[test]
+fn registry_capabilities() {
+	let mut registry = Registry::new();
+	let bool_symbol = registry.register_type(&bool::meta_type());
+	let u64_symbol = registry.tag_type(&u64::meta_type(), "timestamp").unwrap();
+	registry.tag_type(&<[u8; 32]>::meta_type(), "encode-as-hex").unwrap();
+	registry.tag_type(&u64::meta_type(), "timestamp").unwrap();
+	assert_eq!(registry.tag_type(&bool::meta_type(), "timestamp").unwrap(), bool_symbol);
+
+	assert_eq!(registry.types_with_capability("timestamp"), &[u64_symbol, bool_symbol]);
+	assert_eq!(registry.types_with_capability("encode-as-hex").len(), 1);
+	assert!(registry.types_with_capability("address-like").is_empty());
+}
+
+#[test]
+fn registry_resolve() {
+	let mut registry = Registry::new();
+	let symbol = registry.register_type(&Option::<bool>::meta_type());
+
+	let type_id = match registry.resolve_type_id(&symbol) {
+		Some(TypeId::Custom(custom)) => custom,
+		other => panic!("expected a custom type id, got {:?}", other),
+	};
+	assert_eq!(registry.resolve_string(type_id.name()), Some("Option"));
+	let bool_symbol = type_id.type_params()[0];
+	assert_eq!(
+		registry.resolve_type_id(&bool_symbol),
+		Some(&TypeId::Primitive(TypeIdPrimitive::Bool))
+	);
+
+	let variants = match registry.resolve(&symbol) {
+		Some(TypeDef::Enum(def)) => def.variants(),
+		other => panic!("expected an enum definition, got {:?}", other),
+	};
+	assert_eq!(variants.len(), 2);
+	assert_eq!(
+		registry.resolve(&bool_symbol),
+		Some(&TypeDef::Builtin(Builtin::Builtin))
+	);
+
+	assert_eq!(Registry::new().resolve(&symbol), None);
+
+	assert_eq!(
+		registry.type_id_of(symbol.id().get()),
+		registry.resolve_type_id(&symbol)
+	);
+	assert_eq!(
+		registry.type_def_of(bool_symbol.id().get()),
+		registry.resolve(&bool_symbol)
+	);
+	assert_eq!(registry.type_def_of(0), None);
+	assert_eq!(registry.type_id_of(3), None);
+}
+
+#[test]
+fn registry_strings() {
+	let mut registry = Registry::new();
+	registry.register_type(&Option::<bool>::meta_type());
+	assert_eq!(registry.strings(), &["Option", "None", "Some"]);
+
+	let symbol = registry.register_string("Some");
+	assert_eq!(symbol.id().get(), 3);
+	assert_eq!(registry.strings()[symbol.id().get() as usize - 1], "Some");
+	assert_eq!(registry.resolve_string(&symbol), Some("Some"));
+}
+
+#[test]
+fn registry_types() {
+	let mut registry = Registry::new();
+	let option_symbol = registry.register_type(&Option::<bool>::meta_type());
+	let u8_symbol = registry.register_type(&u8::meta_type());
+
+	let types = registry.types().collect::<Vec<_>>();
+	assert_eq!(types.len(), 3);
+	assert_eq!(types[0].0, option_symbol);
+	assert_eq!(types[1].1, &TypeId::Primitive(TypeIdPrimitive::Bool));
+	assert_eq!(types[2].0, u8_symbol);
+	for (symbol, type_id, type_def) in types {
+		assert_eq!(registry.resolve_type_id(&symbol), Some(type_id));
+		assert_eq!(registry.resolve(&symbol), Some(type_def));
+	}
+}
+
+#[test]
+fn registry_merge() {
+	let mut registry = Registry::new();
+	registry.register_type(&Option::<bool>::meta_type());
+	registry.tag_type(&u8::meta_type(), "timestamp").unwrap();
+
+	let mut other = Registry::new();
+	other.tag_type(&u8::meta_type(), "timestamp").unwrap();
+	other.register_type(&Option::<u32>::meta_type());
+	other.tag_type(&<[u8; 32]>::meta_type(), "encode-as-hex").unwrap();
+	other.register_type(&Option::<bool>::meta_type());
+	registry.merge(other);
+
+	let mut expected = Registry::new();
+	expected.register_type(&Option::<bool>::meta_type());
+	expected.tag_type(&u8::meta_type(), "timestamp").unwrap();
+	expected.register_type(&Option::<u32>::meta_type());
+	expected.tag_type(&<[u8; 32]>::meta_type(), "encode-as-hex").unwrap();
+	assert_eq!(registry, expected);
+}
+
+#[test]
+fn registry_diff() {
+	use crate::diff::{AcceptedChanges, AcceptedTypeChanges, Change, TypeDiff};
+
+	struct OldAccount;
+	struct NewAccount;
+
+	fn account_id() -> TypeId {
+		TypeIdCustom::new("Account", Namespace::new(vec!["chain"]).unwrap(), tuple_meta_type!()).into()
+	}
+
+	impl HasTypeId for OldAccount {
+		fn type_id() -> TypeId {
+			account_id()
+		}
+	}
+
+	impl HasTypeDef for OldAccount {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![
+				NamedField::of::<u32>("nonce"),
+				NamedField::of::<u64>("free"),
+				NamedField::of::<bool>("frozen"),
+			])
+			.into()
+		}
+	}
+
+	impl HasTypeId for NewAccount {
+		fn type_id() -> TypeId {
+			account_id()
+		}
+	}
+
+	impl HasTypeDef for NewAccount {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![
+				NamedField::of::<u64>("free").compact(),
+				NamedField::of::<u64>("nonce"),
+				NamedField::of::<u64>("reserved"),
+			])
+			.into()
+		}
+	}
+
+	let old = tuple_meta_type!(OldAccount, Option<bool>)
+		.into_iter()
+		.collect::<Registry>();
+	let new = tuple_meta_type!(NewAccount, Option<bool>)
+		.into_iter()
+		.collect::<Registry>();
+	let diff = old.diff(&new);
+	assert!(diff.added.is_empty());
+	assert_eq!(diff.removed, vec!["u32"]);
+	let changes = vec![
+		Change::FieldMoved {
+			variant: None,
+			field: String::from("nonce"),
+			from: 0,
+			to: 1,
+		},
+		Change::FieldType {
+			variant: None,
+			field: String::from("nonce"),
+			from: String::from("u32"),
+			to: String::from("u64"),
+		},
+		Change::FieldMoved {
+			variant: None,
+			field: String::from("free"),
+			from: 1,
+			to: 0,
+		},
+		Change::FieldCompact {
+			variant: None,
+			field: String::from("free"),
+			compact: true,
+		},
+		Change::FieldRemoved {
+			variant: None,
+			field: String::from("frozen"),
+		},
+		Change::FieldAdded {
+			variant: None,
+			field: String::from("reserved"),
+		},
+	];
+	assert_eq!(
+		diff.changed,
+		vec![TypeDiff {
+			name: String::from("chain::Account"),
+			changes,
+		}]
+	);
+	assert!(new.diff(&new).is_empty());
+
+	let accepted = AcceptedChanges {
+		removed: vec![String::from("u32")],
+		changed: vec![AcceptedTypeChanges {
+			name: String::from("chain::Account"),
+			changes: Some(diff.changed[0].changes[1..].to_vec()),
+		}],
+		..Default::default()
+	};
+	let unexpected = diff.unexpected(&accepted);
+	assert!(unexpected.removed.is_empty());
+	assert_eq!(
+		unexpected.changed,
+		vec![TypeDiff {
+			name: String::from("chain::Account"),
+			changes: vec![Change::FieldMoved {
+				variant: None,
+				field: String::from("nonce"),
+				from: 0,
+				to: 1,
+			}],
+		}]
+	);
+	let accepted = AcceptedChanges {
+		removed: vec![String::from("u32")],
+		changed: vec![AcceptedTypeChanges {
+			name: String::from("chain::Account"),
+			changes: None,
+		}],
+		..Default::default()
+	};
+	assert!(diff.unexpected(&accepted).is_empty());
+	assert_eq!(diff.unexpected(&AcceptedChanges::default()), diff);
+}
+
+#[test]
+fn registry_diff_variants() {
+	use crate::diff::Change;
+
+	struct OldCall;
+	struct NewCall;
+
+	fn call_id() -> TypeId {
+		TypeIdCustom::new("Call", Namespace::new(vec!["chain"]).unwrap(), tuple_meta_type!()).into()
+	}
+
+	impl HasTypeId for OldCall {
+		fn type_id() -> TypeId {
+			call_id()
+		}
+	}
+
+	impl HasTypeDef for OldCall {
+		fn type_def() -> TypeDef {
+			TypeDefEnum::new(vec![
+				EnumVariantUnit::new("Remark").into(),
+				EnumVariantTupleStruct::new("Transfer", vec![UnnamedField::of::<u64>()]).into(),
+				EnumVariantUnit::new("Kill").into(),
+			])
+			.into()
+		}
+	}
+
+	impl HasTypeId for NewCall {
+		fn type_id() -> TypeId {
+			call_id()
+		}
+	}
+
+	impl HasTypeDef for NewCall {
+		fn type_def() -> TypeDef {
+			TypeDefEnum::new(vec![
+				EnumVariantTupleStruct::new("Transfer", vec![UnnamedField::of::<u64>(), UnnamedField::of::<bool>()])
+					.with_index(1)
+					.into(),
+				EnumVariantUnit::new("Remark").with_index(2).into(),
+			])
+			.into()
+		}
+	}
+
+	let old = tuple_meta_type!(OldCall).into_iter().collect::<Registry>();
+	let new = tuple_meta_type!(NewCall).into_iter().collect::<Registry>();
+	let diff = old.diff(&new);
+	assert_eq!(diff.added, vec!["bool"]);
+	assert_eq!(
+		diff.changed[0].changes,
+		vec![
+			Change::VariantIndex {
+				variant: String::from("Remark"),
+				from: 0,
+				to: 2,
+			},
+			Change::FieldAdded {
+				variant: Some(String::from("Transfer")),
+				field: String::from("1"),
+			},
+			Change::VariantRemoved {
+				variant: String::from("Kill"),
+			},
+		]
+	);
+}
+
+#[test]
+fn registry_retain_reachable() {
+	let mut registry = Registry::new();
+	registry.register_type(&Vec::<u8>::meta_type());
+	registry.tag_type(&u64::meta_type(), "timestamp").unwrap();
+	registry.register_type(&Option::<Option<bool>>::meta_type());
+	registry.tag_type(&bool::meta_type(), "flag").unwrap();
+	registry.retain_reachable(tuple_meta_type!(Option<bool>, u16)).unwrap();
+
+	let mut expected = Registry::new();
+	expected.register_type(&Option::<bool>::meta_type());
+	expected.tag_type(&bool::meta_type(), "flag").unwrap();
+	expected.register_type(&u16::meta_type());
+	assert_eq!(registry, expected);
+}
+
+#[cfg(feature = "primitive-types")]
+#[test]
+fn primitive_types() {
+	use primitive_types::{H256, U256};
+
+	assert_type_id!(
+		U256,
+		TypeIdCustom::new(
+			"U256",
+			Namespace::new(vec!["primitive_types"]).unwrap(),
+			tuple_meta_type!()
+		)
+	);
+	assert_eq!(
+		U256::type_def(),
+		TypeDefTupleStruct::new(vec![UnnamedField::of::<[u64; 4]>()]).into()
+	);
+	assert_eq!(
+		H256::type_def(),
+		TypeDefTupleStruct::new(vec![UnnamedField::of::<[u8; 32]>()]).into()
+	);
+}
+
+#[cfg(feature = "codec")]
+#[test]
+fn registry_codec() {
+	use crate::{form::CompactForm, interner::UntrackedSymbol};
+	use core::any::TypeId as AnyTypeId;
+	use parity_scale_codec::{Decode, Encode};
+
+	let mut registry = Registry::new();
+	registry.register_type(&Option::<Vec<bool>>::meta_type());
+	registry.register_type(&<[u8; 32]>::meta_type());
+	registry.register_type(&<(bool, u16)>::meta_type());
+
+	for (symbol, type_id, type_def) in registry.types() {
+		assert_eq!(UntrackedSymbol::decode(&mut &symbol.encode()[..]).unwrap(), symbol);
+		assert_eq!(
+			&TypeId::<CompactForm>::decode(&mut &type_id.encode()[..]).unwrap(),
+			type_id
+		);
+		assert_eq!(
+			&TypeDef::<CompactForm>::decode(&mut &type_def.encode()[..]).unwrap(),
+			type_def
+		);
+	}
+	assert!(UntrackedSymbol::<AnyTypeId>::decode(&mut &0u32.encode()[..]).is_err());
+
+	let loaded = RegistryReadOnly::decode(&mut &registry.encode()[..]).unwrap();
+	assert_eq!(loaded.strings(), registry.strings());
+	assert_eq!(loaded.hash(), registry.hash());
+	assert_eq!(loaded.encode(), registry.encode());
+}
+
+#[test]
+fn registry_dedup_types() {
+	macro_rules! node {
+		( $name:ident, $value:literal ) => {
+			struct $name;
+
+			impl HasTypeId for $name {
+				fn type_id() -> TypeId {
+					TypeIdCustom::new("Node", Namespace::new(vec!["chain"]).unwrap(), tuple_meta_type!()).into()
+				}
+			}
+
+			impl HasTypeDef for $name {
+				fn type_def() -> TypeDef {
+					TypeDefStruct::new(vec![
+						NamedField::of::<u8>($value),
+						NamedField::of::<Vec<$name>>("children"),
+					])
+					.into()
+				}
+			}
+		};
+	}
+	node!(NodeV1, "value");
+	node!(NodeV2, "value");
+	node!(NodeV3, "amount");
+
+	let mut registry = Registry::new();
+	registry.register_type(&NodeV1::meta_type());
+	registry.tag_type(&NodeV2::meta_type(), "node").unwrap();
+	registry.dedup_types();
+
+	let mut expected = Registry::new();
+	expected.tag_type(&NodeV1::meta_type(), "node").unwrap();
+	assert_eq!(registry, expected);
+
+	// Removed types resolve to the remaining ones upon later registrations.
+	let len = registry.types().count();
+	assert_eq!(
+		registry.register_type(&NodeV2::meta_type()),
+		registry.register_type(&NodeV1::meta_type())
+	);
+	registry.register_type(&Vec::<NodeV2>::meta_type());
+	assert_eq!(registry.types().count(), len);
+
+	// The node with differently named fields is kept along with its vector and slice.
+	registry.register_type(&NodeV3::meta_type());
+	registry.dedup_types();
+	assert_eq!(registry.types().count(), len + 3);
+	assert_eq!(
+		registry.register_type(&NodeV2::meta_type()),
+		registry.register_type(&NodeV1::meta_type())
+	);
+
+	// Merged and pruned registries keep resolving removed types.
+	let mut merged = Registry::new();
+	merged.register_type(&u16::meta_type());
+	merged.merge(registry);
+	assert_eq!(
+		merged.register_type(&NodeV2::meta_type()),
+		merged.register_type(&NodeV1::meta_type())
+	);
+	merged.retain_reachable(tuple_meta_type!(NodeV1)).unwrap();
+	let len = merged.types().count();
+	merged.register_type(&NodeV2::meta_type());
+	assert_eq!(merged.types().count(), len);
+}
+
+#[test]
+fn registry_validate() {
+	let mut registry = Registry::new();
+	registry.register_type(&Option::<Vec<bool>>::meta_type());
+	registry.tag_type(&<[u8; 32]>::meta_type(), "encode-as-hex").unwrap();
+	assert_eq!(registry.validate(), Ok(()));
+
+	registry.register_string("unused");
+	assert_eq!(
+		registry.validate(),
+		Err(vec![ValidationError::UnusedString {
+			string: registry.strings().len() as u32
+		}])
+	);
+}
+
+#[test]
+fn registry_content_ids() {
+	#[allow(unused)]
+	struct Node {
+		value: u8,
+		children: Vec<Node>,
+	}
+
+	impl HasTypeId for Node {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Node", Namespace::new(vec!["chain"]).unwrap(), tuple_meta_type!()).into()
+		}
+	}
+
+	impl HasTypeDef for Node {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![
+				NamedField::of::<u8>("value"),
+				NamedField::of::<Vec<Node>>("children"),
+			])
+			.into()
+		}
+	}
+
+	fn content_id<T: Metadata>(registry: &mut Registry) -> content_id::ContentId {
+		let symbol = registry.register_type(&T::meta_type());
+		registry.content_ids()[symbol.id().get() as usize - 1]
+	}
+
+	let mut registry = Registry::new();
+	let node = content_id::<Node>(&mut registry);
+	let option = content_id::<Option<u32>>(&mut registry);
+	// The BLAKE2b-256 digest of the canonical encoding is part of the format.
+	assert_eq!(
+		option.to_string(),
+		"db6be11439a4529e179f34c42f843e8efe1ddac04a97724b5ed56946cc816118"
+	);
+
+	let mut reordered = Registry::new();
+	reordered.register_type(&u64::meta_type());
+	assert_eq!(content_id::<Option<u32>>(&mut reordered), option);
+	assert_eq!(content_id::<Node>(&mut reordered), node);
+
+	assert_ne!(content_id::<Option<u64>>(&mut registry), option);
+	assert_ne!(content_id::<Vec<Node>>(&mut registry), node);
+	assert_eq!(registry.content_ids().len(), registry.types().count());
+
+	// Content identifiers of single types need no registry.
+	assert_eq!(content_id::ContentId::of::<Node>(), node);
+	assert_eq!(content_id::ContentId::of::<Option<u32>>(), option);
+}
+
+#[test]
+fn registry_hash() {
+	fn registry<T: Metadata>() -> Registry {
+		let mut registry = Registry::new();
+		registry.register_type(&T::meta_type());
+		registry
+	}
+
+	let hash = registry::<Option<u32>>().hash();
+	assert_eq!(registry::<Option<u32>>().hash(), hash);
+	assert_ne!(registry::<Option<u64>>().hash(), hash);
+	assert_ne!(registry::<Result<u32, u8>>().hash(), hash);
+	assert_ne!(Registry::new().hash(), hash);
+
+	struct Point;
+
+	impl HasTypeId for Point {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Point", Namespace::new(vec!["geo"]).unwrap(), tuple_meta_type!()).into()
+		}
+	}
+
+	impl HasTypeDef for Point {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![NamedField::of::<u8>("x")])
+				.with_docs(&["A point."])
+				.into()
+		}
+	}
+
+	struct Undocumented;
+
+	impl HasTypeId for Undocumented {
+		fn type_id() -> TypeId {
+			Point::type_id()
+		}
+	}
+
+	impl HasTypeDef for Undocumented {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![NamedField::of::<u8>("x")]).into()
+		}
+	}
+
+	// Docs are part of the fingerprint independent of the `docs` feature.
+	assert_ne!(registry::<Point>().hash(), registry::<Undocumented>().hash());
+}
+
+#[test]
+fn registry_compacts_types_once() {
+	use core::sync::atomic::{AtomicUsize, Ordering};
+
+	static COMPACTED: AtomicUsize = AtomicUsize::new(0);
+
+	#[allow(unused)]
+	struct Leaf;
+
+	impl HasTypeId for Leaf {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Leaf", Namespace::new(vec!["chain"]).unwrap(), tuple_meta_type!()).into()
+		}
+	}
+
+	impl HasTypeDef for Leaf {
+		fn type_def() -> TypeDef {
+			COMPACTED.fetch_add(1, Ordering::SeqCst);
+			TypeDefStruct::new(vec![]).into()
+		}
+	}
+
+	let mut registry = Registry::new();
+	registry.register_type(&<(Leaf, [Leaf; 2], Option<Leaf>, Vec<(Leaf, Leaf)>)>::meta_type());
+	registry.register_type(&Leaf::meta_type());
+	assert_eq!(COMPACTED.load(Ordering::SeqCst), 1);
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn registry_stats() {
+	#[allow(unused)]
+	struct Balance(u128);
+
+	impl HasTypeId for Balance {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new(
+				"Balance",
+				Namespace::new(vec!["chain", "balances"]).unwrap(),
+				tuple_meta_type!(),
+			)
+			.into()
+		}
+	}
+
+	impl HasTypeDef for Balance {
+		fn type_def() -> TypeDef {
+			TypeDefTupleStruct::new(vec![UnnamedField::of::<u128>()]).into()
+		}
+	}
+
+	let registry = tuple_meta_type!(Balance, Option<Balance>, Result<u8, bool>)
+		.into_iter()
+		.collect::<Registry>();
+	let stats = registry.stats();
+	assert_eq!(stats.types, registry.types().count());
+	assert_eq!(stats.strings, registry.strings().len());
+	assert_eq!(stats.serialized_bytes, serde_json::to_vec(&registry).unwrap().len());
+	assert_eq!(
+		stats.namespaces.keys().map(String::as_str).collect::<Vec<_>>(),
+		vec!["", "chain::balances"]
+	);
+	assert_eq!(stats.namespaces[""].types, 2);
+	assert_eq!(stats.namespaces["chain::balances"].types, 1);
+	assert!(stats
+		.namespaces
+		.values()
+		.all(|namespace| namespace.serialized_bytes > 0));
+	assert!(
+		stats
+			.namespaces
+			.values()
+			.map(|namespace| namespace.serialized_bytes)
+			.sum::<usize>()
+			< stats.serialized_bytes
+	);
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn registry_doc_stats() {
+	struct Transfer;
+
+	impl HasTypeId for Transfer {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Transfer", Namespace::new(vec!["chain"]).unwrap(), tuple_meta_type!()).into()
+		}
+	}
+
+	impl HasTypeDef for Transfer {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![
+				NamedField::of::<u64>("from").with_docs(&["The account identifier."]),
+				NamedField::of::<u64>("to").with_docs(&["The account identifier."]),
+			])
+			.with_docs(&["A transfer between two accounts."])
+			.into()
+		}
+	}
+
+	let registry = tuple_meta_type!(Transfer).into_iter().collect::<Registry>();
+	let docs = registry.stats().docs;
+	assert_eq!(docs.lines, 3);
+	assert_eq!(docs.strings, 2);
+	let line_len = |line: &str| serde_json::to_vec(line).unwrap().len();
+	assert_eq!(
+		docs.inlined_bytes,
+		2 * line_len("The account identifier.") + line_len("A transfer between two accounts.")
+	);
+	// The shared line is stored once, at the cost of a symbol per line.
+	assert!(docs.saved_bytes() > 0);
+	assert_eq!(docs.saved_bytes(), docs.inlined_bytes - docs.interned_bytes);
+
+	// Types without documentation save nothing.
+	let registry = tuple_meta_type!(Option<u32>).into_iter().collect::<Registry>();
+	assert_eq!(registry.stats().docs, stats::DocStats::default());
+}
+
+#[test]
+fn registry_portable_types() {
+	let registry = tuple_meta_type!(Option<u32>, Result<[u8; 4], bool>)
+		.into_iter()
+		.collect::<Registry>();
+	let portable = registry.portable_types();
+	assert_eq!(portable.len(), registry.types().count());
+	match portable[0].id() {
+		TypeId::Custom(custom) => {
+			assert_eq!(custom.name(), "Option");
+			assert!(custom.namespace().segments().is_empty());
+			assert_eq!(custom.type_params()[0].id().get(), 2);
+		}
+		_ => panic!("`Option<u32>` must be a custom type"),
+	}
+
+	let loaded = RegistryReadOnly::from_portable_types(portable.clone());
+	assert_eq!(loaded.validate(), Ok(()));
+	assert_eq!(loaded.portable_types(), Some(portable));
+	assert_eq!(loaded.types().len(), registry.types().count());
+}
+
+#[test]
+fn registry_freeze() {
+	use crate::form::StrRef;
+
+	let mut registry = tuple_meta_type!(Option<u32>, Result<[u8; 4], bool>)
+		.into_iter()
+		.collect::<Registry>();
+	registry.register_string("unused");
+	let frozen = registry.freeze();
+	assert_eq!(frozen.strings(), "OptionNoneSomeResultOkErrunused");
+	assert_eq!(frozen.portable_types(), Some(registry.portable_types()));
+
+	let option = registry.register_type(&Option::<u32>::meta_type());
+	let name = match frozen.resolve_type_id(&option) {
+		Some(TypeId::Custom(custom)) => *custom.name(),
+		_ => panic!("`Option<u32>` must be a custom type"),
+	};
+	assert_eq!(name, StrRef::new(0, 6));
+	assert_eq!(frozen.resolve_string(&name), Some("Option"));
+	let buffer = String::from(frozen.strings());
+	assert_eq!(name.resolve(&buffer), Some("Option"));
+	assert_eq!(StrRef::new(25, 7).resolve(&buffer), None);
+	assert_eq!(frozen.resolve(&option), frozen.types().first().map(TypeIdDef::def));
+}
+
+#[test]
+fn registry_expand() {
+	use crate::form::{DisplayForm, Expanded};
+
+	#[allow(unused)]
+	struct Node {
+		children: Vec<Node>,
+	}
+
+	impl HasTypeId for Node {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Node", Namespace::new(vec!["chain"]).unwrap(), tuple_meta_type!()).into()
+		}
+	}
+
+	impl HasTypeDef for Node {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![NamedField::of::<Vec<Node>>("children")]).into()
+		}
+	}
+
+	fn expanded(expanded: &Expanded) -> &TypeIdDef<DisplayForm> {
+		match expanded {
+			Expanded::Type(type_id_def) | Expanded::Labelled { type_id_def, .. } => type_id_def,
+			Expanded::Recursive { .. } => panic!("expected an inlined type"),
+		}
+	}
+
+	let mut registry = Registry::new();
+	let option = registry.register_type(&<Option<u32>>::meta_type());
+	let node = registry.register_type(&Node::meta_type());
+
+	let option = registry.expand(&option).unwrap();
+	match (option.id(), option.def()) {
+		(TypeId::Custom(custom), TypeDef::Enum(r#enum)) => {
+			assert_eq!(custom.name(), "Option");
+			// Types that do not recur are not labelled.
+			assert!(matches!(custom.type_params()[0], Expanded::Type(_)));
+			assert_eq!(
+				expanded(&custom.type_params()[0]).id(),
+				&TypeId::Primitive(TypeIdPrimitive::U32)
+			);
+			assert_eq!(r#enum.variants().len(), 2);
+		}
+		_ => panic!("`Option<u32>` must be a custom enum"),
+	}
+
+	let label = registry.content_ids()[node.id().get() as usize - 1];
+	let expanded_node = registry.expand(&node).unwrap();
+	let children = match expanded_node.def() {
+		TypeDef::Struct(r#struct) => expanded(r#struct.fields()[0].ty()),
+		_ => panic!("`Node` must be a struct"),
+	};
+	match children.id() {
+		TypeId::Custom(custom) => {
+			assert_eq!(custom.name(), "Vec");
+			assert_eq!(custom.type_params()[0], Expanded::Recursive { label, depth: 2 });
+		}
+		_ => panic!("`Vec<Node>` must be a custom type"),
+	}
+
+	// Enclosed types that recur are labelled by their content identifiers.
+	let option_node = registry.register_type(&<Option<Node>>::meta_type());
+	let option_node = registry.expand(&option_node).unwrap();
+	match option_node.id() {
+		TypeId::Custom(custom) => match &custom.type_params()[0] {
+			Expanded::Labelled {
+				label: node_label,
+				type_id_def,
+			} => {
+				assert_eq!(*node_label, label);
+				assert_eq!(**type_id_def, expanded_node);
+			}
+			_ => panic!("`Node` must be labelled"),
+		},
+		_ => panic!("`Option<Node>` must be a custom type"),
+	}
+
+	let unregistered = Registry::new().register_type(&u8::meta_type());
+	assert_eq!(Registry::new().expand(&unregistered), None);
+}
+
+#[test]
+fn strict_registry() {
+	const POINTER_SIZED: &str = "pointer-sized integers are described as 64-bit integers";
+
+	let mut registry = Registry::new().strict();
+	assert!(registry.try_register_type(&<Option<u32>>::meta_type()).is_ok());
+	assert_eq!(
+		registry.try_register_type(&<Vec<Option<usize>>>::meta_type()),
+		Err(RegisterError::Lossy {
+			type_id: usize::type_id(),
+			reason: POINTER_SIZED,
+		})
+	);
+	assert_eq!(
+		registry.try_register_type(&<Box<isize>>::meta_type()),
+		Err(RegisterError::Lossy {
+			type_id: isize::type_id(),
+			reason: POINTER_SIZED,
+		})
+	);
+	assert_eq!(
+		registry.try_register_type(&MetaType::opaque()),
+		Err(RegisterError::Lossy {
+			type_id: MetaType::opaque().type_id(),
+			reason: "opaque types carry no metadata",
+		})
+	);
+	// Refused types are not registered partially.
+	assert_eq!(registry.types().count(), 2);
+	// Markers encode to nothing and are thus not lossy.
+	assert!(registry.try_register_type(&<PhantomData<u8>>::meta_type()).is_ok());
+
+	// Registries that are not strict accept lossy types.
+	assert!(Registry::new().try_register_type(&usize::meta_type()).is_ok());
+}
+
+#[test]
+fn refused_types_leave_registry_unchanged() {
+	let mut registry = Registry::new().strict();
+	registry.register_type(&bool::meta_type());
+	let strings = registry.strings().len();
+	let types = registry.types().count();
+
+	let type_def: TypeDef = TypeDefStruct::new(vec![
+		NamedField::of::<Option<u32>>("a"),
+		NamedField::new("b", MetaType::opaque()),
+	])
+	.into();
+	assert!(type_def.to_compact(&mut registry).is_err());
+	assert!(registry
+		.register_types(
+			tuple_meta_type!(u8, Option<u16>)
+				.into_iter()
+				.chain(Some(MetaType::opaque()))
+		)
+		.is_err());
+	assert!(registry.tag_type(&MetaType::opaque(), "opaque").is_err());
+	assert!(registry.retain_reachable(vec![MetaType::opaque()]).is_err());
+	assert_eq!(registry.strings().len(), strings);
+	assert_eq!(registry.types().count(), types);
+	assert!(registry.types_with_capability("opaque").is_empty());
+
+	// Refused types are refused again rather than resolved to a half-registered symbol.
+	assert!(registry.try_register_type(&MetaType::opaque()).is_err());
+}
+
+#[test]
+fn registry_expand_compact_type_def() {
+	let type_def: TypeDef = TypeDefStruct::new(vec![
+		NamedField::of::<Option<u32>>("a"),
+		NamedField::of::<Vec<bool>>("b"),
+	])
+	.into();
+
+	// Both registries assign different symbols to the same types.
+	let mut stored = Registry::new();
+	stored.register_type(&bool::meta_type());
+	let stored_def = type_def.to_compact(&mut stored).unwrap();
+	let mut registry = Registry::new();
+	let compact_def = type_def.to_compact(&mut registry).unwrap();
+	assert_ne!(stored_def, compact_def);
+
+	let expanded = stored.expand_type_def(&stored_def).unwrap();
+	assert_eq!(registry.expand_type_def(&compact_def), Some(expanded.clone()));
+	assert_eq!(registry.compact_type_def(expanded.clone()), Some(compact_def));
+	assert_eq!(stored.compact_type_def(expanded.clone()), Some(stored_def.clone()));
+
+	// Definitions referring to unregistered types cannot be converted.
+	assert_eq!(Registry::new().expand_type_def(&stored_def), None);
+	assert_eq!(Registry::new().compact_type_def(expanded), None);
+
+	// Type identifiers are converted the same way.
+	let type_id = <Result<Option<u32>, bool>>::type_id();
+	let stored_id = type_id.to_compact(&mut stored).unwrap();
+	let compact_id = type_id.to_compact(&mut registry).unwrap();
+	assert_ne!(stored_id, compact_id);
+	let expanded = stored.expand_type_id(&stored_id).unwrap();
+	assert_eq!(registry.expand_type_id(&compact_id), Some(expanded.clone()));
+	assert_eq!(registry.compact_type_id(expanded.clone()), Some(compact_id));
+	assert_eq!(stored.compact_type_id(expanded.clone()), Some(stored_id.clone()));
+	assert_eq!(Registry::new().expand_type_id(&stored_id), None);
+	assert_eq!(Registry::new().compact_type_id(expanded), None);
+}
+
+#[test]
+fn registry_compact_recursive_and_shared_types() {
+	#[allow(unused)]
+	struct Node {
+		children: Vec<Node>,
+		parent: Option<Box<Node>>,
+	}
+
+	impl HasTypeId for Node {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Node", Namespace::new(vec!["chain"]).unwrap(), tuple_meta_type!()).into()
+		}
+	}
+
+	impl HasTypeDef for Node {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![
+				NamedField::of::<Vec<Node>>("children"),
+				NamedField::of::<Option<Box<Node>>>("parent"),
+			])
+			.into()
+		}
+	}
+
+	type Shared = (Option<u8>, Option<u8>);
+
+	let type_def: TypeDef = TypeDefStruct::new(vec![
+		NamedField::of::<Node>("node"),
+		NamedField::of::<Option<bool>>("flag"),
+		NamedField::of::<(Shared, Shared)>("shared"),
+	])
+	.into();
+
+	let mut registry = Registry::new();
+	// Types of the same shape that expand differently are told apart.
+	registry.register_type(&<Option<u16>>::meta_type());
+	let compact_def = type_def.to_compact(&mut registry).unwrap();
+	let expanded = registry.expand_type_def(&compact_def).unwrap();
+	assert_eq!(registry.compact_type_def(expanded), Some(compact_def));
+}
+
+#[test]
+fn to_compact_borrows() {
+	let type_def = <Result<Option<u8>, [bool; 2]>>::type_def();
+
+	let mut first = Registry::new();
+	let mut second = Registry::new();
+	second.register_type(&u64::meta_type());
+	let first_def = type_def.to_compact(&mut first).unwrap();
+	let second_def = type_def.to_compact(&mut second).unwrap();
+	assert_ne!(first_def, second_def);
+	assert_eq!(type_def.into_compact(&mut first).unwrap(), first_def);
+}
+
+#[test]
+fn runtime_built_strings() {
+	let name = String::from("Dynamic");
+	let type_id: TypeId = TypeIdCustom::new(
+		name.clone(),
+		Namespace::new(vec![String::from("chain"), String::from("runtime")]).unwrap(),
+		tuple_meta_type!(),
+	)
+	.into();
+	let type_def: TypeDef = TypeDefStruct::new(vec![
+		NamedField::of::<u8>(String::from("field")),
+		NamedField::of::<bool>("flag"),
+	])
+	.with_deprecated(format!("use `{}V2` instead", name))
+	.into();
+
+	let mut registry = Registry::new();
+	let compact_id = type_id.into_compact(&mut registry).unwrap();
+	let compact_def = type_def.into_compact(&mut registry).unwrap();
+	match compact_id {
+		TypeId::Custom(custom) => assert_eq!(registry.resolve_string(custom.name()), Some("Dynamic")),
+		_ => panic!("the type must be a custom type"),
+	}
+	let r#struct = compact_def.as_struct().unwrap();
+	assert_eq!(registry.resolve_string(r#struct.fields()[0].name()), Some("field"));
+	assert_eq!(
+		registry.resolve_string(r#struct.deprecated().unwrap()),
+		Some("use `DynamicV2` instead")
+	);
+	assert_eq!(
+		registry.strings(),
+		[
+			"Dynamic",
+			"chain",
+			"runtime",
+			"field",
+			"flag",
+			"use `DynamicV2` instead"
+		]
+	);
+}
@@ -30,7 +30,11 @@ pub use self::core::{
 	u8, u16, u32, u64, u128,
 
 	marker::PhantomData,
-	num::NonZeroU32,
+	mem::{self, ManuallyDrop},
+	num::{
+		NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128,
+		NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128,
+	},
 	option::Option,
 	result::Result,
 
@@ -54,8 +58,16 @@ mod alloc {
 
 #[rustfmt::skip]
 pub use self::alloc::{
+	borrow::{Cow, ToOwned},
 	boxed::Box,
+	collections::{BinaryHeap, LinkedList, VecDeque},
 	collections::btree_map::{BTreeMap, Entry},
+	collections::btree_set::BTreeSet,
+	rc::Rc,
 	string::{String, ToString},
+	sync::Arc,
 	vec, vec::Vec,
 };
+
+#[cfg(feature = "std")]
+pub use std::collections::{HashMap, HashSet};
@@ -17,11 +17,15 @@
 use crate::tm_std::*;
 
 use crate::{
-	form::{CompactForm, Form, MetaForm},
+	form::{CompactForm, ConvertForm, Form, FormMap, MetaForm},
+	registry::{MapSymbols, RegisterError, SymbolMap},
 	IntoCompact, MetaType, Metadata, Registry,
 };
 use derive_more::From;
-use serde::Serialize;
+use serde::{ser::SerializeStruct, Deserialize, Serialize};
+
+#[cfg(feature = "codec")]
+use parity_scale_codec::{Decode, Encode};
 
 /// Types implementing this trait can communicate their type structure.
 ///
@@ -31,12 +35,26 @@ use serde::Serialize;
 pub trait HasTypeDef {
 	/// Returns the type definition for `Self` type.
 	fn type_def() -> TypeDef;
+
+	/// Returns why the type definition describes `Self` lossily, if it does.
+	///
+	/// A type definition is lossy if it omits or approximates parts of the type,
+	/// e.g. skipped fields, opaque types or pointer-sized integers described as 64-bit integers.
+	/// Strict registries refuse to register lossy types, see `Registry::strict`.
+	fn lossy() -> Option<&'static str> {
+		None
+	}
 }
 
 /// A type definition represents the internal structure of a concrete type.
-#[derive(PartialEq, Eq, Debug, Serialize, From)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize, From)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: Deserialize<'de>, F::TypeId: Deserialize<'de>"
+))]
 #[serde(untagged)]
+#[non_exhaustive]
 pub enum TypeDef<F: Form = MetaForm> {
 	/// A builtin type that has an implied and known internal structure.
 	Builtin(Builtin),
@@ -59,8 +77,84 @@ impl TypeDef {
 	}
 }
 
+/// The kind of a type definition.
+///
+/// Allows to inspect the kind of a type definition without matching on its contents.
+#[derive(PartialEq, Eq, Clone, Copy, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum TypeDefKind {
+	/// A builtin type.
+	Builtin,
+	/// A struct with named fields.
+	Struct,
+	/// A tuple-struct with unnamed fields.
+	TupleStruct,
+	/// A C-like enum with simple named variants.
+	ClikeEnum,
+	/// A Rust enum with different kinds of variants.
+	Enum,
+	/// An unsafe Rust union type.
+	Union,
+}
+
+impl<F: Form> TypeDef<F> {
+	/// Returns the kind of the type definition.
+	pub fn kind(&self) -> TypeDefKind {
+		match self {
+			TypeDef::Builtin(_) => TypeDefKind::Builtin,
+			TypeDef::Struct(_) => TypeDefKind::Struct,
+			TypeDef::TupleStruct(_) => TypeDefKind::TupleStruct,
+			TypeDef::ClikeEnum(_) => TypeDefKind::ClikeEnum,
+			TypeDef::Enum(_) => TypeDefKind::Enum,
+			TypeDef::Union(_) => TypeDefKind::Union,
+		}
+	}
+
+	/// Returns the struct definition if this defines a struct with named fields.
+	pub fn as_struct(&self) -> Option<&TypeDefStruct<F>> {
+		match self {
+			TypeDef::Struct(r#struct) => Some(r#struct),
+			_ => None,
+		}
+	}
+
+	/// Returns the tuple-struct definition if this defines a tuple-struct.
+	pub fn as_tuple_struct(&self) -> Option<&TypeDefTupleStruct<F>> {
+		match self {
+			TypeDef::TupleStruct(tuple_struct) => Some(tuple_struct),
+			_ => None,
+		}
+	}
+
+	/// Returns the C-like enum definition if this defines a C-like enum.
+	pub fn as_clike_enum(&self) -> Option<&TypeDefClikeEnum<F>> {
+		match self {
+			TypeDef::ClikeEnum(clike_enum) => Some(clike_enum),
+			_ => None,
+		}
+	}
+
+	/// Returns the enum definition if this defines a Rust enum.
+	pub fn as_enum(&self) -> Option<&TypeDefEnum<F>> {
+		match self {
+			TypeDef::Enum(r#enum) => Some(r#enum),
+			_ => None,
+		}
+	}
+
+	/// Returns the union definition if this defines a union.
+	pub fn as_union(&self) -> Option<&TypeDefUnion<F>> {
+		match self {
+			TypeDef::Union(union) => Some(union),
+			_ => None,
+		}
+	}
+}
+
 /// This struct just exists for the purpose of better JSON output.
-#[derive(PartialEq, Eq, Debug, Serialize)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
 pub enum Builtin {
 	/// This enum variant just exists for the purpose of special JSON output.
 	#[serde(rename = "builtin")]
@@ -70,14 +164,43 @@ pub enum Builtin {
 impl IntoCompact for TypeDef {
 	type Output = TypeDef<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		registry.check_referred(self)?;
+		Ok(match self {
+			TypeDef::Builtin(builtin) => TypeDef::Builtin(builtin.clone()),
+			TypeDef::Struct(r#struct) => r#struct.to_compact(registry)?.into(),
+			TypeDef::TupleStruct(tuple_struct) => tuple_struct.to_compact(registry)?.into(),
+			TypeDef::ClikeEnum(clike_enum) => clike_enum.to_compact(registry)?.into(),
+			TypeDef::Enum(r#enum) => r#enum.to_compact(registry)?.into(),
+			TypeDef::Union(union) => union.to_compact(registry)?.into(),
+		})
+	}
+}
+
+impl MapSymbols for TypeDef<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		match self {
+			TypeDef::Builtin(_) => (),
+			TypeDef::Struct(r#struct) => r#struct.map_symbols(map),
+			TypeDef::TupleStruct(tuple_struct) => tuple_struct.map_symbols(map),
+			TypeDef::ClikeEnum(clike_enum) => clike_enum.map_symbols(map),
+			TypeDef::Enum(r#enum) => r#enum.map_symbols(map),
+			TypeDef::Union(union) => union.map_symbols(map),
+		}
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeDef<F> {
+	type Output = TypeDef<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		match self {
 			TypeDef::Builtin(builtin) => TypeDef::Builtin(builtin),
-			TypeDef::Struct(r#struct) => r#struct.into_compact(registry).into(),
-			TypeDef::TupleStruct(tuple_struct) => tuple_struct.into_compact(registry).into(),
-			TypeDef::ClikeEnum(clike_enum) => clike_enum.into_compact(registry).into(),
-			TypeDef::Enum(r#enum) => r#enum.into_compact(registry).into(),
-			TypeDef::Union(union) => union.into_compact(registry).into(),
+			TypeDef::Struct(r#struct) => r#struct.convert_form(map).into(),
+			TypeDef::TupleStruct(tuple_struct) => tuple_struct.convert_form(map).into(),
+			TypeDef::ClikeEnum(clike_enum) => clike_enum.convert_form(map).into(),
+			TypeDef::Enum(r#enum) => r#enum.convert_form(map).into(),
+			TypeDef::Union(union) => union.convert_form(map).into(),
 		}
 	}
 }
@@ -93,24 +216,63 @@ impl IntoCompact for TypeDef {
 ///     friends: Vec<Person>,
 /// }
 /// ```
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: Deserialize<'de>, F::TypeId: Deserialize<'de>"
+))]
 pub struct TypeDefStruct<F: Form = MetaForm> {
 	/// The named fields of the struct.
 	#[serde(rename = "struct.fields")]
 	fields: Vec<NamedField<F>>,
+	/// The documentation of the struct.
+	#[serde(rename = "struct.docs", skip_serializing_if = "Vec::is_empty", default)]
+	docs: Vec<F::String>,
+	/// The deprecation note of the struct if it is deprecated.
+	#[serde(rename = "struct.deprecated", skip_serializing_if = "Option::is_none")]
+	deprecated: Option<F::String>,
 }
 
 impl IntoCompact for TypeDefStruct {
 	type Output = TypeDefStruct<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
-		TypeDefStruct {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(TypeDefStruct {
 			fields: self
 				.fields
-				.into_iter()
-				.map(|field| field.into_compact(registry))
+				.iter()
+				.map(|field| field.to_compact(registry))
+				.collect::<Result<Vec<_>, _>>()?,
+			docs: self
+				.docs
+				.iter()
+				.map(|doc| registry.register_string(doc.clone()))
 				.collect::<Vec<_>>(),
+			deprecated: self
+				.deprecated
+				.as_ref()
+				.map(|note| registry.register_string(note.clone())),
+		})
+	}
+}
+
+impl MapSymbols for TypeDefStruct<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.fields.map_symbols(map);
+		self.docs.map_symbols(map);
+		self.deprecated.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeDefStruct<F> {
+	type Output = TypeDefStruct<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
+		TypeDefStruct {
+			fields: self.fields.into_iter().map(|field| field.convert_form(map)).collect(),
+			docs: self.docs.into_iter().map(|doc| map.map_string(doc)).collect(),
+			deprecated: self.deprecated.map(|note| map.map_string(note)),
 		}
 	}
 }
@@ -123,30 +285,113 @@ impl TypeDefStruct {
 	{
 		Self {
 			fields: fields.into_iter().collect(),
+			docs: Vec::new(),
+			deprecated: None,
 		}
 	}
+
+	/// Sets the documentation of the struct.
+	pub fn with_docs<S>(mut self, docs: &[S]) -> Self
+	where
+		S: Clone + Into<Cow<'static, str>>,
+	{
+		self.docs = docs.iter().cloned().map(Into::into).collect();
+		self
+	}
+
+	/// Marks the struct as deprecated with the given note.
+	pub fn with_deprecated<N>(mut self, note: N) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		self.deprecated = Some(note.into());
+		self
+	}
+}
+
+impl<F: Form> TypeDefStruct<F> {
+	/// Returns the named fields of the struct.
+	pub fn fields(&self) -> &[NamedField<F>] {
+		&self.fields
+	}
+
+	/// Returns the documentation of the struct.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+
+	/// Returns the deprecation note of the struct if it is deprecated.
+	pub fn deprecated(&self) -> Option<&F::String> {
+		self.deprecated.as_ref()
+	}
 }
 
 /// A named field.
 ///
 /// This can be a named field of a struct type or a struct variant.
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: Deserialize<'de>, F::TypeId: Deserialize<'de>"
+))]
 pub struct NamedField<F: Form = MetaForm> {
 	/// The name of the field.
 	name: F::String,
 	/// The type of the field.
 	#[serde(rename = "type")]
 	ty: F::TypeId,
+	/// Whether the field is encoded using the SCALE compact encoding.
+	#[serde(skip_serializing_if = "is_false", default)]
+	compact: bool,
+	/// The documentation of the field.
+	#[serde(skip_serializing_if = "Vec::is_empty", default)]
+	docs: Vec<F::String>,
+	/// The deprecation note of the field if it is deprecated.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	deprecated: Option<F::String>,
 }
 
 impl IntoCompact for NamedField {
 	type Output = NamedField<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(NamedField {
+			name: registry.register_string(self.name.clone()),
+			ty: registry.try_register_type(&self.ty)?,
+			compact: self.compact,
+			docs: self
+				.docs
+				.iter()
+				.map(|doc| registry.register_string(doc.clone()))
+				.collect::<Vec<_>>(),
+			deprecated: self
+				.deprecated
+				.as_ref()
+				.map(|note| registry.register_string(note.clone())),
+		})
+	}
+}
+
+impl MapSymbols for NamedField<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.name.map_symbols(map);
+		self.ty.map_symbols(map);
+		self.docs.map_symbols(map);
+		self.deprecated.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for NamedField<F> {
+	type Output = NamedField<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		NamedField {
-			name: registry.register_string(self.name),
-			ty: registry.register_type(&self.ty),
+			name: map.map_string(self.name),
+			ty: map.map_type_id(self.ty),
+			compact: self.compact,
+			docs: self.docs.into_iter().map(|doc| map.map_string(doc)).collect(),
+			deprecated: self.deprecated.map(|note| map.map_string(note)),
 		}
 	}
 }
@@ -155,19 +400,84 @@ impl NamedField {
 	/// Creates a new named field.
 	///
 	/// Use this constructor if you want to instantiate from a given meta type.
-	pub fn new(name: <MetaForm as Form>::String, ty: MetaType) -> Self {
-		Self { name, ty }
+	pub fn new<N>(name: N, ty: MetaType) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		Self {
+			name: name.into(),
+			ty,
+			compact: false,
+			docs: Vec::new(),
+			deprecated: None,
+		}
 	}
 
 	/// Creates a new named field.
 	///
 	/// Use this constructor if you want to instantiate from a given compile-time type.
-	pub fn of<T>(name: <MetaForm as Form>::String) -> Self
+	pub fn of<T>(name: impl Into<Cow<'static, str>>) -> Self
 	where
 		T: Metadata + ?Sized + 'static,
 	{
 		Self::new(name, MetaType::new::<T>())
 	}
+
+	/// Marks the field as encoded using the SCALE compact encoding.
+	pub fn compact(mut self) -> Self {
+		self.compact = true;
+		self
+	}
+
+	/// Sets the documentation of the field.
+	pub fn with_docs<S>(mut self, docs: &[S]) -> Self
+	where
+		S: Clone + Into<Cow<'static, str>>,
+	{
+		self.docs = docs.iter().cloned().map(Into::into).collect();
+		self
+	}
+
+	/// Marks the field as deprecated with the given note.
+	pub fn with_deprecated<N>(mut self, note: N) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		self.deprecated = Some(note.into());
+		self
+	}
+}
+
+impl<F: Form> NamedField<F> {
+	/// Returns the name of the field.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns the type of the field.
+	pub fn ty(&self) -> &F::TypeId {
+		&self.ty
+	}
+
+	/// Returns `true` if the field is encoded using the SCALE compact encoding.
+	pub fn is_compact(&self) -> bool {
+		self.compact
+	}
+
+	/// Returns the documentation of the field.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+
+	/// Returns the deprecation note of the field if it is deprecated.
+	pub fn deprecated(&self) -> Option<&F::String> {
+		self.deprecated.as_ref()
+	}
+}
+
+/// Used to skip serializing the compact flag of fields that are not compact.
+fn is_false(value: &bool) -> bool {
+	!*value
 }
 
 /// A tuple struct with unnamed fields.
@@ -181,24 +491,63 @@ impl NamedField {
 /// ```
 /// struct JustAMarker;
 /// ```
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: Deserialize<'de>, F::TypeId: Deserialize<'de>"
+))]
 pub struct TypeDefTupleStruct<F: Form = MetaForm> {
 	/// The unnamed fields.
 	#[serde(rename = "tuple_struct.types")]
 	fields: Vec<UnnamedField<F>>,
+	/// The documentation of the tuple-struct.
+	#[serde(rename = "tuple_struct.docs", skip_serializing_if = "Vec::is_empty", default)]
+	docs: Vec<F::String>,
+	/// The deprecation note of the tuple-struct if it is deprecated.
+	#[serde(rename = "tuple_struct.deprecated", skip_serializing_if = "Option::is_none")]
+	deprecated: Option<F::String>,
 }
 
 impl IntoCompact for TypeDefTupleStruct {
 	type Output = TypeDefTupleStruct<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
-		TypeDefTupleStruct {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(TypeDefTupleStruct {
 			fields: self
 				.fields
-				.into_iter()
-				.map(|field| field.into_compact(registry))
+				.iter()
+				.map(|field| field.to_compact(registry))
+				.collect::<Result<Vec<_>, _>>()?,
+			docs: self
+				.docs
+				.iter()
+				.map(|doc| registry.register_string(doc.clone()))
 				.collect::<Vec<_>>(),
+			deprecated: self
+				.deprecated
+				.as_ref()
+				.map(|note| registry.register_string(note.clone())),
+		})
+	}
+}
+
+impl MapSymbols for TypeDefTupleStruct<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.fields.map_symbols(map);
+		self.docs.map_symbols(map);
+		self.deprecated.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeDefTupleStruct<F> {
+	type Output = TypeDefTupleStruct<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
+		TypeDefTupleStruct {
+			fields: self.fields.into_iter().map(|field| field.convert_form(map)).collect(),
+			docs: self.docs.into_iter().map(|doc| map.map_string(doc)).collect(),
+			deprecated: self.deprecated.map(|note| map.map_string(note)),
 		}
 	}
 }
@@ -211,31 +560,139 @@ impl TypeDefTupleStruct {
 	{
 		Self {
 			fields: fields.into_iter().collect(),
+			docs: Vec::new(),
+			deprecated: None,
 		}
 	}
 
 	/// Creates the unit tuple-struct that has no fields.
 	pub fn unit() -> Self {
-		Self { fields: vec![] }
+		Self {
+			fields: vec![],
+			docs: Vec::new(),
+			deprecated: None,
+		}
+	}
+
+	/// Sets the documentation of the tuple-struct.
+	pub fn with_docs<S>(mut self, docs: &[S]) -> Self
+	where
+		S: Clone + Into<Cow<'static, str>>,
+	{
+		self.docs = docs.iter().cloned().map(Into::into).collect();
+		self
+	}
+
+	/// Marks the tuple-struct as deprecated with the given note.
+	pub fn with_deprecated<N>(mut self, note: N) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		self.deprecated = Some(note.into());
+		self
+	}
+}
+
+impl<F: Form> TypeDefTupleStruct<F> {
+	/// Returns the unnamed fields of the tuple-struct.
+	pub fn fields(&self) -> &[UnnamedField<F>] {
+		&self.fields
+	}
+
+	/// Returns the documentation of the tuple-struct.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+
+	/// Returns the deprecation note of the tuple-struct if it is deprecated.
+	pub fn deprecated(&self) -> Option<&F::String> {
+		self.deprecated.as_ref()
 	}
 }
 
 /// An unnamed field from either a tuple-struct type or a tuple-struct variant.
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
-#[serde(transparent)]
+///
+/// Serialized as its plain type unless it uses the SCALE compact encoding.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
 pub struct UnnamedField<F: Form = MetaForm> {
 	/// The type of the unnamed field.
-	#[serde(rename = "type")]
 	ty: F::TypeId,
+	/// Whether the field is encoded using the SCALE compact encoding.
+	compact: bool,
+}
+
+impl<F: Form> Serialize for UnnamedField<F>
+where
+	F::TypeId: Serialize,
+{
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		if !self.compact {
+			return self.ty.serialize(serializer);
+		}
+		let mut state = serializer.serialize_struct("UnnamedField", 2)?;
+		state.serialize_field("type", &self.ty)?;
+		state.serialize_field("compact", &self.compact)?;
+		state.end()
+	}
+}
+
+impl<'de, F: Form> Deserialize<'de> for UnnamedField<F>
+where
+	F::TypeId: Deserialize<'de>,
+{
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		#[derive(Deserialize)]
+		struct Compact<T> {
+			#[serde(rename = "type")]
+			ty: T,
+			compact: bool,
+		}
+
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Repr<T> {
+			Plain(T),
+			Compact(Compact<T>),
+		}
+
+		Ok(match Repr::deserialize(deserializer)? {
+			Repr::Plain(ty) => UnnamedField { ty, compact: false },
+			Repr::Compact(Compact { ty, compact }) => UnnamedField { ty, compact },
+		})
+	}
 }
 
 impl IntoCompact for UnnamedField {
 	type Output = UnnamedField<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(UnnamedField {
+			ty: registry.try_register_type(&self.ty)?,
+			compact: self.compact,
+		})
+	}
+}
+
+impl MapSymbols for UnnamedField<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.ty.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for UnnamedField<F> {
+	type Output = UnnamedField<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		UnnamedField {
-			ty: registry.register_type(&self.ty),
+			ty: map.map_type_id(self.ty),
+			compact: self.compact,
 		}
 	}
 }
@@ -245,7 +702,10 @@ impl UnnamedField {
 	///
 	/// Use this constructor if you want to instantiate from a given meta type.
 	pub fn new(meta_type: MetaType) -> Self {
-		Self { ty: meta_type }
+		Self {
+			ty: meta_type,
+			compact: false,
+		}
 	}
 
 	/// Creates a new unnamed field.
@@ -257,6 +717,24 @@ impl UnnamedField {
 	{
 		Self::new(MetaType::new::<T>())
 	}
+
+	/// Marks the field as encoded using the SCALE compact encoding.
+	pub fn compact(mut self) -> Self {
+		self.compact = true;
+		self
+	}
+}
+
+impl<F: Form> UnnamedField<F> {
+	/// Returns the type of the unnamed field.
+	pub fn ty(&self) -> &F::TypeId {
+		&self.ty
+	}
+
+	/// Returns `true` if the field is encoded using the SCALE compact encoding.
+	pub fn is_compact(&self) -> bool {
+		self.compact
+	}
 }
 
 /// A C-like enum type.
@@ -278,24 +756,85 @@ impl UnnamedField {
 /// ```
 /// enum JustAMarker {}
 /// ```
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: Deserialize<'de>, F::TypeId: Deserialize<'de>"
+))]
 pub struct TypeDefClikeEnum<F: Form = MetaForm> {
 	/// The variants of the C-like enum.
 	#[serde(rename = "clike_enum.variants")]
 	variants: Vec<ClikeEnumVariant<F>>,
+	/// The integer type of the discriminants if given by a `#[repr(..)]` attribute.
+	#[serde(rename = "clike_enum.discriminant_type", skip_serializing_if = "Option::is_none")]
+	discriminant_type: Option<F::TypeId>,
+	/// Retired discriminants that must not be used by any variant.
+	#[serde(
+		rename = "clike_enum.reserved_indices",
+		skip_serializing_if = "Vec::is_empty",
+		default
+	)]
+	reserved_indices: Vec<u64>,
+	/// The documentation of the C-like enum.
+	#[serde(rename = "clike_enum.docs", skip_serializing_if = "Vec::is_empty", default)]
+	docs: Vec<F::String>,
+	/// The deprecation note of the C-like enum if it is deprecated.
+	#[serde(rename = "clike_enum.deprecated", skip_serializing_if = "Option::is_none")]
+	deprecated: Option<F::String>,
 }
 
 impl IntoCompact for TypeDefClikeEnum {
 	type Output = TypeDefClikeEnum<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(TypeDefClikeEnum {
+			variants: self
+				.variants
+				.iter()
+				.map(|variant| variant.to_compact(registry))
+				.collect::<Result<Vec<_>, _>>()?,
+			discriminant_type: self
+				.discriminant_type
+				.map(|ty| registry.try_register_type(&ty))
+				.transpose()?,
+			reserved_indices: self.reserved_indices.clone(),
+			docs: self
+				.docs
+				.iter()
+				.map(|doc| registry.register_string(doc.clone()))
+				.collect::<Vec<_>>(),
+			deprecated: self
+				.deprecated
+				.as_ref()
+				.map(|note| registry.register_string(note.clone())),
+		})
+	}
+}
+
+impl MapSymbols for TypeDefClikeEnum<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.variants.map_symbols(map);
+		self.discriminant_type.map_symbols(map);
+		self.docs.map_symbols(map);
+		self.deprecated.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeDefClikeEnum<F> {
+	type Output = TypeDefClikeEnum<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		TypeDefClikeEnum {
 			variants: self
 				.variants
 				.into_iter()
-				.map(|variant| variant.into_compact(registry))
-				.collect::<Vec<_>>(),
+				.map(|variant| variant.convert_form(map))
+				.collect(),
+			discriminant_type: self.discriminant_type.map(|ty| map.map_type_id(ty)),
+			reserved_indices: self.reserved_indices,
+			docs: self.docs.into_iter().map(|doc| map.map_string(doc)).collect(),
+			deprecated: self.deprecated.map(|note| map.map_string(note)),
 		}
 	}
 }
@@ -308,25 +847,118 @@ impl TypeDefClikeEnum {
 	{
 		Self {
 			variants: variants.into_iter().collect(),
+			discriminant_type: None,
+			reserved_indices: Vec::new(),
+			docs: Vec::new(),
+			deprecated: None,
 		}
 	}
+
+	/// Sets the integer type of the discriminants, e.g. as given by `#[repr(u8)]`.
+	pub fn with_discriminant_type(mut self, ty: MetaType) -> Self {
+		self.discriminant_type = Some(ty);
+		self
+	}
+
+	/// Records the given retired discriminants that must not be used by any variant.
+	pub fn with_reserved_indices<I>(mut self, reserved_indices: I) -> Self
+	where
+		I: IntoIterator<Item = u64>,
+	{
+		self.reserved_indices = reserved_indices.into_iter().collect();
+		self
+	}
+
+	/// Sets the documentation of the C-like enum.
+	pub fn with_docs<S>(mut self, docs: &[S]) -> Self
+	where
+		S: Clone + Into<Cow<'static, str>>,
+	{
+		self.docs = docs.iter().cloned().map(Into::into).collect();
+		self
+	}
+
+	/// Marks the C-like enum as deprecated with the given note.
+	pub fn with_deprecated<N>(mut self, note: N) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		self.deprecated = Some(note.into());
+		self
+	}
+}
+
+impl<F: Form> TypeDefClikeEnum<F> {
+	/// Returns the variants of the C-like enum.
+	pub fn variants(&self) -> &[ClikeEnumVariant<F>] {
+		&self.variants
+	}
+
+	/// Returns the integer type of the discriminants if given by a `#[repr(..)]` attribute.
+	pub fn discriminant_type(&self) -> Option<&F::TypeId> {
+		self.discriminant_type.as_ref()
+	}
+
+	/// Returns the retired discriminants that must not be used by any variant.
+	pub fn reserved_indices(&self) -> &[u64] {
+		&self.reserved_indices
+	}
+
+	/// Returns the name of the variant with the given discriminant.
+	///
+	/// Returns `None` if no variant has the given discriminant.
+	pub fn variant_name(&self, discriminant: u64) -> Option<&F::String> {
+		self.variants
+			.iter()
+			.find(|variant| variant.discriminant == discriminant)
+			.map(|variant| &variant.name)
+	}
+
+	/// Returns the discriminant of the variant with the given name.
+	///
+	/// Returns `None` if no variant has the given name.
+	pub fn discriminant<N>(&self, name: N) -> Option<u64>
+	where
+		F::String: PartialEq<N>,
+	{
+		self.variants
+			.iter()
+			.find(|variant| variant.name == name)
+			.map(|variant| variant.discriminant)
+	}
+
+	/// Returns the documentation of the C-like enum.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+
+	/// Returns the deprecation note of the C-like enum if it is deprecated.
+	pub fn deprecated(&self) -> Option<&F::String> {
+		self.deprecated.as_ref()
+	}
 }
 
 /// A C-like enum variant.
 ///
+/// Variants are described by their Rust discriminants, i.e. the values of `Variant as Repr`.
+/// Note that `parity-scale-codec` encodes variants without an explicit discriminant by their
+/// position instead, thus both only agree if all discriminants are given explicitly. The
+/// derive rejects index attributes on C-like enum variants for the same reason.
+///
 /// # Example
 ///
 /// ```
 /// enum Food {
 ///     Pizza,
-/// //  ^^^^^ this is a C-like enum variant
+/// //  ^^^^^ this is a C-like enum variant with discriminant 0
 ///     Salad = 1337,
-/// //  ^^^^^ this as well
+/// //  ^^^^^ this as well with discriminant 1337
 ///     Apple,
-/// //  ^^^^^ and this
+/// //  ^^^^^ and this with discriminant 1338
 /// }
 /// ```
-#[derive(PartialEq, Eq, Debug, Serialize)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
 pub struct ClikeEnumVariant<F: Form = MetaForm> {
 	/// The name of the variant.
 	name: F::String,
@@ -337,31 +969,114 @@ pub struct ClikeEnumVariant<F: Form = MetaForm> {
 	/// Even though setting the discriminant is optional
 	/// every C-like enum variant has a discriminant specified
 	/// upon compile-time.
+	///
+	/// Negative discriminants of enums with a signed discriminant type
+	/// are stored in two's complement, i.e. as `discriminant as i64 as u64`.
+	/// Discriminants that fit into neither `u64` nor `i64` cannot be described.
 	discriminant: u64,
+	/// The documentation of the variant.
+	#[serde(skip_serializing_if = "Vec::is_empty", default)]
+	docs: Vec<F::String>,
+	/// The deprecation note of the variant if it is deprecated.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	deprecated: Option<F::String>,
 }
 
 impl IntoCompact for ClikeEnumVariant {
 	type Output = ClikeEnumVariant<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(ClikeEnumVariant {
+			name: registry.register_string(self.name.clone()),
+			discriminant: self.discriminant,
+			docs: self
+				.docs
+				.iter()
+				.map(|doc| registry.register_string(doc.clone()))
+				.collect::<Vec<_>>(),
+			deprecated: self
+				.deprecated
+				.as_ref()
+				.map(|note| registry.register_string(note.clone())),
+		})
+	}
+}
+
+impl MapSymbols for ClikeEnumVariant<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.name.map_symbols(map);
+		self.docs.map_symbols(map);
+		self.deprecated.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for ClikeEnumVariant<F> {
+	type Output = ClikeEnumVariant<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		ClikeEnumVariant {
-			name: registry.register_string(self.name),
+			name: map.map_string(self.name),
 			discriminant: self.discriminant,
+			docs: self.docs.into_iter().map(|doc| map.map_string(doc)).collect(),
+			deprecated: self.deprecated.map(|note| map.map_string(note)),
 		}
 	}
 }
 
 impl ClikeEnumVariant {
 	/// Creates a new C-like enum variant.
-	pub fn new<D>(name: <MetaForm as Form>::String, discriminant: D) -> Self
+	pub fn new<N, D>(name: N, discriminant: D) -> Self
 	where
+		N: Into<Cow<'static, str>>,
 		D: Into<u64>,
 	{
 		Self {
-			name,
+			name: name.into(),
 			discriminant: discriminant.into(),
+			docs: Vec::new(),
+			deprecated: None,
 		}
 	}
+
+	/// Sets the documentation of the variant.
+	pub fn with_docs<S>(mut self, docs: &[S]) -> Self
+	where
+		S: Clone + Into<Cow<'static, str>>,
+	{
+		self.docs = docs.iter().cloned().map(Into::into).collect();
+		self
+	}
+
+	/// Marks the variant as deprecated with the given note.
+	pub fn with_deprecated<N>(mut self, note: N) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		self.deprecated = Some(note.into());
+		self
+	}
+}
+
+impl<F: Form> ClikeEnumVariant<F> {
+	/// Returns the name of the variant.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns the discriminant of the variant.
+	pub fn discriminant(&self) -> u64 {
+		self.discriminant
+	}
+
+	/// Returns the documentation of the variant.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+
+	/// Returns the deprecation note of the variant if it is deprecated.
+	pub fn deprecated(&self) -> Option<&F::String> {
+		self.deprecated.as_ref()
+	}
 }
 
 /// A Rust enum, aka tagged union.
@@ -380,24 +1095,72 @@ impl ClikeEnumVariant {
 ///     ItIsntPossibleToSetADiscriminantThough,
 /// }
 /// ```
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: Deserialize<'de>, F::TypeId: Deserialize<'de>"
+))]
 pub struct TypeDefEnum<F: Form = MetaForm> {
 	/// The variants of the enum.
 	#[serde(rename = "enum.variants")]
 	variants: Vec<EnumVariant<F>>,
+	/// Retired variant indices that must not be used by any variant.
+	#[serde(rename = "enum.reserved_indices", skip_serializing_if = "Vec::is_empty", default)]
+	reserved_indices: Vec<u64>,
+	/// The documentation of the enum.
+	#[serde(rename = "enum.docs", skip_serializing_if = "Vec::is_empty", default)]
+	docs: Vec<F::String>,
+	/// The deprecation note of the enum if it is deprecated.
+	#[serde(rename = "enum.deprecated", skip_serializing_if = "Option::is_none")]
+	deprecated: Option<F::String>,
 }
 
 impl IntoCompact for TypeDefEnum {
 	type Output = TypeDefEnum<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(TypeDefEnum {
+			variants: self
+				.variants
+				.iter()
+				.map(|variant| variant.to_compact(registry))
+				.collect::<Result<Vec<_>, _>>()?,
+			reserved_indices: self.reserved_indices.clone(),
+			docs: self
+				.docs
+				.iter()
+				.map(|doc| registry.register_string(doc.clone()))
+				.collect::<Vec<_>>(),
+			deprecated: self
+				.deprecated
+				.as_ref()
+				.map(|note| registry.register_string(note.clone())),
+		})
+	}
+}
+
+impl MapSymbols for TypeDefEnum<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.variants.map_symbols(map);
+		self.docs.map_symbols(map);
+		self.deprecated.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeDefEnum<F> {
+	type Output = TypeDefEnum<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		TypeDefEnum {
 			variants: self
 				.variants
 				.into_iter()
-				.map(|variant| variant.into_compact(registry))
-				.collect::<Vec<_>>(),
+				.map(|variant| variant.convert_form(map))
+				.collect(),
+			reserved_indices: self.reserved_indices,
+			docs: self.docs.into_iter().map(|doc| map.map_string(doc)).collect(),
+			deprecated: self.deprecated.map(|note| map.map_string(note)),
 		}
 	}
 }
@@ -410,8 +1173,60 @@ impl TypeDefEnum {
 	{
 		Self {
 			variants: variants.into_iter().collect(),
+			reserved_indices: Vec::new(),
+			docs: Vec::new(),
+			deprecated: None,
 		}
 	}
+
+	/// Records the given retired variant indices that must not be used by any variant.
+	pub fn with_reserved_indices<I>(mut self, reserved_indices: I) -> Self
+	where
+		I: IntoIterator<Item = u64>,
+	{
+		self.reserved_indices = reserved_indices.into_iter().collect();
+		self
+	}
+
+	/// Sets the documentation of the enum.
+	pub fn with_docs<S>(mut self, docs: &[S]) -> Self
+	where
+		S: Clone + Into<Cow<'static, str>>,
+	{
+		self.docs = docs.iter().cloned().map(Into::into).collect();
+		self
+	}
+
+	/// Marks the enum as deprecated with the given note.
+	pub fn with_deprecated<N>(mut self, note: N) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		self.deprecated = Some(note.into());
+		self
+	}
+}
+
+impl<F: Form> TypeDefEnum<F> {
+	/// Returns the variants of the enum.
+	pub fn variants(&self) -> &[EnumVariant<F>] {
+		&self.variants
+	}
+
+	/// Returns the retired variant indices that must not be used by any variant.
+	pub fn reserved_indices(&self) -> &[u64] {
+		&self.reserved_indices
+	}
+
+	/// Returns the documentation of the enum.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+
+	/// Returns the deprecation note of the enum if it is deprecated.
+	pub fn deprecated(&self) -> Option<&F::String> {
+		self.deprecated.as_ref()
+	}
 }
 
 /// A Rust enum variant.
@@ -419,9 +1234,14 @@ impl TypeDefEnum {
 /// This can either be a unit struct, just like in C-like enums,
 /// a tuple-struct with unnamed fields,
 /// or a struct with named fields.
-#[derive(PartialEq, Eq, Debug, Serialize, From)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize, From)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: Deserialize<'de>, F::TypeId: Deserialize<'de>"
+))]
 #[serde(untagged)]
+#[non_exhaustive]
 pub enum EnumVariant<F: Form = MetaForm> {
 	/// A unit struct variant.
 	Unit(EnumVariantUnit<F>),
@@ -434,11 +1254,33 @@ pub enum EnumVariant<F: Form = MetaForm> {
 impl IntoCompact for EnumVariant {
 	type Output = EnumVariant<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(match self {
+			EnumVariant::Unit(unit) => unit.to_compact(registry)?.into(),
+			EnumVariant::Struct(r#struct) => r#struct.to_compact(registry)?.into(),
+			EnumVariant::TupleStruct(tuple_struct) => tuple_struct.to_compact(registry)?.into(),
+		})
+	}
+}
+
+impl MapSymbols for EnumVariant<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		match self {
+			EnumVariant::Unit(unit) => unit.map_symbols(map),
+			EnumVariant::Struct(r#struct) => r#struct.map_symbols(map),
+			EnumVariant::TupleStruct(tuple_struct) => tuple_struct.map_symbols(map),
+		}
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for EnumVariant<F> {
+	type Output = EnumVariant<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		match self {
-			EnumVariant::Unit(unit) => unit.into_compact(registry).into(),
-			EnumVariant::Struct(r#struct) => r#struct.into_compact(registry).into(),
-			EnumVariant::TupleStruct(tuple_struct) => tuple_struct.into_compact(registry).into(),
+			EnumVariant::Unit(unit) => unit.convert_form(map).into(),
+			EnumVariant::Struct(r#struct) => r#struct.convert_form(map).into(),
+			EnumVariant::TupleStruct(tuple_struct) => tuple_struct.convert_form(map).into(),
 		}
 	}
 }
@@ -457,27 +1299,124 @@ impl IntoCompact for EnumVariant {
 ///     Minus { source: i32 }
 /// }
 /// ```
-#[derive(PartialEq, Eq, Debug, Serialize)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
 pub struct EnumVariantUnit<F: Form = MetaForm> {
 	/// The name of the variant.
 	#[serde(rename = "unit_variant.name")]
 	name: F::String,
+	/// The index of the variant if set explicitly.
+	///
+	/// Variants without an explicit index are indexed by their position.
+	#[serde(rename = "unit_variant.index", skip_serializing_if = "Option::is_none")]
+	index: Option<u64>,
+	/// The documentation of the variant.
+	#[serde(rename = "unit_variant.docs", skip_serializing_if = "Vec::is_empty", default)]
+	docs: Vec<F::String>,
+	/// The deprecation note of the variant if it is deprecated.
+	#[serde(rename = "unit_variant.deprecated", skip_serializing_if = "Option::is_none")]
+	deprecated: Option<F::String>,
 }
 
 impl IntoCompact for EnumVariantUnit {
 	type Output = EnumVariantUnit<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(EnumVariantUnit {
+			name: registry.register_string(self.name.clone()),
+			index: self.index,
+			docs: self
+				.docs
+				.iter()
+				.map(|doc| registry.register_string(doc.clone()))
+				.collect::<Vec<_>>(),
+			deprecated: self
+				.deprecated
+				.as_ref()
+				.map(|note| registry.register_string(note.clone())),
+		})
+	}
+}
+
+impl MapSymbols for EnumVariantUnit<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.name.map_symbols(map);
+		self.docs.map_symbols(map);
+		self.deprecated.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for EnumVariantUnit<F> {
+	type Output = EnumVariantUnit<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		EnumVariantUnit {
-			name: registry.register_string(self.name),
+			name: map.map_string(self.name),
+			index: self.index,
+			docs: self.docs.into_iter().map(|doc| map.map_string(doc)).collect(),
+			deprecated: self.deprecated.map(|note| map.map_string(note)),
 		}
 	}
 }
 
 impl EnumVariantUnit {
 	/// Creates a new unit struct variant.
-	pub fn new(name: &'static str) -> Self {
-		Self { name }
+	pub fn new<N>(name: N) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		Self {
+			name: name.into(),
+			index: None,
+			docs: Vec::new(),
+			deprecated: None,
+		}
+	}
+
+	/// Sets the explicit index of the variant.
+	pub fn with_index(mut self, index: u64) -> Self {
+		self.index = Some(index);
+		self
+	}
+
+	/// Sets the documentation of the variant.
+	pub fn with_docs<S>(mut self, docs: &[S]) -> Self
+	where
+		S: Clone + Into<Cow<'static, str>>,
+	{
+		self.docs = docs.iter().cloned().map(Into::into).collect();
+		self
+	}
+
+	/// Marks the variant as deprecated with the given note.
+	pub fn with_deprecated<N>(mut self, note: N) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		self.deprecated = Some(note.into());
+		self
+	}
+}
+
+impl<F: Form> EnumVariantUnit<F> {
+	/// Returns the name of the variant.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns the explicit index of the variant, if any.
+	pub fn index(&self) -> Option<u64> {
+		self.index
+	}
+
+	/// Returns the documentation of the variant.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+
+	/// Returns the deprecation note of the variant if it is deprecated.
+	pub fn deprecated(&self) -> Option<&F::String> {
+		self.deprecated.as_ref()
 	}
 }
 
@@ -493,43 +1432,146 @@ impl EnumVariantUnit {
 /// //  ^^^^^^^^^^^^^^^^^^^^^ this is a struct enum variant
 /// }
 /// ```
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: Deserialize<'de>, F::TypeId: Deserialize<'de>"
+))]
 pub struct EnumVariantStruct<F: Form = MetaForm> {
 	/// The name of the struct variant.
 	#[serde(rename = "struct_variant.name")]
 	name: F::String,
+	/// The index of the variant if set explicitly.
+	///
+	/// Variants without an explicit index are indexed by their position.
+	#[serde(rename = "struct_variant.index", skip_serializing_if = "Option::is_none")]
+	index: Option<u64>,
 	/// The fields of the struct variant.
 	#[serde(rename = "struct_variant.fields")]
 	fields: Vec<NamedField<F>>,
+	/// The documentation of the struct variant.
+	#[serde(rename = "struct_variant.docs", skip_serializing_if = "Vec::is_empty", default)]
+	docs: Vec<F::String>,
+	/// The deprecation note of the struct variant if it is deprecated.
+	#[serde(rename = "struct_variant.deprecated", skip_serializing_if = "Option::is_none")]
+	deprecated: Option<F::String>,
 }
 
 impl IntoCompact for EnumVariantStruct {
 	type Output = EnumVariantStruct<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
-		EnumVariantStruct {
-			name: registry.register_string(self.name),
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(EnumVariantStruct {
+			name: registry.register_string(self.name.clone()),
+			index: self.index,
 			fields: self
 				.fields
-				.into_iter()
-				.map(|field| field.into_compact(registry))
+				.iter()
+				.map(|field| field.to_compact(registry))
+				.collect::<Result<Vec<_>, _>>()?,
+			docs: self
+				.docs
+				.iter()
+				.map(|doc| registry.register_string(doc.clone()))
 				.collect::<Vec<_>>(),
+			deprecated: self
+				.deprecated
+				.as_ref()
+				.map(|note| registry.register_string(note.clone())),
+		})
+	}
+}
+
+impl MapSymbols for EnumVariantStruct<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.name.map_symbols(map);
+		self.fields.map_symbols(map);
+		self.docs.map_symbols(map);
+		self.deprecated.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for EnumVariantStruct<F> {
+	type Output = EnumVariantStruct<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
+		EnumVariantStruct {
+			name: map.map_string(self.name),
+			index: self.index,
+			fields: self.fields.into_iter().map(|field| field.convert_form(map)).collect(),
+			docs: self.docs.into_iter().map(|doc| map.map_string(doc)).collect(),
+			deprecated: self.deprecated.map(|note| map.map_string(note)),
 		}
 	}
 }
 
 impl EnumVariantStruct {
 	/// Creates a new struct variant from the given fields.
-	pub fn new<F>(name: <MetaForm as Form>::String, fields: F) -> Self
+	pub fn new<N, F>(name: N, fields: F) -> Self
 	where
+		N: Into<Cow<'static, str>>,
 		F: IntoIterator<Item = NamedField>,
 	{
 		Self {
-			name,
+			name: name.into(),
+			index: None,
 			fields: fields.into_iter().collect(),
+			docs: Vec::new(),
+			deprecated: None,
 		}
 	}
+
+	/// Sets the explicit index of the variant.
+	pub fn with_index(mut self, index: u64) -> Self {
+		self.index = Some(index);
+		self
+	}
+
+	/// Sets the documentation of the struct variant.
+	pub fn with_docs<S>(mut self, docs: &[S]) -> Self
+	where
+		S: Clone + Into<Cow<'static, str>>,
+	{
+		self.docs = docs.iter().cloned().map(Into::into).collect();
+		self
+	}
+
+	/// Marks the struct variant as deprecated with the given note.
+	pub fn with_deprecated<N>(mut self, note: N) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		self.deprecated = Some(note.into());
+		self
+	}
+}
+
+impl<F: Form> EnumVariantStruct<F> {
+	/// Returns the name of the struct variant.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns the explicit index of the variant, if any.
+	pub fn index(&self) -> Option<u64> {
+		self.index
+	}
+
+	/// Returns the fields of the struct variant.
+	pub fn fields(&self) -> &[NamedField<F>] {
+		&self.fields
+	}
+
+	/// Returns the documentation of the struct variant.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+
+	/// Returns the deprecation note of the struct variant if it is deprecated.
+	pub fn deprecated(&self) -> Option<&F::String> {
+		self.deprecated.as_ref()
+	}
 }
 
 /// A tuple struct enum variant.
@@ -546,43 +1588,146 @@ impl EnumVariantStruct {
 ///     }
 /// }
 /// ```
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: Deserialize<'de>, F::TypeId: Deserialize<'de>"
+))]
 pub struct EnumVariantTupleStruct<F: Form = MetaForm> {
 	/// The name of the variant.
 	#[serde(rename = "tuple_struct_variant.name")]
 	name: F::String,
+	/// The index of the variant if set explicitly.
+	///
+	/// Variants without an explicit index are indexed by their position.
+	#[serde(rename = "tuple_struct_variant.index", skip_serializing_if = "Option::is_none")]
+	index: Option<u64>,
 	/// The fields of the variant.
 	#[serde(rename = "tuple_struct_variant.types")]
 	fields: Vec<UnnamedField<F>>,
+	/// The documentation of the variant.
+	#[serde(rename = "tuple_struct_variant.docs", skip_serializing_if = "Vec::is_empty", default)]
+	docs: Vec<F::String>,
+	/// The deprecation note of the variant if it is deprecated.
+	#[serde(rename = "tuple_struct_variant.deprecated", skip_serializing_if = "Option::is_none")]
+	deprecated: Option<F::String>,
 }
 
 impl IntoCompact for EnumVariantTupleStruct {
 	type Output = EnumVariantTupleStruct<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
-		EnumVariantTupleStruct {
-			name: registry.register_string(self.name),
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(EnumVariantTupleStruct {
+			name: registry.register_string(self.name.clone()),
+			index: self.index,
 			fields: self
 				.fields
-				.into_iter()
-				.map(|field| field.into_compact(registry))
+				.iter()
+				.map(|field| field.to_compact(registry))
+				.collect::<Result<Vec<_>, _>>()?,
+			docs: self
+				.docs
+				.iter()
+				.map(|doc| registry.register_string(doc.clone()))
 				.collect::<Vec<_>>(),
+			deprecated: self
+				.deprecated
+				.as_ref()
+				.map(|note| registry.register_string(note.clone())),
+		})
+	}
+}
+
+impl MapSymbols for EnumVariantTupleStruct<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.name.map_symbols(map);
+		self.fields.map_symbols(map);
+		self.docs.map_symbols(map);
+		self.deprecated.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for EnumVariantTupleStruct<F> {
+	type Output = EnumVariantTupleStruct<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
+		EnumVariantTupleStruct {
+			name: map.map_string(self.name),
+			index: self.index,
+			fields: self.fields.into_iter().map(|field| field.convert_form(map)).collect(),
+			docs: self.docs.into_iter().map(|doc| map.map_string(doc)).collect(),
+			deprecated: self.deprecated.map(|note| map.map_string(note)),
 		}
 	}
 }
 
 impl EnumVariantTupleStruct {
 	/// Creates a new tuple struct enum variant from the given fields.
-	pub fn new<F>(name: <MetaForm as Form>::String, fields: F) -> Self
+	pub fn new<N, F>(name: N, fields: F) -> Self
 	where
+		N: Into<Cow<'static, str>>,
 		F: IntoIterator<Item = UnnamedField>,
 	{
 		Self {
-			name,
+			name: name.into(),
+			index: None,
 			fields: fields.into_iter().collect(),
+			docs: Vec::new(),
+			deprecated: None,
 		}
 	}
+
+	/// Sets the explicit index of the variant.
+	pub fn with_index(mut self, index: u64) -> Self {
+		self.index = Some(index);
+		self
+	}
+
+	/// Sets the documentation of the variant.
+	pub fn with_docs<S>(mut self, docs: &[S]) -> Self
+	where
+		S: Clone + Into<Cow<'static, str>>,
+	{
+		self.docs = docs.iter().cloned().map(Into::into).collect();
+		self
+	}
+
+	/// Marks the variant as deprecated with the given note.
+	pub fn with_deprecated<N>(mut self, note: N) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		self.deprecated = Some(note.into());
+		self
+	}
+}
+
+impl<F: Form> EnumVariantTupleStruct<F> {
+	/// Returns the name of the variant.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns the explicit index of the variant, if any.
+	pub fn index(&self) -> Option<u64> {
+		self.index
+	}
+
+	/// Returns the fields of the variant.
+	pub fn fields(&self) -> &[UnnamedField<F>] {
+		&self.fields
+	}
+
+	/// Returns the documentation of the variant.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+
+	/// Returns the deprecation note of the variant if it is deprecated.
+	pub fn deprecated(&self) -> Option<&F::String> {
+		self.deprecated.as_ref()
+	}
 }
 
 /// A union, aka untagged union, type definition.
@@ -595,24 +1740,63 @@ impl EnumVariantTupleStruct {
 ///     ext: *mut i32,
 /// }
 /// ```
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: Deserialize<'de>, F::TypeId: Deserialize<'de>"
+))]
 pub struct TypeDefUnion<F: Form = MetaForm> {
 	/// The fields of the union.
 	#[serde(rename = "union.fields")]
 	fields: Vec<NamedField<F>>,
+	/// The documentation of the union.
+	#[serde(rename = "union.docs", skip_serializing_if = "Vec::is_empty", default)]
+	docs: Vec<F::String>,
+	/// The deprecation note of the union if it is deprecated.
+	#[serde(rename = "union.deprecated", skip_serializing_if = "Option::is_none")]
+	deprecated: Option<F::String>,
 }
 
 impl IntoCompact for TypeDefUnion {
 	type Output = TypeDefUnion<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
-		TypeDefUnion {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(TypeDefUnion {
 			fields: self
 				.fields
-				.into_iter()
-				.map(|field| field.into_compact(registry))
+				.iter()
+				.map(|field| field.to_compact(registry))
+				.collect::<Result<Vec<_>, _>>()?,
+			docs: self
+				.docs
+				.iter()
+				.map(|doc| registry.register_string(doc.clone()))
 				.collect::<Vec<_>>(),
+			deprecated: self
+				.deprecated
+				.as_ref()
+				.map(|note| registry.register_string(note.clone())),
+		})
+	}
+}
+
+impl MapSymbols for TypeDefUnion<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.fields.map_symbols(map);
+		self.docs.map_symbols(map);
+		self.deprecated.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeDefUnion<F> {
+	type Output = TypeDefUnion<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
+		TypeDefUnion {
+			fields: self.fields.into_iter().map(|field| field.convert_form(map)).collect(),
+			docs: self.docs.into_iter().map(|doc| map.map_string(doc)).collect(),
+			deprecated: self.deprecated.map(|note| map.map_string(note)),
 		}
 	}
 }
@@ -625,6 +1809,43 @@ impl TypeDefUnion {
 	{
 		Self {
 			fields: fields.into_iter().collect(),
+			docs: Vec::new(),
+			deprecated: None,
 		}
 	}
+
+	/// Sets the documentation of the union.
+	pub fn with_docs<S>(mut self, docs: &[S]) -> Self
+	where
+		S: Clone + Into<Cow<'static, str>>,
+	{
+		self.docs = docs.iter().cloned().map(Into::into).collect();
+		self
+	}
+
+	/// Marks the union as deprecated with the given note.
+	pub fn with_deprecated<N>(mut self, note: N) -> Self
+	where
+		N: Into<Cow<'static, str>>,
+	{
+		self.deprecated = Some(note.into());
+		self
+	}
+}
+
+impl<F: Form> TypeDefUnion<F> {
+	/// Returns the fields of the union.
+	pub fn fields(&self) -> &[NamedField<F>] {
+		&self.fields
+	}
+
+	/// Returns the documentation of the union.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+
+	/// Returns the deprecation note of the union if it is deprecated.
+	pub fn deprecated(&self) -> Option<&F::String> {
+		self.deprecated.as_ref()
+	}
 }
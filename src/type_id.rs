@@ -17,17 +17,68 @@
 use crate::tm_std::*;
 
 use crate::{
-	form::{CompactForm, Form, MetaForm},
+	form::{CompactForm, ConvertForm, Form, FormMap, MetaForm},
+	registry::{MapSymbols, RegisterError, SymbolMap},
 	utils::is_rust_identifier,
 	IntoCompact, MetaType, Metadata, Registry,
 };
 use derive_more::From;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "codec")]
+use parity_scale_codec::{Decode, Encode, EncodeLike, Error, Input, Output};
+
+/// Implements the SCALE codec for single-field structs by forwarding to their field.
+///
+/// The codec derive cannot be used for them since the methods it generates
+/// for single-field structs declare an `F` parameter of their own.
+#[cfg(feature = "codec")]
+macro_rules! impl_codec_for_single_field {
+	( $ty:ident { $field:ident: $field_ty:ty } ) => {
+		impl<F: Form> Encode for $ty<F>
+		where
+			$field_ty: Encode,
+		{
+			fn size_hint(&self) -> usize {
+				self.$field.size_hint()
+			}
+
+			fn encode_to<W: Output>(&self, dest: &mut W) {
+				self.$field.encode_to(dest)
+			}
+		}
+
+		impl<F: Form> EncodeLike for $ty<F> where $field_ty: Encode {}
+
+		impl<F: Form> Decode for $ty<F>
+		where
+			$field_ty: Decode,
+		{
+			fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+				Ok(Self {
+					$field: Decode::decode(input)?,
+				})
+			}
+		}
+	};
+}
 
 /// Implementors return their meta type identifiers.
 pub trait HasTypeId {
 	/// Returns the static type identifier for `Self`.
 	fn type_id() -> TypeId;
+
+	/// Returns the type identifier provided by `core::any` under which `Self` is registered.
+	///
+	/// Types whose identifier and definition do not depend on some of their type parameters,
+	/// e.g. `PhantomData<T>`, return the same identity for all of their instantiations so
+	/// that these share a single registry entry.
+	fn any_id() -> AnyTypeId
+	where
+		Self: 'static,
+	{
+		AnyTypeId::of::<Self>()
+	}
 }
 
 /// Represents the namespace of a type definition.
@@ -36,13 +87,17 @@ pub trait HasTypeId {
 /// The first segment represents the crate name in which the type has been defined.
 ///
 /// Rust prelude type may have an empty namespace definition.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
+#[serde(bound(serialize = "F::String: Serialize", deserialize = "F::String: Deserialize<'de>"))]
 #[serde(transparent)]
 pub struct Namespace<F: Form = MetaForm> {
 	/// The segments of the namespace.
 	segments: Vec<F::String>,
 }
 
+#[cfg(feature = "codec")]
+impl_codec_for_single_field!(Namespace { segments: Vec<F::String> });
+
 /// An error that may be encountered upon constructing namespaces.
 #[derive(PartialEq, Eq, Debug)]
 pub enum NamespaceError {
@@ -59,13 +114,33 @@ impl IntoCompact for Namespace {
 	type Output = Namespace<CompactForm>;
 
 	/// Compacts this namespace using the given registry.
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(Namespace {
+			segments: self
+				.segments
+				.iter()
+				.map(|seg| registry.register_string(seg.clone()))
+				.collect::<Vec<_>>(),
+		})
+	}
+}
+
+impl MapSymbols for Namespace<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.segments.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for Namespace<F> {
+	type Output = Namespace<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		Namespace {
 			segments: self
 				.segments
 				.into_iter()
-				.map(|seg| registry.register_string(seg))
-				.collect::<Vec<_>>(),
+				.map(|segment| map.map_string(segment))
+				.collect(),
 		}
 	}
 }
@@ -74,9 +149,10 @@ impl Namespace {
 	/// Creates a new namespace from the given segments.
 	pub fn new<S>(segments: S) -> Result<Self, NamespaceError>
 	where
-		S: IntoIterator<Item = <MetaForm as Form>::String>,
+		S: IntoIterator,
+		S::Item: Into<Cow<'static, str>>,
 	{
-		let segments = segments.into_iter().collect::<Vec<_>>();
+		let segments = segments.into_iter().map(Into::into).collect::<Vec<_>>();
 		if segments.is_empty() {
 			return Err(NamespaceError::MissingSegments);
 		}
@@ -91,7 +167,7 @@ impl Namespace {
 	/// # Note
 	///
 	/// Module path is generally obtained from the `module_path!` Rust macro.
-	pub fn from_module_path(module_path: <MetaForm as Form>::String) -> Result<Self, NamespaceError> {
+	pub fn from_module_path(module_path: &'static str) -> Result<Self, NamespaceError> {
 		Self::new(module_path.split("::"))
 	}
 
@@ -101,24 +177,46 @@ impl Namespace {
 	}
 }
 
+impl<F: Form> Namespace<F> {
+	/// Returns the segments of the namespace.
+	pub fn segments(&self) -> &[F::String] {
+		&self.segments
+	}
+}
+
 /// A type identifier.
 ///
 /// This uniquely identifies types and can be used to refer to type definitions.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, From, Debug, Serialize)]
-#[serde(bound = "
-	F::TypeId: Serialize,
-	F::IndirectTypeId: Serialize
-")]
+///
+/// # Note
+///
+/// Tuples are serialized as sequences which serde would also accept for the
+/// slice and array structs. They are thus listed before them in order to be
+/// deserialized unambiguously.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, From, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "
+		F::TypeId: Serialize,
+		F::IndirectTypeId: Serialize
+	",
+	deserialize = "
+		F::String: Deserialize<'de>,
+		F::TypeId: Deserialize<'de>,
+		F::IndirectTypeId: Deserialize<'de>
+	"
+))]
 #[serde(untagged)]
+#[non_exhaustive]
 pub enum TypeId<F: Form = MetaForm> {
 	/// A custom type defined by the user.
 	Custom(TypeIdCustom<F>),
+	/// A tuple type.
+	Tuple(TypeIdTuple<F>),
 	/// A slice type with runtime known length.
 	Slice(TypeIdSlice<F>),
 	/// An array type with compile-time known lengh.
 	Array(TypeIdArray<F>),
-	/// A tuple type.
-	Tuple(TypeIdTuple<F>),
 	/// A Rust primitive type.
 	Primitive(TypeIdPrimitive),
 }
@@ -126,19 +224,47 @@ pub enum TypeId<F: Form = MetaForm> {
 impl IntoCompact for TypeId {
 	type Output = TypeId<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		registry.check_referred(self)?;
+		Ok(match self {
+			TypeId::Custom(custom) => custom.to_compact(registry)?.into(),
+			TypeId::Slice(slice) => slice.to_compact(registry)?.into(),
+			TypeId::Array(array) => array.to_compact(registry)?.into(),
+			TypeId::Tuple(tuple) => tuple.to_compact(registry)?.into(),
+			TypeId::Primitive(primitive) => primitive.clone().into(),
+		})
+	}
+}
+
+impl MapSymbols for TypeId<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		match self {
+			TypeId::Custom(custom) => custom.map_symbols(map),
+			TypeId::Slice(slice) => slice.map_symbols(map),
+			TypeId::Array(array) => array.map_symbols(map),
+			TypeId::Tuple(tuple) => tuple.map_symbols(map),
+			TypeId::Primitive(_) => (),
+		}
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeId<F> {
+	type Output = TypeId<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		match self {
-			TypeId::Custom(custom) => custom.into_compact(registry).into(),
-			TypeId::Slice(slice) => slice.into_compact(registry).into(),
-			TypeId::Array(array) => array.into_compact(registry).into(),
-			TypeId::Tuple(tuple) => tuple.into_compact(registry).into(),
+			TypeId::Custom(custom) => custom.convert_form(map).into(),
+			TypeId::Slice(slice) => slice.convert_form(map).into(),
+			TypeId::Array(array) => array.convert_form(map).into(),
+			TypeId::Tuple(tuple) => tuple.convert_form(map).into(),
 			TypeId::Primitive(primitive) => primitive.into(),
 		}
 	}
 }
 
 /// Identifies a primitive Rust type.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
 #[serde(rename_all = "lowercase")]
 pub enum TypeIdPrimitive {
 	/// `bool` type
@@ -167,11 +293,46 @@ pub enum TypeIdPrimitive {
 	I64,
 	/// `i128`
 	I128,
+	/// `f32`
+	#[cfg(feature = "floats")]
+	F32,
+	/// `f64`
+	#[cfg(feature = "floats")]
+	F64,
+}
+
+impl TypeIdPrimitive {
+	/// Returns the name of the primitive type, e.g. `u8`.
+	pub fn name(&self) -> &'static str {
+		match self {
+			TypeIdPrimitive::Bool => "bool",
+			TypeIdPrimitive::Char => "char",
+			TypeIdPrimitive::Str => "str",
+			TypeIdPrimitive::U8 => "u8",
+			TypeIdPrimitive::U16 => "u16",
+			TypeIdPrimitive::U32 => "u32",
+			TypeIdPrimitive::U64 => "u64",
+			TypeIdPrimitive::U128 => "u128",
+			TypeIdPrimitive::I8 => "i8",
+			TypeIdPrimitive::I16 => "i16",
+			TypeIdPrimitive::I32 => "i32",
+			TypeIdPrimitive::I64 => "i64",
+			TypeIdPrimitive::I128 => "i128",
+			#[cfg(feature = "floats")]
+			TypeIdPrimitive::F32 => "f32",
+			#[cfg(feature = "floats")]
+			TypeIdPrimitive::F64 => "f64",
+		}
+	}
 }
 
 /// A type identifier for custom type definitions.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: Deserialize<'de>, F::TypeId: Deserialize<'de>"
+))]
 pub struct TypeIdCustom<F: Form = MetaForm> {
 	/// The name of the custom type.
 	#[serde(rename = "custom.name")]
@@ -186,45 +347,169 @@ pub struct TypeIdCustom<F: Form = MetaForm> {
 	/// The generic type parameters of the custom type in use.
 	#[serde(rename = "custom.params")]
 	type_params: Vec<F::TypeId>,
+	/// The generic const parameters of the custom type in use.
+	#[serde(rename = "custom.const_params", skip_serializing_if = "Vec::is_empty", default)]
+	const_params: Vec<ConstParam>,
 }
 
 impl IntoCompact for TypeIdCustom {
 	type Output = TypeIdCustom<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(TypeIdCustom {
+			name: registry.register_string(self.name.clone()),
+			namespace: self.namespace.to_compact(registry)?,
+			type_params: self
+				.type_params
+				.iter()
+				.map(|param| registry.try_register_type(param))
+				.collect::<Result<Vec<_>, _>>()?,
+			const_params: self.const_params.clone(),
+		})
+	}
+}
+
+impl MapSymbols for TypeIdCustom<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.name.map_symbols(map);
+		self.namespace.map_symbols(map);
+		self.type_params.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeIdCustom<F> {
+	type Output = TypeIdCustom<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		TypeIdCustom {
-			name: registry.register_string(self.name),
-			namespace: self.namespace.into_compact(registry),
+			name: map.map_string(self.name),
+			namespace: self.namespace.convert_form(map),
 			type_params: self
 				.type_params
 				.into_iter()
-				.map(|param| registry.register_type(&param))
-				.collect::<Vec<_>>(),
+				.map(|param| map.map_type_id(param))
+				.collect(),
+			const_params: self.const_params,
 		}
 	}
 }
 
 impl TypeIdCustom {
 	/// Creates a new type identifier to refer to a custom type definition.
-	pub fn new<T>(name: &'static str, namespace: Namespace, type_params: T) -> Self
+	pub fn new<N, T>(name: N, namespace: Namespace, type_params: T) -> Self
 	where
+		N: Into<Cow<'static, str>>,
 		T: IntoIterator<Item = MetaType>,
 	{
 		Self {
-			name,
+			name: name.into(),
 			namespace,
 			type_params: type_params.into_iter().collect(),
+			const_params: Vec::new(),
+		}
+	}
+
+	/// Sets the generic const parameters of the custom type in use.
+	///
+	/// The values of const parameters are converted via `ConstParam::from`.
+	pub fn with_const_params<I>(mut self, const_params: I) -> Self
+	where
+		I: IntoIterator<Item = ConstParam>,
+	{
+		self.const_params = const_params.into_iter().collect();
+		self
+	}
+}
+
+impl<F: Form> TypeIdCustom<F> {
+	/// Returns the name of the custom type.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns the namespace in which the custom type has been defined.
+	pub fn namespace(&self) -> &Namespace<F> {
+		&self.namespace
+	}
+
+	/// Returns the generic type parameters of the custom type in use.
+	pub fn type_params(&self) -> &[F::TypeId] {
+		&self.type_params
+	}
+
+	/// Returns the generic const parameters of the custom type in use.
+	pub fn const_params(&self) -> &[ConstParam] {
+		&self.const_params
+	}
+}
+
+/// The value of a generic const parameter.
+///
+/// Values of all types allowed for const parameters convert losslessly into a const
+/// parameter. Unsigned integers, `bool` and `char` are converted into `u128` and signed
+/// integers into `i128` just as by an `as` cast.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(rename_all = "lowercase")]
+pub enum ConstParam {
+	/// The value of an unsigned integer, `bool` or `char` const parameter.
+	Unsigned(u128),
+	/// The value of a signed integer const parameter.
+	Signed(i128),
+}
+
+macro_rules! impl_from_for_const_param {
+	( $variant:ident($repr:ty): $( $ty:ty ),* ) => {
+		$(
+			impl From<$ty> for ConstParam {
+				fn from(value: $ty) -> Self {
+					ConstParam::$variant(value as $repr)
+				}
+			}
+		)*
+	};
+}
+
+impl_from_for_const_param!(Unsigned(u128): u8, u16, u32, u64, u128, usize, bool);
+impl_from_for_const_param!(Signed(i128): i8, i16, i32, i64, i128, isize);
+
+impl From<char> for ConstParam {
+	fn from(value: char) -> Self {
+		ConstParam::Unsigned(u32::from(value).into())
+	}
+}
+
+impl core::fmt::Display for ConstParam {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		match self {
+			ConstParam::Unsigned(value) => write!(f, "{}", value),
+			ConstParam::Signed(value) => write!(f, "{}", value),
 		}
 	}
 }
 
 /// An array type identifier.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
-#[serde(bound = "F::IndirectTypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+#[serde(bound(
+	serialize = "F::IndirectTypeId: Serialize",
+	deserialize = "F::IndirectTypeId: Deserialize<'de>"
+))]
 pub struct TypeIdArray<F: Form = MetaForm> {
 	/// The length of the array type definition.
+	///
+	/// # Note
+	///
+	/// Lengths used to be `u16` and are now `u64` in order to describe arrays of any length.
+	///
+	/// - JSON: lengths remain plain numbers, so existing metadata still deserializes.
+	///   Consumers that parse lengths as `u16` have to be widened in order to accept
+	///   lengths beyond `65535`.
+	/// - SCALE: lengths are encoded as fixed-width `u64` of 8 bytes instead of 2 bytes,
+	///   so SCALE encoded metadata from before the change cannot be decoded anymore and
+	///   needs to be re-generated.
 	#[serde(rename = "array.len")]
-	pub len: u16,
+	pub len: u64,
 	/// The element type of the array type definition.
 	#[serde(rename = "array.type")]
 	pub type_param: F::IndirectTypeId,
@@ -233,40 +518,80 @@ pub struct TypeIdArray<F: Form = MetaForm> {
 impl IntoCompact for TypeIdArray {
 	type Output = TypeIdArray<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(TypeIdArray {
+			len: self.len,
+			type_param: registry.try_register_type(&self.type_param)?,
+		})
+	}
+}
+
+impl MapSymbols for TypeIdArray<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.type_param.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeIdArray<F> {
+	type Output = TypeIdArray<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		TypeIdArray {
 			len: self.len,
-			type_param: registry.register_type(&self.type_param),
+			type_param: map.map_indirect_type_id(self.type_param),
 		}
 	}
 }
 
 impl TypeIdArray {
 	/// Creates a new identifier to refer to array type definition.
-	pub fn new(len: u16, type_param: MetaType) -> Self {
+	pub fn new(len: u64, type_param: MetaType) -> Self {
 		Self { len, type_param }
 	}
 }
 
 /// A type identifier to refer to tuple types.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
+#[serde(bound(serialize = "F::TypeId: Serialize", deserialize = "F::TypeId: Deserialize<'de>"))]
 #[serde(transparent)]
 pub struct TypeIdTuple<F: Form = MetaForm> {
 	/// The types in the tuple type definition.
 	pub type_params: Vec<F::TypeId>,
 }
 
+#[cfg(feature = "codec")]
+impl_codec_for_single_field!(TypeIdTuple { type_params: Vec<F::TypeId> });
+
 impl IntoCompact for TypeIdTuple {
 	type Output = TypeIdTuple<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(TypeIdTuple {
+			type_params: self
+				.type_params
+				.iter()
+				.map(|param| registry.try_register_type(param))
+				.collect::<Result<Vec<_>, _>>()?,
+		})
+	}
+}
+
+impl MapSymbols for TypeIdTuple<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.type_params.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeIdTuple<F> {
+	type Output = TypeIdTuple<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		TypeIdTuple {
 			type_params: self
 				.type_params
 				.into_iter()
-				.map(|param| registry.register_type(&param))
-				.collect::<Vec<_>>(),
+				.map(|param| map.map_type_id(param))
+				.collect(),
 		}
 	}
 }
@@ -289,20 +614,44 @@ impl TypeIdTuple {
 }
 
 /// A type identifier to refer to slice type definitions.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
-#[serde(bound = "F::IndirectTypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
+#[serde(bound(
+	serialize = "F::IndirectTypeId: Serialize",
+	deserialize = "F::IndirectTypeId: Deserialize<'de>"
+))]
 pub struct TypeIdSlice<F: Form = MetaForm> {
 	/// The element type of the slice type definition.
 	#[serde(rename = "slice.type")]
 	type_param: F::IndirectTypeId,
 }
 
+#[cfg(feature = "codec")]
+impl_codec_for_single_field!(TypeIdSlice {
+	type_param: F::IndirectTypeId
+});
+
 impl IntoCompact for TypeIdSlice {
 	type Output = TypeIdSlice<CompactForm>;
 
-	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+	fn to_compact(&self, registry: &mut Registry) -> Result<Self::Output, RegisterError> {
+		Ok(TypeIdSlice {
+			type_param: registry.try_register_type(&self.type_param)?,
+		})
+	}
+}
+
+impl MapSymbols for TypeIdSlice<CompactForm> {
+	fn map_symbols<M: SymbolMap>(&mut self, map: &mut M) {
+		self.type_param.map_symbols(map);
+	}
+}
+
+impl<F: Form, G: Form> ConvertForm<F, G> for TypeIdSlice<F> {
+	type Output = TypeIdSlice<G>;
+
+	fn convert_form<M: FormMap<F, G>>(self, map: &mut M) -> Self::Output {
 		TypeIdSlice {
-			type_param: registry.register_type(&self.type_param),
+			type_param: map.map_indirect_type_id(self.type_param),
 		}
 	}
 }
@@ -326,6 +675,13 @@ impl TypeIdSlice {
 	}
 }
 
+impl<F: Form> TypeIdSlice<F> {
+	/// Returns the element type of the slice type definition.
+	pub fn type_param(&self) -> &F::IndirectTypeId {
+		&self.type_param
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -335,21 +691,26 @@ mod tests {
 		assert_eq!(
 			Namespace::new(vec!["hello"]),
 			Ok(Namespace {
-				segments: vec!["hello"]
+				segments: vec!["hello".into()]
 			})
 		);
 		assert_eq!(
 			Namespace::new(vec!["Hello", "World"]),
 			Ok(Namespace {
-				segments: vec!["Hello", "World"]
+				segments: vec!["Hello".into(), "World".into()]
+			})
+		);
+		assert_eq!(
+			Namespace::new(vec!["_"]),
+			Ok(Namespace {
+				segments: vec!["_".into()]
 			})
 		);
-		assert_eq!(Namespace::new(vec!["_"]), Ok(Namespace { segments: vec!["_"] }));
 	}
 
 	#[test]
 	fn namespace_err() {
-		assert_eq!(Namespace::new(vec![]), Err(NamespaceError::MissingSegments));
+		assert_eq!(Namespace::new(Vec::<&str>::new()), Err(NamespaceError::MissingSegments));
 		assert_eq!(
 			Namespace::new(vec![""]),
 			Err(NamespaceError::InvalidIdentifier { segment: 0 })
@@ -369,7 +730,7 @@ mod tests {
 		assert_eq!(
 			Namespace::from_module_path("hello::world"),
 			Ok(Namespace {
-				segments: vec!["hello", "world"]
+				segments: vec!["hello".into(), "world".into()]
 			})
 		);
 		assert_eq!(
@@ -0,0 +1,195 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integrity validation of registries.
+//!
+//! Type identifiers and definitions refer to strings and other types by their symbols.
+//! A valid registry stores an entry for every symbol referred to, stores no strings
+//! that are not referred to and has no type identifiers referring back to themselves.
+//!
+//! Type definitions may refer back to their own type, e.g. for recursive types such as
+//! trees, since they only refer to it indirectly by its symbol. Type identifiers in
+//! contrast are rendered as the names of types and thus must not be cyclic.
+//!
+//! Registries built by registering types are always valid, whereas registries loaded
+//! from their serialized form should be validated before they are used.
+//!
+//! # Example
+//!
+//! ```
+//! # use type_metadata::{tuple_meta_type, Registry};
+//! let registry = tuple_meta_type!(Option<u32>, Vec<bool>).into_iter().collect::<Registry>();
+//! assert_eq!(registry.validate(), Ok(()));
+//! ```
+
+use crate::tm_std::*;
+use crate::{
+	interner::UntrackedSymbol,
+	registry::{MapSymbols, SymbolMap},
+	TypeIdDef,
+};
+
+/// An integrity error of a registry.
+///
+/// Strings and types are identified by the identifiers of their symbols.
+#[derive(PartialEq, Eq, Debug)]
+pub enum ValidationError {
+	/// A type refers to a string that is not stored in the registry.
+	MissingString {
+		/// The type referring to the string.
+		ty: u32,
+		/// The missing string.
+		string: u32,
+	},
+	/// A type refers to another type that is not stored in the registry.
+	MissingType {
+		/// The type referring to the other type.
+		ty: u32,
+		/// The missing type.
+		referenced: u32,
+	},
+	/// A capability tags a type that is not stored in the registry.
+	MissingTaggedType {
+		/// The name of the capability.
+		capability: String,
+		/// The missing type.
+		ty: u32,
+	},
+	/// A string is not referred to by any type.
+	UnusedString {
+		/// The unused string.
+		string: u32,
+	},
+	/// The identifier of a type refers back to the type itself, e.g. as its own type parameter.
+	CyclicTypeId {
+		/// The type with the cyclic identifier.
+		ty: u32,
+	},
+}
+
+/// Collects the identifiers of all symbols referred to, leaving the symbols unchanged.
+#[derive(Default)]
+struct Referenced {
+	strings: Vec<u32>,
+	types: Vec<u32>,
+}
+
+impl Referenced {
+	/// Returns the symbols referred to by the given compacted type identifier or definition.
+	fn of<T: MapSymbols + Clone>(symbols: &T) -> Self {
+		let mut referenced = Self::default();
+		symbols.clone().map_symbols(&mut referenced);
+		referenced
+	}
+}
+
+impl SymbolMap for Referenced {
+	fn map_string(&mut self, symbol: UntrackedSymbol<String>) -> UntrackedSymbol<String> {
+		if !self.strings.contains(&symbol.id().get()) {
+			self.strings.push(symbol.id().get());
+		}
+		symbol
+	}
+
+	fn map_type(&mut self, symbol: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		if !self.types.contains(&symbol.id().get()) {
+			self.types.push(symbol.id().get());
+		}
+		symbol
+	}
+}
+
+/// Validates the given types and capabilities against the number of stored strings.
+///
+/// The type at index `i` is the one with symbol identifier `i + 1`.
+pub(crate) fn validate<'a, C>(strings: usize, types: &[&TypeIdDef], capabilities: C) -> Result<(), Vec<ValidationError>>
+where
+	C: IntoIterator<Item = (&'a str, &'a [UntrackedSymbol<AnyTypeId>])>,
+{
+	let exists = |id: u32, len: usize| (id as usize) <= len;
+	let mut errors = Vec::new();
+	let mut used_strings = vec![false; strings];
+	let mut id_references = Vec::with_capacity(types.len());
+	for (index, type_id_def) in types.iter().enumerate() {
+		let ty = index as u32 + 1;
+		let type_id = Referenced::of(type_id_def.id());
+		let type_def = Referenced::of(type_id_def.def());
+		for string in type_id.strings.iter().chain(&type_def.strings) {
+			if exists(*string, strings) {
+				used_strings[*string as usize - 1] = true;
+			} else {
+				errors.push(ValidationError::MissingString { ty, string: *string });
+			}
+		}
+		for referenced in type_id.types.iter().chain(&type_def.types) {
+			if !exists(*referenced, types.len()) {
+				errors.push(ValidationError::MissingType {
+					ty,
+					referenced: *referenced,
+				});
+			}
+		}
+		id_references.push(type_id.types);
+	}
+	for (capability, tagged) in capabilities {
+		for symbol in tagged {
+			if !exists(symbol.id().get(), types.len()) {
+				errors.push(ValidationError::MissingTaggedType {
+					capability: capability.to_string(),
+					ty: symbol.id().get(),
+				});
+			}
+		}
+	}
+	for (index, used) in used_strings.into_iter().enumerate() {
+		if !used {
+			errors.push(ValidationError::UnusedString {
+				string: index as u32 + 1,
+			});
+		}
+	}
+	for ty in 1..=types.len() as u32 {
+		if refers_to(&id_references, ty, ty) {
+			errors.push(ValidationError::CyclicTypeId { ty });
+		}
+	}
+	if errors.is_empty() {
+		Ok(())
+	} else {
+		Err(errors)
+	}
+}
+
+/// Returns `true` if the identifier of type `from` transitively refers to type `to`.
+fn refers_to(id_references: &[Vec<u32>], from: u32, to: u32) -> bool {
+	let mut visited = vec![false; id_references.len()];
+	let mut pending = vec![from];
+	while let Some(ty) = pending.pop() {
+		for referenced in id_references.get(ty as usize - 1).into_iter().flatten() {
+			if *referenced == to {
+				return true;
+			}
+			match visited.get_mut(*referenced as usize - 1) {
+				Some(seen) if !*seen => {
+					*seen = true;
+					pending.push(*referenced);
+				}
+				_ => (),
+			}
+		}
+	}
+	false
+}
@@ -0,0 +1,255 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Visitors to match on type identifiers and definitions.
+//!
+//! `TypeId`, `TypeDef` and `EnumVariant` are non-exhaustive since new kinds
+//! of types may be added in the future. Instead of matching on them with a
+//! wildcard arm, downstream code may implement the visitors below that only
+//! handle the kinds they are interested in and fall back to `visit_default`
+//! for all others, including kinds added in future versions.
+//!
+//! # Example
+//!
+//! ```
+//! # use type_metadata::{visit::TypeDefVisitor, HasTypeDef, TypeDefStruct};
+//! struct NumFields;
+//!
+//! impl TypeDefVisitor for NumFields {
+//! 	type Output = usize;
+//!
+//! 	fn visit_struct(&mut self, r#struct: &TypeDefStruct) -> usize {
+//! 		r#struct.fields().len()
+//! 	}
+//!
+//! 	fn visit_default(&mut self) -> usize {
+//! 		0
+//! 	}
+//! }
+//!
+//! assert_eq!(<Vec<u8>>::type_def().accept(&mut NumFields), 1);
+//! assert_eq!(<Option<u8>>::type_def().accept(&mut NumFields), 0);
+//! ```
+
+use crate::{
+	form::{Form, MetaForm},
+	Builtin, EnumVariant, EnumVariantStruct, EnumVariantTupleStruct, EnumVariantUnit, TypeDef, TypeDefClikeEnum,
+	TypeDefEnum, TypeDefStruct, TypeDefTupleStruct, TypeDefUnion, TypeId, TypeIdArray, TypeIdCustom, TypeIdPrimitive,
+	TypeIdSlice, TypeIdTuple,
+};
+
+/// Visits the different kinds of type identifiers.
+///
+/// All kinds that are not explicitly handled fall back to `visit_default`.
+pub trait TypeIdVisitor<F: Form = MetaForm> {
+	/// The result of visiting a type identifier.
+	type Output;
+
+	/// Visits a custom type identifier.
+	fn visit_custom(&mut self, _custom: &TypeIdCustom<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits a slice type identifier.
+	fn visit_slice(&mut self, _slice: &TypeIdSlice<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits an array type identifier.
+	fn visit_array(&mut self, _array: &TypeIdArray<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits a tuple type identifier.
+	fn visit_tuple(&mut self, _tuple: &TypeIdTuple<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits a primitive type identifier.
+	fn visit_primitive(&mut self, _primitive: &TypeIdPrimitive) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits any type identifier that is not explicitly handled.
+	fn visit_default(&mut self) -> Self::Output;
+}
+
+impl<F: Form> TypeId<F> {
+	/// Dispatches to the visitor method of the kind of this type identifier.
+	pub fn accept<V: TypeIdVisitor<F>>(&self, visitor: &mut V) -> V::Output {
+		match self {
+			TypeId::Custom(custom) => visitor.visit_custom(custom),
+			TypeId::Slice(slice) => visitor.visit_slice(slice),
+			TypeId::Array(array) => visitor.visit_array(array),
+			TypeId::Tuple(tuple) => visitor.visit_tuple(tuple),
+			TypeId::Primitive(primitive) => visitor.visit_primitive(primitive),
+		}
+	}
+}
+
+/// Visits the different kinds of type definitions.
+///
+/// All kinds that are not explicitly handled fall back to `visit_default`.
+pub trait TypeDefVisitor<F: Form = MetaForm> {
+	/// The result of visiting a type definition.
+	type Output;
+
+	/// Visits a builtin type definition.
+	fn visit_builtin(&mut self, _builtin: &Builtin) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits a struct definition.
+	fn visit_struct(&mut self, _struct: &TypeDefStruct<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits a tuple-struct definition.
+	fn visit_tuple_struct(&mut self, _tuple_struct: &TypeDefTupleStruct<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits a C-like enum definition.
+	fn visit_clike_enum(&mut self, _clike_enum: &TypeDefClikeEnum<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits an enum definition.
+	fn visit_enum(&mut self, _enum: &TypeDefEnum<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits a union definition.
+	fn visit_union(&mut self, _union: &TypeDefUnion<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits any type definition that is not explicitly handled.
+	fn visit_default(&mut self) -> Self::Output;
+}
+
+impl<F: Form> TypeDef<F> {
+	/// Dispatches to the visitor method of the kind of this type definition.
+	pub fn accept<V: TypeDefVisitor<F>>(&self, visitor: &mut V) -> V::Output {
+		match self {
+			TypeDef::Builtin(builtin) => visitor.visit_builtin(builtin),
+			TypeDef::Struct(r#struct) => visitor.visit_struct(r#struct),
+			TypeDef::TupleStruct(tuple_struct) => visitor.visit_tuple_struct(tuple_struct),
+			TypeDef::ClikeEnum(clike_enum) => visitor.visit_clike_enum(clike_enum),
+			TypeDef::Enum(r#enum) => visitor.visit_enum(r#enum),
+			TypeDef::Union(union) => visitor.visit_union(union),
+		}
+	}
+}
+
+/// Visits the different kinds of enum variants.
+///
+/// All kinds that are not explicitly handled fall back to `visit_default`.
+pub trait EnumVariantVisitor<F: Form = MetaForm> {
+	/// The result of visiting an enum variant.
+	type Output;
+
+	/// Visits a unit variant.
+	fn visit_unit(&mut self, _unit: &EnumVariantUnit<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits a struct variant.
+	fn visit_struct(&mut self, _struct: &EnumVariantStruct<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits a tuple-struct variant.
+	fn visit_tuple_struct(&mut self, _tuple_struct: &EnumVariantTupleStruct<F>) -> Self::Output {
+		self.visit_default()
+	}
+
+	/// Visits any enum variant that is not explicitly handled.
+	fn visit_default(&mut self) -> Self::Output;
+}
+
+impl<F: Form> EnumVariant<F> {
+	/// Dispatches to the visitor method of the kind of this enum variant.
+	pub fn accept<V: EnumVariantVisitor<F>>(&self, visitor: &mut V) -> V::Output {
+		match self {
+			EnumVariant::Unit(unit) => visitor.visit_unit(unit),
+			EnumVariant::Struct(r#struct) => visitor.visit_struct(r#struct),
+			EnumVariant::TupleStruct(tuple_struct) => visitor.visit_tuple_struct(tuple_struct),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::*;
+
+	/// Counts the fields of all variants of an enum.
+	struct CountFields;
+
+	impl EnumVariantVisitor for CountFields {
+		type Output = usize;
+
+		fn visit_struct(&mut self, r#struct: &EnumVariantStruct) -> usize {
+			r#struct.fields().len()
+		}
+
+		fn visit_tuple_struct(&mut self, tuple_struct: &EnumVariantTupleStruct) -> usize {
+			tuple_struct.fields().len()
+		}
+
+		fn visit_default(&mut self) -> usize {
+			0
+		}
+	}
+
+	impl TypeDefVisitor for CountFields {
+		type Output = usize;
+
+		fn visit_enum(&mut self, r#enum: &TypeDefEnum) -> usize {
+			r#enum.variants().iter().map(|variant| variant.accept(self)).sum()
+		}
+
+		fn visit_default(&mut self) -> usize {
+			0
+		}
+	}
+
+	/// Returns the name of custom types.
+	struct CustomName;
+
+	impl TypeIdVisitor for CustomName {
+		type Output = Option<String>;
+
+		fn visit_custom(&mut self, custom: &TypeIdCustom) -> Self::Output {
+			Some(custom.name().to_string())
+		}
+
+		fn visit_default(&mut self) -> Self::Output {
+			None
+		}
+	}
+
+	#[test]
+	fn visitors_dispatch_or_fall_back() {
+		assert_eq!(<Result<u8, bool>>::type_def().accept(&mut CountFields), 2);
+		assert_eq!(<Option<u8>>::type_def().accept(&mut CountFields), 1);
+		assert_eq!(<Vec<u8>>::type_def().accept(&mut CountFields), 0);
+
+		assert_eq!(<Option<u8>>::type_id().accept(&mut CustomName), Some("Option".into()));
+		assert_eq!(<[u8; 4]>::type_id().accept(&mut CustomName), None);
+	}
+}
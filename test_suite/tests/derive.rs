@@ -20,12 +20,12 @@
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-use alloc::{boxed::Box, vec};
+use alloc::{boxed::Box, vec, vec::Vec};
 
 use type_metadata::{
-	tuple_meta_type, ClikeEnumVariant, EnumVariantStruct, EnumVariantTupleStruct, EnumVariantUnit, HasTypeDef,
-	HasTypeId, MetaType, Metadata, NamedField, Namespace, TypeDefClikeEnum, TypeDefEnum, TypeDefStruct,
-	TypeDefTupleStruct, TypeDefUnion, TypeId, TypeIdCustom, UnnamedField,
+	tuple_meta_type, ClikeEnumVariant, ConstParam, EnumVariantStruct, EnumVariantTupleStruct, EnumVariantUnit,
+	HasTypeDef, HasTypeId, MetaType, Metadata, NamedField, Namespace, RegisterError, Registry, TypeDef,
+	TypeDefClikeEnum, TypeDefEnum, TypeDefStruct, TypeDefTupleStruct, TypeDefUnion, TypeId, TypeIdCustom, UnnamedField,
 };
 
 fn assert_type_id<T, E>(expected: E)
@@ -39,7 +39,7 @@ where
 macro_rules! assert_type_id {
 	( $ty:ty, $expected:expr ) => {{
 		assert_type_id::<$ty, _>($expected)
-		}};
+	}};
 }
 
 #[test]
@@ -115,14 +115,39 @@ fn c_like_enum_derive() {
 	enum E {
 		A,
 		B = 10,
+		C,
 	}
 
 	let type_id = TypeIdCustom::new("E", Namespace::new(vec!["derive"]).unwrap(), vec![]);
 	assert_type_id!(E, type_id);
 
+	// Variants are recorded with their Rust discriminants.
 	let type_def = TypeDefClikeEnum::new(vec![
 		ClikeEnumVariant::new("A", 0u64),
 		ClikeEnumVariant::new("B", 10u64),
+		ClikeEnumVariant::new("C", E::C as u64),
+	])
+	.into();
+	assert_eq!(E::type_def(), type_def);
+	assert_eq!(E::C as u8, 11);
+}
+
+#[test]
+fn c_like_enum_discriminant_expr_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E {
+		A = 1 << 4,
+		B = (2 + 3) * 2,
+		C,
+		D = 0xF0 | 0x0F ^ 0x01,
+	}
+
+	let type_def = TypeDefClikeEnum::new(vec![
+		ClikeEnumVariant::new("A", 16u64),
+		ClikeEnumVariant::new("B", 10u64),
+		ClikeEnumVariant::new("C", 11u64),
+		ClikeEnumVariant::new("D", 254u64),
 	])
 	.into();
 	assert_eq!(E::type_def(), type_def);
@@ -151,17 +176,1173 @@ fn enum_derive() {
 }
 
 #[test]
-// #[should_panic] // TODO: remove #[should_panic]
-fn union_derive() {
+fn enum_reserved_indices_derive() {
 	#[allow(unused)]
 	#[derive(Metadata)]
-	union U<T: Copy> {
-		u: T,
+	#[metadata(reserved_indices(1, 3))]
+	enum C {
+		A,
+		B = 2,
 	}
 
-	let type_id = TypeIdCustom::new("U", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!(bool));
-	assert_type_id!(U<bool>, type_id);
+	let type_def = TypeDefClikeEnum::new(vec![ClikeEnumVariant::new("A", 0u64), ClikeEnumVariant::new("B", 2u64)])
+		.with_reserved_indices(vec![1, 3])
+		.into();
+	assert_eq!(C::type_def(), type_def);
 
-	let type_def = TypeDefUnion::new(vec![NamedField::new("u", bool::meta_type())]).into();
-	assert_eq!(<U<bool>>::type_def(), type_def);
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(reserved_indices(2))]
+	enum E {
+		A(bool),
+		B,
+	}
+
+	let type_def = TypeDefEnum::new(vec![
+		EnumVariantTupleStruct::new("A", vec![UnnamedField::of::<bool>()]).into(),
+		EnumVariantUnit::new("B").into(),
+	])
+	.with_reserved_indices(vec![2])
+	.into();
+	assert_eq!(E::type_def(), type_def);
+}
+
+#[test]
+fn skipped_fields_derive() {
+	// Does not implement `Metadata`.
+	struct Cache;
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S {
+		a: bool,
+		#[metadata(skip)]
+		cache: Cache,
+	}
+
+	let type_def = TypeDefStruct::new(vec![NamedField::new("a", bool::meta_type())]).into();
+	assert_eq!(S::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E {
+		A(#[metadata(skip)] Cache, u8),
+		B {
+			#[metadata(skip)]
+			cache: Cache,
+			b: bool,
+		},
+	}
+
+	let type_def = TypeDefEnum::new(vec![
+		EnumVariantTupleStruct::new("A", vec![UnnamedField::of::<u8>()]).into(),
+		EnumVariantStruct::new("B", vec![NamedField::new("b", bool::meta_type())]).into(),
+	])
+	.into();
+	assert_eq!(E::type_def(), type_def);
+}
+
+#[test]
+fn opaque_fields_derive() {
+	// Does not implement `Metadata`.
+	struct Cache;
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S {
+		a: bool,
+		#[metadata(opaque)]
+		cache: Cache,
+	}
+
+	let type_def = TypeDefStruct::new(vec![
+		NamedField::new("a", bool::meta_type()),
+		NamedField::new("cache", MetaType::opaque()),
+	])
+	.into();
+	assert_eq!(S::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Mixed {
+		#[metadata(opaque)]
+		a: u32,
+		b: u32,
+	}
+
+	// The opaque field does not stand in for the plain field of the same type.
+	assert_ne!(MetaType::opaque(), u32::meta_type());
+	let mut registry = Registry::new();
+	registry.register_type(&Mixed::meta_type());
+	assert_eq!(registry.types().count(), 3);
+}
+
+#[test]
+fn renamed_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(rename = "Point")]
+	struct S {
+		#[metadata(rename = "xCoord")]
+		x: u8,
+		y: u8,
+	}
+
+	let type_id = TypeIdCustom::new("Point", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!());
+	assert_type_id!(S, type_id);
+
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<u8>("xCoord"), NamedField::of::<u8>("y")]).into();
+	assert_eq!(S::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E {
+		#[metadata(rename = "first")]
+		A(bool),
+		B {
+			#[metadata(rename = "value")]
+			b: bool,
+		},
+	}
+
+	let type_def = TypeDefEnum::new(vec![
+		EnumVariantTupleStruct::new("first", vec![UnnamedField::of::<bool>()]).into(),
+		EnumVariantStruct::new("B", vec![NamedField::of::<bool>("value")]).into(),
+	])
+	.into();
+	assert_eq!(E::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum C {
+		#[metadata(rename = "a")]
+		A,
+		B,
+	}
+
+	let type_def =
+		TypeDefClikeEnum::new(vec![ClikeEnumVariant::new("a", 0u64), ClikeEnumVariant::new("B", 1u64)]).into();
+	assert_eq!(C::type_def(), type_def);
+}
+
+#[test]
+fn custom_bound_derive() {
+	trait Config {
+		type Balance;
+	}
+
+	#[derive(Metadata)]
+	struct Runtime;
+
+	impl Config for Runtime {
+		type Balance = u64;
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(bound = "T: Config + Metadata + 'static, T::Balance: Metadata + 'static")]
+	struct Account<T: Config> {
+		balance: T::Balance,
+	}
+
+	let type_id = TypeIdCustom::new(
+		"Account",
+		Namespace::new(vec!["derive"]).unwrap(),
+		tuple_meta_type!(Runtime),
+	);
+	assert_type_id!(Account<Runtime>, type_id);
+
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<u64>("balance")]).into();
+	assert_eq!(<Account<Runtime>>::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(bound = "")]
+	struct Wrapper<T: Metadata + 'static>(T);
+
+	let type_def = TypeDefTupleStruct::new(vec![UnnamedField::of::<bool>()]).into();
+	assert_eq!(<Wrapper<bool>>::type_def(), type_def);
+}
+
+#[test]
+fn declaration_order_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S<Z, A, M> {
+		zeta: Z,
+		alpha: A,
+		mu: M,
+		beta: bool,
+	}
+
+	let type_id = TypeIdCustom::new(
+		"S",
+		Namespace::new(vec!["derive"]).unwrap(),
+		tuple_meta_type!(u8, u16, u32),
+	);
+	assert_type_id!(S<u8, u16, u32>, type_id);
+
+	let type_def = TypeDefStruct::new(vec![
+		NamedField::of::<u8>("zeta"),
+		NamedField::of::<u16>("alpha"),
+		NamedField::of::<u32>("mu"),
+		NamedField::of::<bool>("beta"),
+	])
+	.into();
+	assert_eq!(<S<u8, u16, u32>>::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E {
+		Zeta(u8, bool),
+		Alpha,
+		Mu { z: u8, a: bool },
+	}
+
+	let type_def = TypeDefEnum::new(vec![
+		EnumVariantTupleStruct::new("Zeta", vec![UnnamedField::of::<u8>(), UnnamedField::of::<bool>()]).into(),
+		EnumVariantUnit::new("Alpha").into(),
+		EnumVariantStruct::new("Mu", vec![NamedField::of::<u8>("z"), NamedField::of::<bool>("a")]).into(),
+	])
+	.into();
+	assert_eq!(E::type_def(), type_def);
+}
+
+#[test]
+fn lifetimes_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Borrowed<'a, T> {
+		s: &'a str,
+		items: &'a [T],
+		buf: &'a mut [u8; 4],
+	}
+
+	let type_id = TypeIdCustom::new(
+		"Borrowed",
+		Namespace::new(vec!["derive"]).unwrap(),
+		tuple_meta_type!(u8),
+	);
+	assert_type_id!(Borrowed<u8>, type_id);
+
+	let type_def = TypeDefStruct::new(vec![
+		NamedField::of::<&'static str>("s"),
+		NamedField::of::<&'static [u8]>("items"),
+		NamedField::of::<&'static mut [u8; 4]>("buf"),
+	])
+	.into();
+	assert_eq!(<Borrowed<'static, u8>>::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum Cow<'a> {
+		Borrowed(&'a str),
+		Owned(Box<str>),
+	}
+
+	let type_def = TypeDefEnum::new(vec![
+		EnumVariantTupleStruct::new("Borrowed", vec![UnnamedField::of::<&'static str>()]).into(),
+		EnumVariantTupleStruct::new("Owned", vec![UnnamedField::of::<Box<str>>()]).into(),
+	])
+	.into();
+	assert_eq!(Cow::type_def(), type_def);
+}
+
+#[test]
+fn const_generics_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(bound = "T: Metadata + 'static, [T; N]: Metadata")]
+	struct Buf<T, const N: usize, const FLAG: bool> {
+		data: [T; N],
+	}
+
+	let type_id = TypeIdCustom::new("Buf", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!(u8))
+		.with_const_params(vec![ConstParam::Unsigned(4), ConstParam::Unsigned(1)]);
+	assert_type_id!(Buf<u8, 4, true>, type_id);
+
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<[u8; 4]>("data")]).into();
+	assert_eq!(<Buf<u8, 4, true>>::type_def(), type_def);
+
+	// Const parameters of all types are described losslessly.
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Wide<const S: i8, const U: u128>;
+
+	let wide = |const_params| {
+		TypeIdCustom::new("Wide", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!())
+			.with_const_params(const_params)
+	};
+	assert_type_id!(
+		Wide<-1, 255>,
+		wide(vec![ConstParam::Signed(-1), ConstParam::Unsigned(255)])
+	);
+	assert_type_id!(
+		Wide<127, { u128::MAX }>,
+		wide(vec![ConstParam::Signed(127), ConstParam::Unsigned(u128::MAX)])
+	);
+	assert_ne!(<Wide<-1, 0>>::type_id(), <Wide<1, 0>>::type_id());
+}
+
+#[cfg(not(feature = "docs"))]
+#[test]
+fn docs_not_collected_derive() {
+	/// A point.
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S {
+		/// The x coordinate.
+		x: u8,
+	}
+
+	// The documentation fields are still present but left empty.
+	let type_def = S::type_def();
+	let type_def = type_def.as_struct().unwrap();
+	assert!(type_def.docs().is_empty());
+	assert!(type_def.fields()[0].docs().is_empty());
+}
+
+#[cfg(feature = "docs")]
+#[test]
+fn docs_derive() {
+	/// A point.
+	///
+	/// With docs spanning multiple lines.
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S {
+		/// The x coordinate.
+		x: u8,
+		y: u8,
+	}
+
+	let type_def = TypeDefStruct::new(vec![
+		NamedField::of::<u8>("x").with_docs(&["The x coordinate."]),
+		NamedField::of::<u8>("y"),
+	])
+	.with_docs(&["A point.", "", "With docs spanning multiple lines."])
+	.into();
+	assert_eq!(S::type_def(), type_def);
+
+	let type_def = S::type_def();
+	let type_def = type_def.as_struct().unwrap();
+	assert_eq!(type_def.docs(), &["A point.", "", "With docs spanning multiple lines."]);
+	assert_eq!(type_def.fields()[0].docs(), &["The x coordinate."]);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E {
+		/// A unit variant.
+		A,
+		/// A tuple variant.
+		B(bool),
+	}
+
+	let type_def = TypeDefEnum::new(vec![
+		EnumVariantUnit::new("A").with_docs(&["A unit variant."]).into(),
+		EnumVariantTupleStruct::new("B", vec![UnnamedField::of::<bool>()])
+			.with_docs(&["A tuple variant."])
+			.into(),
+	])
+	.into();
+	assert_eq!(E::type_def(), type_def);
+}
+
+#[test]
+fn transparent_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(transparent)]
+	struct Balance(u128);
+
+	assert_eq!(Balance::type_id(), u128::type_id());
+	assert_eq!(Balance::type_def(), u128::type_def());
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(transparent)]
+	struct Wrapper<T> {
+		inner: Option<T>,
+		#[metadata(skip)]
+		cache: core::marker::PhantomData<T>,
+	}
+
+	assert_eq!(<Wrapper<bool>>::type_id(), <Option<bool>>::type_id());
+	assert_eq!(<Wrapper<bool>>::type_def(), <Option<bool>>::type_def());
+}
+
+#[test]
+fn namespace_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(namespace = "my_crate::api")]
+	struct S;
+
+	let type_id = TypeIdCustom::new(
+		"S",
+		Namespace::new(vec!["my_crate", "api"]).unwrap(),
+		tuple_meta_type!(),
+	);
+	assert_type_id!(S, type_id);
+}
+
+#[test]
+fn remote_derive() {
+	mod other_crate {
+		pub struct Thing {
+			pub a: u8,
+		}
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(remote = "other_crate::Thing")]
+	struct ThingDef {
+		a: u8,
+	}
+
+	let type_id = TypeIdCustom::new(
+		"Thing",
+		Namespace::new(vec!["other_crate"]).unwrap(),
+		tuple_meta_type!(),
+	);
+	assert_type_id!(ThingDef, type_id);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S {
+		#[metadata(with = "ThingDef")]
+		thing: other_crate::Thing,
+	}
+
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<ThingDef>("thing")]).into();
+	assert_eq!(S::type_def(), type_def);
+}
+
+#[test]
+fn meta_type_with_derive() {
+	mod other_crate {
+		pub struct DateTime(pub u64);
+	}
+
+	fn timestamp() -> MetaType {
+		MetaType::new::<u64>()
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S {
+		#[metadata(meta_type_with = "timestamp")]
+		at: other_crate::DateTime,
+		#[metadata(meta_type_with = "timestamp", compact)]
+		until: other_crate::DateTime,
+	}
+
+	let type_def = TypeDefStruct::new(vec![
+		NamedField::of::<u64>("at"),
+		NamedField::of::<u64>("until").compact(),
+	])
+	.into();
+	assert_eq!(S::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct T(#[metadata(meta_type_with = "timestamp")] other_crate::DateTime);
+
+	let type_def = TypeDefTupleStruct::new(vec![UnnamedField::of::<u64>()]).into();
+	assert_eq!(T::type_def(), type_def);
+}
+
+#[test]
+fn recursive_types_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Node {
+		next: Option<Box<Node>>,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Tree {
+		forest: Forest,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum Forest {
+		Empty,
+		Trees(Vec<Tree>),
+	}
+
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<Option<Box<Node>>>("next")]).into();
+	assert_eq!(Node::type_def(), type_def);
+
+	let mut registry = Registry::new();
+	registry.register_type(&Node::meta_type());
+	// `Node`, `Option<Box<Node>>` and `Box<Node>`.
+	assert_eq!(registry.sequence(), 3);
+	registry.register_type(&Tree::meta_type());
+	// `Tree`, `Forest`, `Vec<Tree>` and `[Tree]`.
+	assert_eq!(registry.sequence(), 7);
+}
+
+#[test]
+fn variant_index_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E {
+		#[metadata(index = 5)]
+		A(bool),
+		B {
+			b: u8,
+		},
+		#[metadata(index = 0)]
+		C,
+	}
+
+	let type_def = TypeDefEnum::new(vec![
+		EnumVariantTupleStruct::new("A", vec![UnnamedField::of::<bool>()])
+			.with_index(5)
+			.into(),
+		EnumVariantStruct::new("B", vec![NamedField::of::<u8>("b")]).into(),
+		EnumVariantUnit::new("C").with_index(0).into(),
+	])
+	.into();
+	assert_eq!(E::type_def(), type_def);
+}
+
+#[test]
+fn compact_fields_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S {
+		#[metadata(compact)]
+		a: u64,
+		b: u8,
+	}
+
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<u64>("a").compact(), NamedField::of::<u8>("b")]).into();
+	assert_eq!(S::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct T(bool, #[metadata(compact)] u128);
+
+	let type_def =
+		TypeDefTupleStruct::new(vec![UnnamedField::of::<bool>(), UnnamedField::of::<u128>().compact()]).into();
+	assert_eq!(T::type_def(), type_def);
+}
+
+#[test]
+fn phantom_data_derive() {
+	// Does not implement `Metadata`.
+	struct NoMetadata;
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Foo<T> {
+		_marker: core::marker::PhantomData<T>,
+		x: u32,
+	}
+
+	fn assert_metadata<T: Metadata>() {}
+	assert_metadata::<Foo<NoMetadata>>();
+
+	// Phantom type parameters are recorded as markers and need not implement `Metadata`.
+	let type_id = TypeIdCustom::new(
+		"Foo",
+		Namespace::new(vec!["derive"]).unwrap(),
+		tuple_meta_type!(core::marker::PhantomData<()>),
+	);
+	assert_eq!(<Foo<NoMetadata>>::type_id(), type_id.into());
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<u32>("x")]).into();
+	assert_eq!(<Foo<NoMetadata>>::type_def(), type_def);
+
+	let mut registry = Registry::new().strict();
+	assert!(registry.try_register_type(&<Foo<NoMetadata>>::meta_type()).is_ok());
+	assert_eq!(registry.validate(), Ok(()));
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S<T, U> {
+		_marker: core::marker::PhantomData<T>,
+		x: U,
+	}
+
+	// Described type parameters are still recorded by their types.
+	let type_id = TypeIdCustom::new(
+		"S",
+		Namespace::new(vec!["derive"]).unwrap(),
+		tuple_meta_type!(core::marker::PhantomData<()>, u32),
+	);
+	assert_eq!(<S<NoMetadata, u32>>::type_id(), type_id.into());
+	assert_ne!(<S<NoMetadata, u32>>::type_id(), <S<NoMetadata, u16>>::type_id());
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E<T> {
+		A(core::marker::PhantomData<T>),
+		B(T),
+	}
+
+	let type_id = TypeIdCustom::new("E", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!(bool));
+	assert_eq!(<E<bool>>::type_id(), type_id.into());
+
+	let type_def = TypeDefEnum::new(vec![
+		EnumVariantTupleStruct::new("A", vec![]).into(),
+		EnumVariantTupleStruct::new("B", vec![UnnamedField::of::<bool>()]).into(),
+	])
+	.into();
+	assert_eq!(<E<bool>>::type_def(), type_def);
+}
+
+#[test]
+fn phantom_data_registry() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Marker<T> {
+		_marker: core::marker::PhantomData<T>,
+	}
+
+	let mut expected = Registry::new();
+	let symbol = expected.register_type(&u32::meta_type());
+	let expected = expected.expand(&symbol);
+
+	// The phantom type parameter is recorded as a marker rather than by its type.
+	let mut registry = Registry::new();
+	registry.register_type(&<Marker<u32>>::meta_type());
+	let symbol = registry.register_type(&u32::meta_type());
+	assert_eq!(registry.expand(&symbol), expected);
+
+	let mut registry = Registry::new();
+	let symbol = registry.register_type(&u32::meta_type());
+	registry.register_type(&<Marker<u32>>::meta_type());
+	assert_eq!(registry.expand(&symbol), expected);
+}
+
+mod facade {
+	pub use type_metadata as metadata;
+}
+
+#[test]
+fn crate_path_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(crate = "crate::facade::metadata")]
+	struct S {
+		a: bool,
+	}
+
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<bool>("a")]).into();
+	assert_eq!(S::type_def(), type_def);
+}
+
+#[cfg(feature = "repr-transparent")]
+#[test]
+fn repr_transparent_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[repr(transparent)]
+	struct Balance(u128);
+
+	assert_eq!(Balance::type_id(), u128::type_id());
+	assert_eq!(Balance::type_def(), u128::type_def());
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[repr(transparent)]
+	#[metadata(rename = "Nonce")]
+	struct Wrapper(u64);
+
+	assert_type_id!(
+		Wrapper,
+		TypeIdCustom::new("Nonce", Namespace::new(vec!["derive"]).unwrap(), vec![])
+	);
+}
+
+#[test]
+fn where_clause_derive() {
+	trait Trait {
+		type Assoc;
+	}
+
+	impl Trait for bool {
+		type Assoc = u8;
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S<T>
+	where
+		T: Trait,
+		T::Assoc: Clone,
+	{
+		a: T,
+	}
+
+	assert_type_id!(
+		S<bool>,
+		TypeIdCustom::new("S", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!(bool))
+	);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(bound = "T: Metadata + 'static")]
+	struct B<T: Trait>(T)
+	where
+		T::Assoc: Clone;
+
+	let type_def = TypeDefTupleStruct::new(vec![UnnamedField::of::<bool>()]).into();
+	assert_eq!(<B<bool>>::type_def(), type_def);
+}
+
+#[test]
+fn cfg_fields_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S {
+		a: bool,
+		#[cfg(any())]
+		b: u8,
+		#[cfg(test)]
+		c: u16,
+	}
+
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<bool>("a"), NamedField::of::<u16>("c")]).into();
+	assert_eq!(S::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E {
+		A(#[cfg(any())] u8, bool),
+		#[cfg(any())]
+		B,
+		C,
+	}
+
+	let type_def = TypeDefEnum::new(vec![
+		EnumVariantTupleStruct::new("A", vec![UnnamedField::of::<bool>()]).into(),
+		EnumVariantUnit::new("C").into(),
+	])
+	.into();
+	assert_eq!(E::type_def(), type_def);
+}
+
+#[test]
+fn separate_derives() {
+	#[allow(unused)]
+	#[derive(HasTypeId)]
+	struct Handle(u32);
+
+	impl HasTypeDef for Handle {
+		fn type_def() -> TypeDef {
+			u32::type_def()
+		}
+	}
+
+	assert_type_id!(
+		Handle,
+		TypeIdCustom::new("Handle", Namespace::new(vec!["derive"]).unwrap(), vec![])
+	);
+	assert_eq!(Handle::type_def(), u32::type_def());
+
+	#[allow(unused)]
+	#[derive(HasTypeDef)]
+	struct Id(u64);
+
+	impl HasTypeId for Id {
+		fn type_id() -> TypeId {
+			u64::type_id()
+		}
+	}
+
+	assert_eq!(Id::meta_type().type_id(), u64::type_id());
+	assert_eq!(
+		Id::type_def(),
+		TypeDefTupleStruct::new(vec![UnnamedField::of::<u64>()]).into()
+	);
+}
+
+#[test]
+fn deprecated_derive() {
+	#[allow(unused, deprecated)]
+	#[derive(Metadata)]
+	#[deprecated(since = "0.2.0", note = "use `T` instead")]
+	struct S {
+		#[deprecated]
+		a: u8,
+		#[metadata(deprecated = "superseded by `c`")]
+		b: u16,
+		c: u32,
+	}
+
+	#[allow(deprecated)]
+	let actual = S::type_def();
+	let type_def = TypeDefStruct::new(vec![
+		NamedField::of::<u8>("a").with_deprecated(""),
+		NamedField::of::<u16>("b").with_deprecated("superseded by `c`"),
+		NamedField::of::<u32>("c"),
+	])
+	.with_deprecated("use `T` instead")
+	.into();
+	assert_eq!(actual, type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E {
+		#[deprecated = "no longer emitted"]
+		A,
+		B(bool),
+	}
+
+	let type_def = TypeDefEnum::new(vec![
+		EnumVariantUnit::new("A").with_deprecated("no longer emitted").into(),
+		EnumVariantTupleStruct::new("B", vec![UnnamedField::of::<bool>()]).into(),
+	])
+	.into();
+	assert_eq!(E::type_def(), type_def);
+}
+
+#[test]
+fn skip_variants_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E {
+		A(bool),
+		#[metadata(skip)]
+		B(u8),
+		C,
+	}
+
+	let type_def = TypeDefEnum::new(vec![
+		EnumVariantTupleStruct::new("A", vec![UnnamedField::of::<bool>()]).into(),
+		EnumVariantUnit::new("C").into(),
+	])
+	.into();
+	assert_eq!(E::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum C {
+		A,
+		#[metadata(skip)]
+		B,
+		C,
+	}
+
+	// Skipped variants still count towards the discriminants of the following variants.
+	let type_def =
+		TypeDefClikeEnum::new(vec![ClikeEnumVariant::new("A", 0u64), ClikeEnumVariant::new("C", 2u64)]).into();
+	assert_eq!(C::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum D {
+		A = 5,
+		#[metadata(skip)]
+		B,
+		C,
+	}
+
+	let type_def =
+		TypeDefClikeEnum::new(vec![ClikeEnumVariant::new("A", 5u64), ClikeEnumVariant::new("C", 7u64)]).into();
+	assert_eq!(D::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum S {
+		#[metadata(skip)]
+		A,
+		B,
+	}
+
+	let type_def = TypeDefClikeEnum::new(vec![ClikeEnumVariant::new("B", 1u64)]).into();
+	assert_eq!(S::type_def(), type_def);
+}
+
+#[test]
+fn lossy_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Skipped {
+		#[metadata(skip)]
+		cache: u8,
+		x: u32,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Marker<T> {
+		_marker: core::marker::PhantomData<T>,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E {
+		A,
+		#[metadata(skip)]
+		B(u8),
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(transparent)]
+	struct Size(usize);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Pointer {
+		#[metadata(opaque)]
+		ptr: *const u8,
+	}
+
+	assert_eq!(u32::lossy(), None);
+	assert_eq!(Skipped::lossy(), Some("skips fields"));
+	// Markers encode to nothing and are thus omitted losslessly.
+	assert_eq!(<Marker<u8>>::lossy(), None);
+	assert!(Registry::new()
+		.strict()
+		.try_register_type(&<Marker<u8>>::meta_type())
+		.is_ok());
+	assert_eq!(E::lossy(), Some("skips variants"));
+	assert_eq!(Size::lossy(), usize::lossy());
+	assert_eq!(Pointer::lossy(), Some("describes fields as opaque types"));
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Account {
+		balance: usize,
+	}
+
+	// Strict registries refuse all of them, including types containing them.
+	let mut registry = Registry::new().strict();
+	for (ty, type_id, reason) in vec![
+		(Skipped::meta_type(), Skipped::type_id(), "skips fields"),
+		(E::meta_type(), E::type_id(), "skips variants"),
+		(
+			Pointer::meta_type(),
+			Pointer::type_id(),
+			"describes fields as opaque types",
+		),
+		(
+			Account::meta_type(),
+			usize::type_id(),
+			"pointer-sized integers are described as 64-bit integers",
+		),
+	] {
+		assert_eq!(
+			registry.try_register_type(&ty),
+			Err(RegisterError::Lossy { type_id, reason })
+		);
+	}
+	assert_eq!(registry.types().count(), 0);
+}
+
+#[test]
+fn default_type_params_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Foo<T = u32> {
+		x: T,
+	}
+
+	let type_id = TypeIdCustom::new("Foo", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!(u32));
+	assert_type_id!(Foo, type_id);
+
+	let type_id = TypeIdCustom::new("Foo", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!(bool));
+	assert_type_id!(Foo<bool>, type_id);
+
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<bool>("x")]).into();
+	assert_eq!(<Foo<bool>>::type_def(), type_def);
+}
+
+#[test]
+fn associated_types_derive() {
+	trait Trait {
+		type Assoc;
+		type Other;
+	}
+
+	impl Trait for bool {
+		type Assoc = u8;
+		type Other = u16;
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Foo<T: Trait> {
+		a: T::Assoc,
+		b: Vec<<T as Trait>::Other>,
+	}
+
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<u8>("a"), NamedField::of::<Vec<u16>>("b")]).into();
+	assert_eq!(<Foo<bool>>::type_def(), type_def);
+}
+
+#[test]
+fn repr_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[repr(u16)]
+	enum E {
+		A = 1,
+		B = 300,
+	}
+
+	let type_def = TypeDefClikeEnum::new(vec![
+		ClikeEnumVariant::new("A", 1u64),
+		ClikeEnumVariant::new("B", 300u64),
+	])
+	.with_discriminant_type(MetaType::new::<u16>())
+	.into();
+	assert_eq!(E::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[repr(i8)]
+	enum S {
+		A,
+	}
+
+	let type_def = TypeDefClikeEnum::new(vec![ClikeEnumVariant::new("A", 0u64)])
+		.with_discriminant_type(MetaType::new::<i8>())
+		.into();
+	assert_eq!(S::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[repr(i8)]
+	enum N {
+		A = -2,
+		B = -1,
+		C = -(1 << 2) + 8,
+	}
+
+	// Negative discriminants are recorded in two's complement.
+	let type_def = TypeDefClikeEnum::new(vec![
+		ClikeEnumVariant::new("A", -2i64 as u64),
+		ClikeEnumVariant::new("B", -1i64 as u64),
+		ClikeEnumVariant::new("C", 4u64),
+	])
+	.with_discriminant_type(MetaType::new::<i8>())
+	.into();
+	assert_eq!(N::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[repr(usize)]
+	enum U {
+		A,
+	}
+
+	let type_def = TypeDefClikeEnum::new(vec![ClikeEnumVariant::new("A", 0u64)]).into();
+	assert_eq!(U::type_def(), type_def);
+}
+
+#[test]
+fn field_type_bounds_derive() {
+	struct NoMetadata;
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Skipped<T> {
+		#[metadata(skip)]
+		t: T,
+		x: u8,
+	}
+
+	// Type parameters of skipped fields are recorded as markers by the type identifier,
+	// so neither the identifier nor the definition requires them to implement `Metadata`.
+	let type_id = TypeIdCustom::new(
+		"Skipped",
+		Namespace::new(vec!["derive"]).unwrap(),
+		tuple_meta_type!(core::marker::PhantomData<()>),
+	);
+	assert_type_id!(Skipped<NoMetadata>, type_id);
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<u8>("x")]).into();
+	assert_eq!(<Skipped<NoMetadata> as HasTypeDef>::type_def(), type_def);
+
+	// The combined derive thus does not require them to implement `Metadata` either.
+	fn assert_metadata<T: Metadata>() {}
+	assert_metadata::<Skipped<NoMetadata>>();
+	let mut registry = Registry::new();
+	assert!(registry.try_register_type(&<Skipped<NoMetadata>>::meta_type()).is_ok());
+	assert_eq!(registry.validate(), Ok(()));
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(transparent)]
+	struct Handle<T> {
+		id: u32,
+		_marker: core::marker::PhantomData<T>,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Handles<T> {
+		handles: Vec<Handle<T>>,
+	}
+
+	// Transparent types are identified by their field type, which does not require `T: Metadata`.
+	assert_metadata::<Handle<NoMetadata>>();
+	assert_type_id!(Handle<NoMetadata>, u32::type_id());
+
+	// Only the field types are bounded, which do not require `T: Metadata`.
+	// The type identifier still records `T` since it appears in a described field.
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<Vec<Handle<NoMetadata>>>("handles")]).into();
+	assert_eq!(<Handles<NoMetadata> as HasTypeDef>::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Buf<T, const N: usize> {
+		data: [T; N],
+	}
+
+	let type_def = TypeDefStruct::new(vec![NamedField::of::<[u8; 4]>("data")]).into();
+	assert_eq!(<Buf<u8, 4>>::type_def(), type_def);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct List<T> {
+		value: T,
+		next: Option<Box<List<T>>>,
+	}
+
+	let type_def = TypeDefStruct::new(vec![
+		NamedField::of::<bool>("value"),
+		NamedField::of::<Option<Box<List<bool>>>>("next"),
+	])
+	.into();
+	assert_eq!(<List<bool>>::type_def(), type_def);
+}
+
+#[test]
+fn union_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	union U<T: Copy> {
+		u: T,
+	}
+
+	let type_id = TypeIdCustom::new("U", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!(bool));
+	assert_type_id!(U<bool>, type_id);
+
+	let type_def = TypeDefUnion::new(vec![NamedField::new("u", bool::meta_type())]).into();
+	assert_eq!(<U<bool>>::type_def(), type_def);
+
+	// Non-`Copy` fields of unions are wrapped in `ManuallyDrop`.
+	#[allow(unused)]
+	#[derive(Metadata)]
+	union Ffi {
+		raw: u64,
+		boxed: core::mem::ManuallyDrop<Box<u8>>,
+	}
+
+	let type_def = TypeDefUnion::new(vec![
+		NamedField::of::<u64>("raw"),
+		NamedField::of::<core::mem::ManuallyDrop<Box<u8>>>("boxed"),
+	])
+	.into();
+	assert_eq!(Ffi::type_def(), type_def);
+	assert_eq!(<core::mem::ManuallyDrop<Box<u8>>>::type_id(), <Box<u8>>::type_id());
 }
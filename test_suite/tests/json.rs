@@ -24,7 +24,12 @@ use alloc::{vec, vec::Vec};
 
 use serde::Serialize;
 use serde_json::json;
-use type_metadata::{form::CompactForm, IntoCompact as _, Metadata, Registry, TypeDef, TypeId};
+use type_metadata::{
+	diff::AcceptedChanges,
+	form::{CompactForm, PortableForm},
+	validate::ValidationError,
+	IntoCompact as _, Metadata, Registry, RegistryReadOnly, TypeDef, TypeId,
+};
 
 #[derive(Serialize)]
 struct TypeIdDef {
@@ -38,8 +43,8 @@ where
 {
 	let mut registry = Registry::new();
 
-	let type_id = T::type_id().into_compact(&mut registry);
-	let type_def = T::type_def().into_compact(&mut registry);
+	let type_id = T::type_id().into_compact(&mut registry).unwrap();
+	let type_def = T::type_def().into_compact(&mut registry).unwrap();
 	let id_def = TypeIdDef {
 		id: type_id,
 		def: type_def,
@@ -126,12 +131,35 @@ fn test_clike_enum() {
 			"clike_enum.variants": [
 				{ "name": 3, "discriminant": 0, },
 				{ "name": 4, "discriminant": 42, },
-				{ "name": 5, "discriminant": 2, },
+				{ "name": 5, "discriminant": 43, },
 			]
 		},
 	}));
 }
 
+#[test]
+fn test_clike_enum_with_repr() {
+	#[derive(Metadata)]
+	#[repr(u8)]
+	enum ClikeEnum {
+		A,
+	}
+
+	assert_json_for_type::<ClikeEnum>(json!({
+		"id": {
+			"custom.name": 1,
+			"custom.namespace": [2],
+			"custom.params": [],
+		},
+		"def": {
+			"clike_enum.variants": [
+				{ "name": 3, "discriminant": 0, },
+			],
+			"clike_enum.discriminant_type": 1,
+		},
+	}));
+}
+
 #[test]
 fn test_enum() {
 	#[derive(Metadata)]
@@ -192,6 +220,99 @@ fn test_union() {
 	}));
 }
 
+#[test]
+fn test_const_generics() {
+	#[derive(Metadata)]
+	#[metadata(bound = "[u8; N]: Metadata")]
+	struct Buf<const N: usize> {
+		data: [u8; N],
+	}
+
+	assert_json_for_type::<Buf<32>>(json!({
+		"id": {
+			"custom.name": 1,
+			"custom.namespace": [2],
+			"custom.params": [],
+			"custom.const_params": [{ "unsigned": 32 }],
+		},
+		"def": {
+			"struct.fields": [
+				{ "name": 3, "type": 1, },
+			]
+		},
+	}));
+}
+
+#[test]
+fn test_compact_fields() {
+	#[derive(Metadata)]
+	struct Struct {
+		#[metadata(compact)]
+		a: u64,
+		b: bool,
+	}
+
+	assert_json_for_type::<Struct>(json!({
+		"id": {
+			"custom.name": 1,
+			"custom.namespace": [2],
+			"custom.params": [],
+		},
+		"def": {
+			"struct.fields": [
+				{ "name": 3, "type": 1, "compact": true, },
+				{ "name": 4, "type": 2, },
+			]
+		},
+	}));
+
+	#[derive(Metadata)]
+	struct TupleStruct(#[metadata(compact)] u64, bool);
+
+	assert_json_for_type::<TupleStruct>(json!({
+		"id": {
+			"custom.name": 1,
+			"custom.namespace": [2],
+			"custom.params": [],
+		},
+		"def": {
+			"tuple_struct.types": [{ "type": 1, "compact": true, }, 2]
+		},
+	}));
+}
+
+#[cfg(feature = "floats")]
+#[test]
+fn test_floats() {
+	assert_json_for_type::<f32>(json!({ "id": "f32", "def": "builtin" }));
+	assert_json_for_type::<f64>(json!({ "id": "f64", "def": "builtin" }));
+}
+
+#[test]
+fn test_deprecated() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[metadata(deprecated = "use `Other` instead")]
+	struct Struct {
+		#[metadata(deprecated = "unused")]
+		a: bool,
+	}
+
+	assert_json_for_type::<Struct>(json!({
+		"id": {
+			"custom.name": 1,
+			"custom.namespace": [2],
+			"custom.params": [],
+		},
+		"def": {
+			"struct.fields": [
+				{ "name": 3, "type": 1, "deprecated": 4, },
+			],
+			"struct.deprecated": 5,
+		},
+	}));
+}
+
 #[test]
 fn test_registry() {
 	let mut registry = Registry::new();
@@ -415,3 +536,324 @@ fn test_registry() {
 
 	assert_eq!(serde_json::to_value(registry).unwrap(), expected_json,);
 }
+
+#[test]
+fn test_registry_capabilities() {
+	let mut registry = Registry::new();
+
+	registry.tag_type(&u64::meta_type(), "timestamp").unwrap();
+	registry.tag_type(&<[u8; 32]>::meta_type(), "encode-as-hex").unwrap();
+
+	let expected_json = json!({
+		"strings": [],
+		"types": [
+			{ "id": "u64", "def": "builtin" },
+			{ "id": { "array.len": 32, "array.type": 3 }, "def": "builtin" },
+			{ "id": "u8", "def": "builtin" },
+		],
+		"capabilities": {
+			"encode-as-hex": [2],
+			"timestamp": [1],
+		},
+	});
+
+	assert_eq!(serde_json::to_value(registry).unwrap(), expected_json);
+}
+
+#[test]
+fn test_registry_deterministic_order() {
+	#[derive(Metadata)]
+	struct Inner {
+		b: u32,
+		a: Option<bool>,
+	}
+	#[derive(Metadata)]
+	struct Outer {
+		inner: Inner,
+		z: u8,
+	}
+
+	fn build_registry() -> Registry {
+		let mut registry = Registry::new();
+		registry.register_type(&Outer::meta_type());
+		registry.tag_type(&u8::meta_type(), "timestamp").unwrap();
+		registry.tag_type(&u32::meta_type(), "encode-as-hex").unwrap();
+		registry
+	}
+
+	let serialized = serde_json::to_string(&build_registry()).unwrap();
+	assert_eq!(serde_json::to_string(&build_registry()).unwrap(), serialized);
+
+	// Strings and types are serialized in the order of their first registration.
+	let registry = serde_json::to_value(build_registry()).unwrap();
+	assert_eq!(
+		registry["strings"],
+		json!(["Outer", "json", "inner", "Inner", "b", "a", "Option", "None", "Some", "z"])
+	);
+	let type_ids = registry["types"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|ty| ty["id"].clone())
+		.collect::<Vec<_>>();
+	assert_eq!(type_ids[2], json!("u32"));
+	assert_eq!(type_ids[5], json!("u8"));
+	assert_eq!(
+		registry["capabilities"].as_object().unwrap().keys().collect::<Vec<_>>(),
+		vec!["encode-as-hex", "timestamp"]
+	);
+}
+
+#[test]
+fn test_registry_deserialize() {
+	#[derive(Metadata)]
+	struct Unit;
+	#[derive(Metadata)]
+	struct Tuple(#[metadata(compact)] u64, [u8; 4]);
+	#[derive(Metadata)]
+	#[repr(u8)]
+	enum ClikeEnum {
+		A,
+		B = 10,
+	}
+	#[derive(Metadata)]
+	enum Enum {
+		Unit,
+		Struct {
+			slice: &'static [u16],
+			tuple: (bool, char),
+			single: (u32,),
+		},
+		TupleStruct(Tuple),
+	}
+	#[derive(Metadata)]
+	struct Outer {
+		unit: Unit,
+		clike: ClikeEnum,
+		#[metadata(compact)]
+		value: u128,
+		nested: Option<Enum>,
+	}
+
+	let mut registry = Registry::new();
+	registry.register_type(&Outer::meta_type());
+	registry.tag_type(&u128::meta_type(), "balance").unwrap();
+
+	let serialized = serde_json::to_value(&registry).unwrap();
+	let loaded: RegistryReadOnly = serde_json::from_value(serialized.clone()).unwrap();
+	assert_eq!(serde_json::to_value(&loaded).unwrap(), serialized);
+	assert_eq!(loaded.strings(), registry.strings());
+	assert_eq!(loaded.hash(), registry.hash());
+
+	let u128_symbol = registry.register_type(&u128::meta_type());
+	assert_eq!(loaded.types_with_capability("balance"), &[u128_symbol]);
+	assert_eq!(loaded.types().len(), registry.types().count());
+	assert_eq!(loaded.type_id_of(0), None);
+	for (symbol, type_id, type_def) in registry.types() {
+		assert_eq!(loaded.resolve_type_id(&symbol), Some(type_id));
+		assert_eq!(loaded.resolve(&symbol), Some(type_def));
+		assert_eq!(loaded.type_def_of(symbol.id().get()), Some(type_def));
+	}
+	let outer_symbol = registry.register_type(&Outer::meta_type());
+	let name = match registry.resolve_type_id(&outer_symbol) {
+		Some(TypeId::Custom(custom)) => *custom.name(),
+		_ => panic!("`Outer` must be a custom type"),
+	};
+	assert_eq!(loaded.resolve_string(&name), Some("Outer"));
+}
+
+#[test]
+fn test_registry_validate() {
+	let loaded: RegistryReadOnly = serde_json::from_value(json!({
+		"strings": ["Node", "unused"],
+		"types": [
+			{
+				"id": { "custom.name": 1, "custom.namespace": [], "custom.params": [1] },
+				"def": { "struct.fields": [{ "name": 3, "type": 2 }] },
+			},
+		],
+		"capabilities": { "flag": [5] },
+	}))
+	.unwrap();
+	assert_eq!(
+		loaded.validate(),
+		Err(vec![
+			ValidationError::MissingString { ty: 1, string: 3 },
+			ValidationError::MissingType { ty: 1, referenced: 2 },
+			ValidationError::MissingTaggedType {
+				capability: "flag".into(),
+				ty: 5,
+			},
+			ValidationError::UnusedString { string: 2 },
+			ValidationError::CyclicTypeId { ty: 1 },
+		])
+	);
+
+	let mut registry = Registry::new();
+	registry.register_type(&Option::<Vec<bool>>::meta_type());
+	let loaded: RegistryReadOnly = serde_json::from_value(serde_json::to_value(&registry).unwrap()).unwrap();
+	assert_eq!(loaded.validate(), Ok(()));
+}
+
+#[test]
+fn test_portable_types() {
+	let types: Vec<type_metadata::TypeIdDef<PortableForm>> = serde_json::from_value(json!([
+		{
+			"id": {
+				"custom.name": "Balance",
+				"custom.namespace": ["chain", "balances"],
+				"custom.params": [],
+			},
+			"def": {
+				"struct.fields": [
+					{ "name": "free", "type": 2, "compact": true },
+					{ "name": "reserved", "type": 2 },
+				],
+			},
+		},
+		{
+			"id": "u128",
+			"def": "builtin",
+		},
+	]))
+	.unwrap();
+
+	let registry = RegistryReadOnly::from_portable_types(types.clone());
+	assert_eq!(registry.validate(), Ok(()));
+	assert_eq!(registry.strings(), ["Balance", "chain", "balances", "free", "reserved"]);
+	assert_eq!(
+		serde_json::to_value(&registry).unwrap(),
+		json!({
+			"strings": ["Balance", "chain", "balances", "free", "reserved"],
+			"types": [
+				{
+					"id": {
+						"custom.name": 1,
+						"custom.namespace": [2, 3],
+						"custom.params": [],
+					},
+					"def": {
+						"struct.fields": [
+							{ "name": 4, "type": 2, "compact": true },
+							{ "name": 5, "type": 2 },
+						],
+					},
+				},
+				{
+					"id": "u128",
+					"def": "builtin",
+				},
+			],
+		})
+	);
+	assert_eq!(registry.portable_types(), Some(types));
+}
+
+#[test]
+fn test_registry_expand() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Node {
+		children: Vec<Node>,
+	}
+
+	let mut registry = Registry::new();
+	let node = registry.register_type(&Node::meta_type());
+	let option = registry.register_type(&<Option<Node>>::meta_type());
+	let label = serde_json::to_value(registry.content_ids()[node.id().get() as usize - 1]).unwrap();
+
+	// Recurring types are labelled and referred back to by their labels.
+	let expanded = serde_json::to_value(registry.expand(&option).unwrap()).unwrap();
+	let node = &expanded["id"]["custom.params"][0];
+	assert_eq!(node["$id"], label);
+	assert_eq!(node["id"]["custom.name"], "Node");
+	assert_eq!(
+		node["def"]["struct.fields"][0]["type"]["id"],
+		json!({
+			"custom.name": "Vec",
+			"custom.namespace": [],
+			"custom.params": [{ "$ref": label, "recursive": 2 }],
+		})
+	);
+	// Types that do not recur are not labelled.
+	assert_eq!(node["def"]["struct.fields"][0]["type"].get("$id"), None);
+}
+
+#[test]
+fn test_accepted_changes() {
+	mod old {
+		use type_metadata::Metadata;
+
+		#[derive(Metadata)]
+		#[metadata(namespace = "chain")]
+		pub struct Account {
+			pub nonce: u32,
+			pub free: u64,
+		}
+
+		#[derive(Metadata)]
+		#[metadata(namespace = "chain")]
+		pub enum Event {
+			Transfer(u64),
+		}
+	}
+
+	mod new {
+		use type_metadata::Metadata;
+
+		#[derive(Metadata)]
+		#[metadata(namespace = "chain")]
+		pub struct Account {
+			pub nonce: u64,
+			pub free: u64,
+			pub reserved: u64,
+		}
+
+		#[derive(Metadata)]
+		#[metadata(namespace = "chain")]
+		pub enum Event {
+			Deposit(u64),
+		}
+	}
+
+	let old = type_metadata::tuple_meta_type!(old::Account, old::Event)
+		.into_iter()
+		.collect::<Registry>();
+	let new = type_metadata::tuple_meta_type!(new::Account, new::Event)
+		.into_iter()
+		.collect::<Registry>();
+	let diff = old.diff(&new);
+
+	let accepted: AcceptedChanges = serde_json::from_value(json!({
+		"removed": ["u32"],
+		"changed": [
+			{
+				"name": "chain::Account",
+				"changes": [
+					{ "field_type": { "variant": null, "field": "nonce", "from": "u32", "to": "u64" } },
+					{ "field_added": { "variant": null, "field": "reserved" } },
+				],
+			},
+			{ "name": "chain::Event" },
+		],
+	}))
+	.unwrap();
+	assert!(diff.unexpected(&accepted).is_empty());
+
+	let accepted: AcceptedChanges = serde_json::from_value(json!({
+		"changed": [
+			{
+				"name": "chain::Account",
+				"changes": [{ "field_added": { "variant": null, "field": "reserved" } }],
+			},
+		],
+	}))
+	.unwrap();
+	let unexpected = diff.unexpected(&accepted);
+	assert_eq!(unexpected.removed, vec!["u32"]);
+	assert_eq!(
+		serde_json::to_value(&unexpected.changed[0].changes).unwrap(),
+		json!([{ "field_type": { "variant": null, "field": "nonce", "from": "u32", "to": "u64" } }])
+	);
+	assert_eq!(unexpected.changed[1].name, "chain::Event");
+}
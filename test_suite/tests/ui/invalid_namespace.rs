@@ -0,0 +1,7 @@
+use type_metadata::Metadata;
+
+#[derive(Metadata)]
+#[metadata(namespace = "my_crate::1api")]
+struct S;
+
+fn main() {}